@@ -3,16 +3,14 @@ use quote::{format_ident, quote};
 use regex::Regex;
 use syn::{Error, Expr, ExprLit, Fields, ItemEnum, Lit, Meta, MetaNameValue};
 use syn::spanned::Spanned;
-use crate::typehandler::{deduce_type, FSONaming, FSOValueType};
+use crate::typehandler::{deduce_type, pathed_type, FSONaming, FSOValueType};
 use crate::util::{fso_build_impl_generics, fso_build_where_clause};
 
 pub(crate) fn fso_enum_build_parse(fields: &Fields, default_enum_case_store_in: bool, field_spacing: &String) -> Result<(TokenStream, TokenStream), Error> {
 	let mut field_parsers = quote!();
 	let mut field_spewers = quote!();
 
-	let mut field_number = 0u32;
-	
-	for field in fields{
+	for (field_number, field) in fields.iter().enumerate() {
 		let append_space = if field_number == 0 { quote!(state.append(#field_spacing);) } else { quote!(state.append(", ");) };
 		if let Some(ident) = &field.ident {
 			if default_enum_case_store_in {
@@ -61,7 +59,6 @@ pub(crate) fn fso_enum_build_parse(fields: &Fields, default_enum_case_store_in:
 		else {
 			return Err(Error::new(field.span(), "FSO table enums cannot have unnamed fields."));
 		}
-		field_number += 1;
 	}
 	
 	Ok((field_parsers, field_spewers))
@@ -79,14 +76,36 @@ pub fn fso_table_enum(item_enum: &mut ItemEnum, instancing_req: Vec<TokenStream>
 			state.consume_whitespace(false)
 		};
 	};
+	//Flags are quoted, as in ( "auto reverse" "loop" )
+	let close_quote = if flagset_naming {
+		parser = quote! {
+			#parser
+			let __quoted = state.consume_string("\"").is_ok();
+		};
+		quote! {
+			if __quoted {
+				state.consume_whitespace_inline(&[]);
+				state.consume_string("\"")?;
+			}
+		}
+	}
+	else {
+		quote!()
+	};
 	let mut spewer = quote!();
+	let mut field_arms = quote!();
+	let mut field_mut_arms = quote!();
+	let mut only_field_arms = quote!();
+	let mut only_field_mut_arms = quote!();
+	let mut field_names_arms = quote!();
+	let mut variant_arms = quote!();
+	let mut flag_name_arms = quote!();
 	let mut fail_message = "Expected one of ".to_string();
-	let mut option_nr = 0;
 
 	let mut has_early_out = false;
 	let num_variants = item_enum.variants.len();
 
-	for option in &mut item_enum.variants {
+	for (option_nr, option) in item_enum.variants.iter_mut().enumerate() {
 		let default_enum_case_store_in = option.attrs.iter().find_map(|a| match &a.meta {
 			Meta::Path( path ) if path.is_ident("use_as_default_string") => {
 				if option_nr != num_variants - 1{
@@ -106,7 +125,7 @@ pub fn fso_table_enum(item_enum: &mut ItemEnum, instancing_req: Vec<TokenStream>
 			Meta::NameValue( MetaNameValue { value: Expr::Lit( ExprLit{ lit: Lit::Str(new_name), ..}), .. })
 			if a.meta.path().is_ident("fso_name") => { Some(Ok(new_name.value())) },
 			_ if a.meta.path().is_ident("fso_name") => {
-				return Some(Err(Error::new(a.span(), "Attribute fso_name must have a value!")));
+				Some(Err(Error::new(a.span(), "Attribute fso_name must have a value!")))
 			}
 			_ => {
 				None
@@ -150,18 +169,22 @@ pub fn fso_table_enum(item_enum: &mut ItemEnum, instancing_req: Vec<TokenStream>
 			has_early_out = true;
 			parser = quote! {
 				#parser
-				return Ok( (#struct_name::#rust_name {
+				let __value = #struct_name::#rust_name {
 					#field_parsers
-				}, None));
+				};
+				#close_quote
+				return Ok((__value, None));
 			};
 		}
 		else {
 			parser = quote! {
 				#parser
 				if let Ok(_) = state.consume_string(#fso_name) {
-					return Ok( (#struct_name::#rust_name {
+					let __value = #struct_name::#rust_name {
 						#field_parsers
-					}, None));
+					};
+					#close_quote
+					return Ok((__value, None));
 				}
 			};
 		}
@@ -175,6 +198,58 @@ pub fn fso_table_enum(item_enum: &mut ItemEnum, instancing_req: Vec<TokenStream>
 			}
 		});
 		
+		//Paths lead into the fields of the current variant by their rust name
+		let pathed_fields = option.fields.iter().filter_map(|field| Some((field.ident.as_ref()?, pathed_type(&field.ty)?))).collect::<Vec<_>>();
+		let pathed_names = pathed_fields.iter().fold(quote!(), |input, (name, _)| quote!(#input #name,));
+		let (mut name_arms, mut name_mut_arms) = (quote!(), quote!());
+		for (name, boxed) in &pathed_fields {
+			let name_string = name.to_string();
+			let (access, access_mut) = if *boxed { (quote!(&**#name), quote!(&mut **#name)) } else { (quote!(#name), quote!(#name)) };
+			name_arms = quote!{ #name_arms #name_string => { Some(#access) } };
+			name_mut_arms = quote!{ #name_mut_arms #name_string => { Some(#access_mut) } };
+		}
+		if !pathed_fields.is_empty() {
+			field_arms = quote! {
+				#field_arms
+				#struct_name::#rust_name { #pathed_names .. } => {
+					match __name {
+						#name_arms
+						_ => { None }
+					}
+				}
+			};
+			field_mut_arms = quote! {
+				#field_mut_arms
+				#struct_name::#rust_name { #pathed_names .. } => {
+					match __name {
+						#name_mut_arms
+						_ => { None }
+					}
+				}
+			};
+		}
+		let pathed_strings = pathed_fields.iter().map(|(name, _)| name.to_string());
+		field_names_arms = quote!{ #field_names_arms #struct_name::#rust_name { .. } => { vec![#(#pathed_strings),*] } };
+		let variant_string = rust_name.to_string();
+		variant_arms = quote!{ #variant_arms #struct_name::#rust_name { .. } => { Some(#variant_string) } };
+		flag_name_arms = quote!{ #flag_name_arms #struct_name::#rust_name { .. } => { Some(#fso_name) } };
+		if let ([(name, boxed)], 1) = (pathed_fields.as_slice(), option.fields.len()) {
+			let (access, access_mut) = if *boxed { (quote!(&**#name), quote!(&mut **#name)) } else { (quote!(#name), quote!(#name)) };
+			only_field_arms = quote!{ #only_field_arms #struct_name::#rust_name { #name } => { Some(#access) } };
+			only_field_mut_arms = quote!{ #only_field_mut_arms #struct_name::#rust_name { #name } => { Some(#access_mut) } };
+		}
+
+		//Variants named like keys, i.e. $Rotation:, go on their own line like one
+		let key_line = if fso_name.starts_with(['+', '$', '#']) {
+			let key_indent = if fso_name.starts_with('+') { 1usize } else { 0usize };
+			quote! {
+				state.start_line();
+				state.indent(#key_indent);
+			}
+		}
+		else {
+			quote!()
+		};
 		let prespew = if flagset_naming { quote!(state.append("\"");) } else { quote!() };
 
 		if default_enum_case_store_in {
@@ -184,7 +259,6 @@ pub fn fso_table_enum(item_enum: &mut ItemEnum, instancing_req: Vec<TokenStream>
 					#prespew
 					#field_spewers
 					#prespew
-					state.append(" ");
 				}
 			};
 		}
@@ -192,17 +266,28 @@ pub fn fso_table_enum(item_enum: &mut ItemEnum, instancing_req: Vec<TokenStream>
 			spewer = quote! {
 				#spewer
 				#struct_name::#rust_name { #field_names } => {
+					#key_line
 					#prespew
 					state.append(#fso_name);
 					#field_spewers
 					#prespew
-					state.append(" ");
 				}
 			};
 		}
+	}
 
-		option_nr += 1;
+	let flag_name = if flagset_naming {
+		quote! {
+			fn flag_name(&self) -> Option<&'static str> {
+				match self {
+					#flag_name_arms
+				}
+			}
+		}
 	}
+	else {
+		quote!()
+	};
 
 	fail_message = format!("{}got {{}}.", fail_message);
 	let fail_return = if has_early_out {
@@ -231,6 +316,45 @@ pub fn fso_table_enum(item_enum: &mut ItemEnum, instancing_req: Vec<TokenStream>
 					#spewer
 				}
 			}
+			#[allow(unreachable_patterns)]
+			fn field(&self, __name: &str) -> Option<&dyn fso_tables::FSOPathed> {
+				match self {
+					#field_arms
+					_ => { None }
+				}
+			}
+			#[allow(unreachable_patterns)]
+			fn field_mut(&mut self, __name: &str) -> Option<&mut dyn fso_tables::FSOPathed> {
+				match self {
+					#field_mut_arms
+					_ => { None }
+				}
+			}
+			fn field_names(&self) -> Vec<&'static str> {
+				match self {
+					#field_names_arms
+				}
+			}
+			fn variant(&self) -> Option<&'static str> {
+				match self {
+					#variant_arms
+				}
+			}
+			#flag_name
+			#[allow(unreachable_patterns)]
+			fn only_field(&self) -> Option<&dyn fso_tables::FSOPathed> {
+				match self {
+					#only_field_arms
+					_ => { None }
+				}
+			}
+			#[allow(unreachable_patterns)]
+			fn only_field_mut(&mut self) -> Option<&mut dyn fso_tables::FSOPathed> {
+				match self {
+					#only_field_mut_arms
+					_ => { None }
+				}
+			}
 		}
 	},
 	quote! { 
@@ -242,7 +366,12 @@ pub fn fso_table_enum(item_enum: &mut ItemEnum, instancing_req: Vec<TokenStream>
 			pub fn spew(&self) -> String {
 				let mut parser = fso_tables::FSOTableBuilder::default();
 				fso_tables::FSOTable::spew(self, &mut parser);
-				fso_tables::FSOBuilder::spew(parser)
+				fso_tables::FSOStringBuilder::spew(parser)
+			}
+			pub fn spew_formatted(&self, formatting: fso_tables::FSOFormatting) -> String {
+				let mut parser = fso_tables::FSOTableBuilder::new(formatting);
+				fso_tables::FSOTable::spew(self, &mut parser);
+				fso_tables::FSOStringBuilder::spew(parser)
 			}
 		}
 	}))
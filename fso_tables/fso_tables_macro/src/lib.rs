@@ -27,6 +27,7 @@ pub fn fso_table(args: proc_macro::TokenStream, input: proc_macro::TokenStream)
 	let mut table_suffix :Option<String> = None;
 	
 	let mut field_spacing :Option<String> = None;
+	let mut constructor :Option<String> = None;
 
 	let mut flagset_naming = false;
 	let mut inline = false;
@@ -91,6 +92,10 @@ pub fn fso_table(args: proc_macro::TokenStream, input: proc_macro::TokenStream)
 			toplevel = true;
 			Ok(())
 		}
+		else if meta.path.is_ident("constructor") {
+			constructor = Some(meta.value()?.parse::<LitStr>()?.value());
+			Ok(())
+		}
 		else if meta.path.is_ident("enum_field_spacing") {
 			field_spacing = Some(meta.value()?.parse::<LitStr>()?.value());
 			Ok(())
@@ -103,7 +108,7 @@ pub fn fso_table(args: proc_macro::TokenStream, input: proc_macro::TokenStream)
 
 	let result = match &mut item {
 		Item::Struct(item_struct) => {
-			fso_table_struct(item_struct, required_parser_traits, required_lifetimes, table_prefix, table_suffix, prefix, suffix, inline)
+			fso_table_struct(item_struct, required_parser_traits, required_lifetimes, table_prefix, table_suffix, prefix, suffix, inline, constructor.unwrap_or("new".to_string()))
 		}
 		Item::Enum(item_enum) => {
 			fso_table_enum(item_enum, required_parser_traits, required_lifetimes, prefix.unwrap_or("".to_string()), suffix.unwrap_or("".to_string()), flagset_naming, field_spacing.unwrap_or(" ".to_string()))
@@ -119,10 +124,10 @@ pub fn fso_table(args: proc_macro::TokenStream, input: proc_macro::TokenStream)
 	};
 	let top_level_impl = if toplevel { top_level_impl } else { quote!() };
 	
-	return quote! {
+	quote! {
 		#[derive(Clone)]
         #item
         #post_item_out
 		#top_level_impl
-    }.into();
+    }.into()
 }
\ No newline at end of file
@@ -4,7 +4,7 @@ use regex::Regex;
 use syn::{Error, Expr, ExprLit, Field, ItemStruct, Lit, Meta, MetaNameValue, Type};
 use syn::parse::Parser;
 use syn::spanned::Spanned;
-use crate::typehandler::{deduce_type, FSONaming, FSOValueType};
+use crate::typehandler::{deduce_type, pathed_type, FSONaming, FSOValueType};
 use crate::util::{fso_build_impl_generics, fso_build_where_clause};
 
 pub(crate) struct TableField {
@@ -13,13 +13,77 @@ pub(crate) struct TableField {
 	rust_token: Ident,
 	rust_type: Type,
 	rust_span: Span,
-	field_number: usize
+	field_number: usize,
+	unknown: bool
 }
 
-pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) -> Result<(TokenStream, TokenStream, TokenStream), Error> {
+//Parses the options a struct does not know into its unknown slot, in front of the field with the given number.
+//Keys of that field and the ones after it are never taken, so that options out of place don't end the struct
+fn fso_struct_parse_unknown(fields: &[TableField], slot: &TableField, inner: &Type, field_num: usize) -> TokenStream {
+	let slot_name = &slot.rust_token;
+	let keys = fields.iter().filter(|field| field.field_number >= field_num).filter_map(|field| match &field.fso_name {
+		FSONaming::Named { fso_name, .. } | FSONaming::ExistenceIsBool { fso_name } => { Some(fso_name.as_str()) }
+		_ => { None }
+	});
+	quote! {
+		while state.get().starts_with(['$', '+']) && ![#(#keys),*].iter().any(|__key: &&str| state.get().get(..__key.len()).is_some_and(|__start| __start.eq_ignore_ascii_case(__key))) {
+			match <#inner as fso_tables::FSOTable>::parse(state, None) {
+				Ok((__option, _)) => {
+					#slot_name.push(__option);
+					__unknown_before.push(#field_num);
+					let (__comment_after, __version_string_after) = state.consume_whitespace(false);
+					__comment = match (__comment.take(), __comment_after) {
+						(Some(before), Some(after)) => { Some(format!("{}\n{}", before, after)) }
+						(before, after) => { before.or(after) }
+					};
+					__version_string = __version_string_after.or(__version_string.take());
+				}
+				Err(_) => { break; }
+			}
+		}
+	}
+}
+
+//Spews the options of the unknown slot that were parsed in front of the field with the given number.
+//The slot itself also spews those added since parsing
+fn fso_struct_spew_unknown(slot: &TableField, field_num: usize) -> TokenStream {
+	let slot_name = &slot.rust_token;
+	let spew_here = if slot.field_number == field_num {
+		quote!(self.__unknown_before.get(__index).is_none_or(|__before| *__before == #field_num))
+	}
+	else {
+		quote!(self.__unknown_before.get(__index) == Some(&#field_num))
+	};
+	quote! {
+		for (__index, __option) in self.#slot_name.iter().enumerate() {
+			if #spew_here {
+				state.start_line();
+				state.indent(0);
+				fso_tables::FSOTable::spew(__option, state);
+			}
+		}
+	}
+}
+
+//Records what was parsed since the previous field as the source of this one, for round trip spewing.
+//Whitespace after it is left to whatever follows
+fn fso_struct_record_source(field_num: usize) -> TokenStream {
+	quote! {
+		if __keep_source {
+			let __parsed = state.source(__source_start..state.position()).trim_end();
+			__source_texts[#field_num] = Some(__parsed.to_string());
+			__source_start += __parsed.len();
+		}
+	}
+}
+
+//A field's number, its spewer and whether it starts its own line
+type FieldSpewer = (usize, TokenStream, bool);
+
+pub(crate) fn fso_struct_build_parse(fields: &[TableField], inline: bool) -> Result<(TokenStream, TokenStream, Vec<FieldSpewer>), Error> {
 	let mut parse = quote! ();
 	let mut fill = TokenStream::new();
-	let mut spew = TokenStream::new();
+	let mut spew = Vec::new();
 	
 	let or_else_fail = quote! {.map_err(|mut err: fso_tables::FSOParsingError| {
 		err.comments = __comment.clone();
@@ -27,6 +91,29 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 		err
 	})?};
 
+	//The slot for unknown options is a Vec, filled in front of whichever field they turn up at
+	let unknown_slot = match fields.iter().filter(|field| field.unknown).collect::<Vec<_>>().as_slice() {
+		[] => { None }
+		[slot] => {
+			if inline {
+				return Err(Error::new(slot.rust_span, "Inline tables cannot have a slot for unknown options."));
+			}
+			match deduce_type(&slot.fso_name, &slot.rust_type, &format_ident!("__to_spew"), &format_ident!("None"))?.0 {
+				FSOValueType::Vector { inner } => {
+					let slot_name = &slot.rust_token;
+					let slot_type = &slot.rust_type;
+					parse = quote! {
+						let mut #slot_name: #slot_type = Vec::new();
+						let mut __unknown_before: Vec<usize> = Vec::new();
+					};
+					Some((*slot, inner))
+				}
+				_ => { return Err(Error::new(slot.rust_span, "The slot for unknown options must be a Vec.")); }
+			}
+		}
+		[_, slot, ..] => { return Err(Error::new(slot.rust_span, "A table can only have one slot for unknown options.")); }
+	};
+
 	for field in fields.iter() {
 		let name = &field.rust_token;
 
@@ -69,8 +156,20 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 		else {
 			quote!()
 		};
+		//Gobbles that look like keys, i.e. "+End Segment", go on their own line like one
 		let spew_gobble = if let Some(gobble) = &field.fso_gobble {
+			let gobble_line = if gobble.starts_with(['+', '$', '#']) {
+				let gobble_indent = if gobble.starts_with('+') { 1usize } else { 0usize };
+				quote! {
+					state.start_line();
+					state.indent(#gobble_indent);
+				}
+			}
+			else {
+				quote!()
+			};
 			quote! {
+				#gobble_line
 				state.append(#gobble);
 				state.append(" ");
 			}
@@ -82,7 +181,7 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 		let spew_comments = quote!{
 			if let Some(comment) = &self.__comments[#field_num] {
 				state.append(comment);
-				state.append("\n");
+				state.newline();
 			}
 			if let Some(version_string) = &self.__version_strings[#field_num] {
 				state.append(version_string);
@@ -98,6 +197,35 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 		};
 		
 		let (value_type, make_type, spew_type) = deduce_type(&field.fso_name, &field.rust_type, &format_ident!("__to_spew"), &format_ident!("None"))?;
+
+		//+ options are indented below the option they belong to, as is everything nested in them and the lines of named multiline lists
+		let key_indent = match &field.fso_name {
+			FSONaming::Named { fso_name, .. } | FSONaming::ExistenceIsBool { fso_name } if fso_name.starts_with('+') => { 1usize }
+			_ => { 0usize }
+		};
+		let nest = key_indent + match (&field.fso_name, &value_type) {
+			(FSONaming::Named { multiline: true, .. }, FSOValueType::Vector { .. }) => { 1usize }
+			_ => { 0usize }
+		};
+		//Comments after a value on the same line stay there. Multiline lists start on the next line, so their comment follows the key
+		let key_trailing = matches!((&field.fso_name, &value_type), (FSONaming::Named { multiline: true, .. }, FSOValueType::Vector { .. }));
+		let parse_trailing = if inline { quote!{} } else { quote!{ __trailing_comments[#field_num] = state.consume_trailing_comment(); } };
+		let spew_trailing = quote!{
+			if let Some(comment) = &self.__trailing_comments[#field_num] {
+				state.append_trailing_comment(comment);
+			}
+		};
+		let (parse_key_trailing, spew_key_trailing, parse_value_trailing, spew_value_trailing) = if key_trailing {
+			(parse_trailing, spew_trailing, quote!{}, quote!{})
+		}
+		else {
+			(quote!{}, quote!{}, parse_trailing, spew_trailing)
+		};
+		let spew_type = quote!{
+			state.get_state().nesting += #nest;
+			#spew_type
+			state.get_state().nesting -= #nest;
+		};
 		let (parse_value, spew_value) = match &field.fso_name {
 			FSONaming::Named { fso_name, .. } => {
 				match value_type {
@@ -116,18 +244,21 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 						},
 						quote!{
 							if let Some(__to_spew) = &self.#name {
-								state.append("\n");
+								state.start_line();
 								#spew_comments
+								state.indent(#key_indent);
 								state.append(#fso_name);
 								state.append(" ");
 								#spew_type
 								#spew_gobble
+								#spew_value_trailing
 							}
 						})
 					}
 					_ => {
 						(quote!{
 							state.consume_string(#fso_name)#or_else_fail;
+							#parse_key_trailing
 							#process_comments
 							let (#name, __inner_gobble) = #make_type #or_else_fail;
 							#parse_inner_gobble
@@ -136,12 +267,15 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 						quote!{
 							{
 								let __to_spew = &self.#name;
-								state.append("\n");
+								state.start_line();
 								#spew_comments
+								state.indent(#key_indent);
 								state.append(#fso_name);
 								state.append(" ");
+								#spew_key_trailing
 								#spew_type
 								#spew_gobble
+								#spew_value_trailing
 							}
 						})
 					}
@@ -166,6 +300,7 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 								#spew_comments
 								#spew_type
 								#spew_gobble
+								#spew_value_trailing
 							}
 						})
 					}
@@ -182,6 +317,7 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 								#spew_comments
 								#spew_type
 								#spew_gobble
+								#spew_value_trailing
 							}
 						})
 					}
@@ -199,10 +335,12 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 						},
 						quote! {
 							if self.#name {
-								state.append("\n");
+								state.start_line();
 								#spew_comments
+								state.indent(#key_indent);
 								state.append(#fso_name);
 								#spew_gobble
+								#spew_value_trailing
 							}
 						})
 					}
@@ -214,10 +352,41 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 			FSONaming::Skipped => { unreachable!() }
 		};
 
+		let (parse_unknown, spew_unknown) = match (&unknown_slot, &field.fso_name) {
+			(Some((slot, inner)), FSONaming::Named { .. } | FSONaming::ExistenceIsBool { .. }) => { (fso_struct_parse_unknown(fields, slot, inner, field_num), fso_struct_spew_unknown(slot, field_num)) }
+			_ => { (quote!(), quote!()) }
+		};
+		let (parse_value, spew_value) = match &unknown_slot {
+			Some((slot, inner)) if field.unknown => {
+				let parse_slot = fso_struct_parse_unknown(fields, slot, inner, field_num);
+				let spew_slot = fso_struct_spew_unknown(slot, field_num);
+				(quote! {
+					#parse_slot
+				},
+				quote! {
+					#spew_comments
+					#spew_slot
+				})
+			}
+			_ => {
+				(quote! {
+					#parse_unknown
+					#parse_value
+				},
+				quote! {
+					#spew_unknown
+					#spew_value
+				})
+			}
+		};
+
+		let record_source = fso_struct_record_source(field_num);
 		parse = quote!(
 			#parse
 			#parse_comments
 			#parse_value
+			#parse_value_trailing
+			#record_source
 		);
 
 		fill = quote!(
@@ -225,21 +394,20 @@ pub(crate) fn fso_struct_build_parse(fields: &Vec<TableField>, inline: bool) ->
 			#name,
 		);
 
-		spew = quote!(
-			#spew
-			#spew_value
-		);
+		spew.push((field_num, spew_value, !matches!(field.fso_name, FSONaming::Unnamed)));
 	}
 
 	Ok((parse, fill, spew))
 }
 
-pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec<TokenStream>, lifetime_req: Vec<TokenStream>, table_prefix: Option<String>, table_suffix: Option<String>, prefix: Option<String>, suffix: Option<String>, inline: bool) -> Result<(TokenStream, TokenStream), Error> {
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec<TokenStream>, lifetime_req: Vec<TokenStream>, table_prefix: Option<String>, table_suffix: Option<String>, prefix: Option<String>, suffix: Option<String>, inline: bool, constructor: String) -> Result<(TokenStream, TokenStream), Error> {
 	let mut table_fields: Vec<TableField> = Vec::new();
 	let struct_name = &item_struct.ident;
 	let (_, ty_generics, where_clause) = item_struct.generics.split_for_impl();
 	let mut field_count: usize = if table_prefix.is_some() { 1 } else { 0 };
 	let mut field_comma_list = quote!();
+	let has_unknown_slot;
 	
 	if let syn::Fields::Named(ref mut fields) = item_struct.fields {
 		for field in fields.named.iter_mut() {
@@ -248,7 +416,7 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 				Meta::NameValue( MetaNameValue { value: Expr::Lit( ExprLit{ lit: Lit::Str(new_name), ..}), .. })
 				if a.meta.path().is_ident("fso_name") => { Some(Ok(new_name.value())) },
 				_ if a.meta.path().is_ident("fso_name") => {
-					return Some(Err(Error::new(a.span(), "Attribute fso_name must have a value!")));
+					Some(Err(Error::new(a.span(), "Attribute fso_name must have a value!")))
 				}
 				_ => { None }
 			});
@@ -256,7 +424,7 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 				Meta::NameValue( MetaNameValue { value: Expr::Lit( ExprLit{ lit: Lit::Str(new_name), ..}), .. })
 				if a.meta.path().is_ident("gobble") => { Some(Ok(new_name.value())) },
 				_ if a.meta.path().is_ident("gobble") => {
-					return Some(Err(Error::new(a.span(), "Attribute gobble must have a value!")));
+					Some(Err(Error::new(a.span(), "Attribute gobble must have a value!")))
 				}
 				_ => { None }
 			}) {
@@ -290,6 +458,12 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 				}
 				_ => { None }
 			});
+			let unknown = field.attrs.iter().find_map(|a| match &a.meta {
+				Meta::Path( path ) if path.is_ident("unknown") => {
+					Some(())
+				}
+				_ => { None }
+			});
 			
 			field.attrs.retain(|a| !(
 				a.path().is_ident("fso_name") ||
@@ -297,7 +471,8 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 				a.path().is_ident("skip") ||
 				a.path().is_ident("unnamed") ||
 				a.path().is_ident("existence") || 
-				a.path().is_ident("multiline")));
+				a.path().is_ident("multiline") ||
+				a.path().is_ident("unknown")));
 
 			if let Some(ident) = field.ident.as_ref() {
 				let rust_token = ident.to_string();
@@ -306,7 +481,7 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 				if skip.is_some() {
 					fso_name = FSONaming::Skipped;
 				}
-				else if unnamed.is_none() {
+				else if unnamed.is_none() && unknown.is_none() {
 					thread_local! { static UNDERSCORE_REGEX: Regex = Regex::new(r"([^_]+)").unwrap(); }
 
 					let mut out_name = "".to_string();
@@ -335,15 +510,22 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 					#field_comma_list #ident: #rust_type,
 				};
 				
-				table_fields.push(TableField { fso_name, fso_gobble, rust_token: ident.clone(), rust_type, rust_span: field.span(), field_number: field_count });
+				table_fields.push(TableField { fso_name, fso_gobble, rust_token: ident.clone(), rust_type, rust_span: field.span(), field_number: field_count, unknown: unknown.is_some() });
 				field_count += 1;
 			}
 		}
 
-		field_count += if table_prefix.is_some() { 1 } else { 0 };
+		field_count += if table_suffix.is_some() { 1 } else { 0 };
+		has_unknown_slot = table_fields.iter().any(|field| field.unknown);
 		
 		fields.named.push(Field::parse_named.parse2(quote! { __comments: [Option<String>; #field_count] })?);
 		fields.named.push(Field::parse_named.parse2(quote! { __version_strings: [Option<String>; #field_count] })?);
+		fields.named.push(Field::parse_named.parse2(quote! { __trailing_comments: [Option<String>; #field_count] })?);
+		fields.named.push(Field::parse_named.parse2(quote! { __sources: Vec<Option<fso_tables::FSOSource>> })?);
+		if has_unknown_slot {
+			//For each unknown option, the number of the field it was found in front of
+			fields.named.push(Field::parse_named.parse2(quote! { __unknown_before: Vec<usize> })?);
+		}
 	}
 	else {
 		return Err(Error::new(item_struct.fields.span(), "A struct annotated with fso_table must have named fields!"));
@@ -353,10 +535,27 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 
 	let where_clause_with_parser = fso_build_where_clause(&instancing_req, &where_clause);
 
-	let (parser, filler, spew) = fso_struct_build_parse(&table_fields, inline)?;
+	let (parser, filler, mut field_spewers) = fso_struct_build_parse(&table_fields, inline)?;
 
-	let (prefix_parser, prefix_spewer) = if let Some(prefix) = table_prefix{
-		(quote! {
+	let mut prefix_parser = quote!{};
+	let mut suffix_parser = quote!{};
+	if let Some(prefix) = table_prefix{
+		let record_source = fso_struct_record_source(0);
+		field_spewers.insert(0, (0, quote! {
+			if let Some(comment) = &self.__comments[0] {
+				state.append(comment);
+				state.newline();
+			}
+			if let Some(version_string) = &self.__version_strings[0] {
+				state.append(version_string);
+			}
+			state.append(#prefix);
+			if let Some(comment) = &self.__trailing_comments[0] {
+				state.append_trailing_comment(comment);
+			}
+			state.newline();
+		}, false));
+		prefix_parser = quote! {
 			if !__already_parsed_comments {
 				(__comment, __version_string) = state.consume_whitespace(false);
 			}
@@ -368,24 +567,14 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 				err.version_string = __version_string.clone();
 				err
 			})?;
-		}, quote! {
-			if let Some(comment) = &self.__comments[0] {
-				state.append(comment);
-				state.append("\n");
-			}
-			if let Some(version_string) = &self.__version_strings[0] {
-				state.append(version_string);
-			}
-			state.append(#prefix);
-			state.append("\n");
-		})
+			__trailing_comments[0] = state.consume_trailing_comment();
+			#record_source
+		};
 	}
-	else {
-		(quote!{}, quote!{})
-	};
-	let (suffix_parser, suffix_spewer) = if let Some(suffix) = table_suffix{
+	if let Some(suffix) = table_suffix{
 		let suffix_field = field_count - 1;
-		(quote! {
+		let record_source = fso_struct_record_source(suffix_field);
+		suffix_parser = quote! {
 			if !__already_parsed_comments {
 				(__comment, __version_string) = state.consume_whitespace(false);
 			}
@@ -393,32 +582,77 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 			__version_strings[#suffix_field] = __version_string.clone();
 			__already_parsed_comments = true;
 			state.consume_string(#suffix)?;
-		}, quote! {
-			state.append("\n\n");
+			__trailing_comments[#suffix_field] = state.consume_trailing_comment();
+			#record_source
+		};
+		field_spewers.push((suffix_field, quote! {
+			state.newline();
+			state.newline();
 			if let Some(comment) = &self.__comments[#suffix_field] {
 				state.append(comment);
-				state.append("\n");
+				state.newline();
 			}
 			if let Some(version_string) = &self.__version_strings[#suffix_field] {
 				state.append(version_string);
 			}
 			state.append(#suffix);
-		})
+			if let Some(comment) = &self.__trailing_comments[#suffix_field] {
+				state.append_trailing_comment(comment);
+			}
+		}, true));
 	}
-	else {
-		(quote!{}, quote!{})
+
+	//Paths lead into fields by their rust name
+	let mut field_arms = quote!();
+	let mut field_mut_arms = quote!();
+	let pathed_fields = table_fields.iter().filter(|field| field.fso_name != FSONaming::Skipped).map(|field| (field, pathed_type(&field.rust_type))).collect::<Vec<_>>();
+	for (field, boxed) in &pathed_fields {
+		let name = &field.rust_token;
+		let name_string = name.to_string();
+		let (access, access_mut) = match boxed {
+			Some(false) => { (quote!(&self.#name), quote!(&mut self.#name)) }
+			Some(true) => { (quote!(&*self.#name), quote!(&mut *self.#name)) }
+			None => { continue; }
+		};
+		field_arms = quote!{ #field_arms #name_string => { Some(#access) } };
+		field_mut_arms = quote!{ #field_mut_arms #name_string => { Some(#access_mut) } };
+	}
+	let field_names = pathed_fields.iter().filter(|(_, boxed)| boxed.is_some()).map(|(field, _)| field.rust_token.to_string());
+	let (only_field, only_field_mut) = match pathed_fields.as_slice() {
+		[(field, Some(boxed))] => {
+			let name = &field.rust_token;
+			if *boxed { (quote!(Some(&*self.#name)), quote!(Some(&mut *self.#name))) } else { (quote!(Some(&self.#name)), quote!(Some(&mut self.#name))) }
+		}
+		_ => { (quote!(None), quote!(None)) }
 	};
 
+	let (unknown_filler, unknown_new_filler) = if has_unknown_slot { (quote!(__unknown_before), quote!(__unknown_before: Vec::new())) } else { (quote!(), quote!()) };
+
+	let mut starts_line = vec![false; field_count];
+	let mut spew_field_cases = quote!();
+	for (field_num, spewer, named) in field_spewers {
+		starts_line[field_num] = named;
+		spew_field_cases = quote! {
+			#spew_field_cases
+			#field_num => { #spewer }
+		};
+	}
+
+	let constructor = format_ident!("{}", constructor);
 	Ok((quote! {
 		impl fso_tables::FSOTable for #struct_name #ty_generics  {
 			fn parse<#impl_with_generics>(state: &'parser Parser, hanging_gobble: Option<fso_tables::FSOParsingHangingGobble>) -> Result<(Self, Option<fso_tables::FSOParsingHangingGobble>), fso_tables::FSOParsingError> #where_clause_with_parser {
 				const NONE_ARRAY_REPEAT_VALUE: Option<String> = None;
 				let mut __comments = [NONE_ARRAY_REPEAT_VALUE; #field_count];
 				let mut __version_strings = [NONE_ARRAY_REPEAT_VALUE; #field_count];
+				let mut __trailing_comments = [NONE_ARRAY_REPEAT_VALUE; #field_count];
 				let (mut __comment, mut __version_string, mut __already_parsed_comments) = if let Some(hanging_gobble) = hanging_gobble {
 					(hanging_gobble.comments, hanging_gobble.version_string, true)
 				} 
 				else { (None, None, false) };
+				let __keep_source = state.keeps_source();
+				let mut __source_start = state.source(0..state.position()).trim_end().len();
+				let mut __source_texts = [NONE_ARRAY_REPEAT_VALUE; #field_count];
 				#prefix_parser
 				#parser
 				#suffix_parser
@@ -426,39 +660,122 @@ pub(crate) fn fso_table_struct(item_struct: &mut ItemStruct, instancing_req: Vec
 					comments: __comment,
 					version_string: __version_string
 				}) } else { None };
-				core::result::Result::Ok((#struct_name {
+				let mut __result = #struct_name {
 					#filler
 					__comments,
-					__version_strings
-				}, __hanging_comments))
+					__version_strings,
+					__trailing_comments,
+					__sources: Vec::new(),
+					#unknown_filler
+				};
+				if __keep_source {
+					//Spewing the freshly parsed value tells later whether a field is still as it was parsed
+					let __sources = __source_texts.into_iter().enumerate().map(|(__index, __text)| __text.map(|text| {
+						let mut __fingerprint = fso_tables::FSOTableBuilder::default();
+						__result.__spew_field(__index, &mut __fingerprint);
+						fso_tables::FSOSource { text, fingerprint: fso_tables::FSOStringBuilder::spew(__fingerprint) }
+					})).collect();
+					__result.__sources = __sources;
+				}
+				core::result::Result::Ok((__result, __hanging_comments))
 			}
 			fn spew(&self, state: &mut impl fso_tables::FSOBuilder) {
-				#prefix_spewer
-				#spew
-				#suffix_spewer
+				const __STARTS_LINE: [bool; #field_count] = [#(#starts_line),*];
+				let __round_trip = state.get_state().round_trip;
+				for __index in 0..#field_count {
+					//Unchanged fields are spewed as they were parsed, whitespace and all
+					if let (true, Some(Some(__source))) = (__round_trip, self.__sources.get(__index)) {
+						let mut __current = fso_tables::FSOTableBuilder::default();
+						self.__spew_field(__index, &mut __current);
+						if fso_tables::FSOStringBuilder::spew(__current) == __source.fingerprint {
+							state.append_source(&__source.text, __STARTS_LINE[__index]);
+							continue;
+						}
+						//Changed fields at least keep the whitespace in front of them
+						state.append_source(&__source.text[..__source.text.len() - __source.text.trim_start().len()], false);
+					}
+					self.__spew_field(__index, state);
+				}
+			}
+			fn field(&self, __name: &str) -> Option<&dyn fso_tables::FSOPathed> {
+				match __name {
+					#field_arms
+					_ => { None }
+				}
+			}
+			fn field_mut(&mut self, __name: &str) -> Option<&mut dyn fso_tables::FSOPathed> {
+				match __name {
+					#field_mut_arms
+					_ => { None }
+				}
+			}
+			fn field_names(&self) -> Vec<&'static str> {
+				vec![#(#field_names),*]
+			}
+			fn only_field(&self) -> Option<&dyn fso_tables::FSOPathed> {
+				#only_field
+			}
+			fn only_field_mut(&mut self) -> Option<&mut dyn fso_tables::FSOPathed> {
+				#only_field_mut
 			}
 		}
 		impl #struct_name #ty_generics {
-			pub fn new(#field_comma_list) -> Self{
+			#[allow(clippy::too_many_arguments)]
+			pub fn #constructor(#field_comma_list) -> Self{
 				const NONE_ARRAY_REPEAT_VALUE: Option<String> = None;
 				#struct_name {
 					#filler
 					__comments: [NONE_ARRAY_REPEAT_VALUE; #field_count],
-					__version_strings: [NONE_ARRAY_REPEAT_VALUE; #field_count]
+					__version_strings: [NONE_ARRAY_REPEAT_VALUE; #field_count],
+					__trailing_comments: [NONE_ARRAY_REPEAT_VALUE; #field_count],
+					__sources: Vec::new(),
+					#unknown_new_filler
+				}
+			}
+			#[allow(clippy::match_single_binding)]
+			fn __spew_field(&self, __index: usize, state: &mut impl fso_tables::FSOBuilder) {
+				match __index {
+					#spew_field_cases
+					_ => { }
 				}
-			}	
+			}
 		}
 	}, 
 	quote! { 
 		impl #struct_name #ty_generics {
 			pub fn parse<Parser>(parser: Parser) -> Result<Self, fso_tables::FSOParsingError> where Parser: for<'a> fso_tables::FSOParser<'a> { 
-				let (parse, _) = fso_tables::FSOTable::parse(&parser, None)?;
+				let (mut parse, _): (Self, _) = fso_tables::FSOTable::parse(&parser, None)?;
+				//Whatever follows the table belongs to its last part
+				let __parsed = parse.__sources.iter().flatten().map(|source| source.text.len()).sum::<usize>();
+				if let Some(last) = parse.__sources.iter_mut().rev().find_map(Option::as_mut) {
+					last.text.push_str(fso_tables::FSOParser::source(&parser, __parsed..fso_tables::FSOParser::position(&parser)));
+					last.text.push_str(fso_tables::FSOParser::get(&parser));
+				}
 				Ok(parse)
 			}
 			pub fn spew(&self) -> String {
 				let mut parser = fso_tables::FSOTableBuilder::default();
 				fso_tables::FSOTable::spew(self, &mut parser);
-				fso_tables::FSOBuilder::spew(parser)
+				fso_tables::FSOStringBuilder::spew(parser)
+			}
+			pub fn spew_formatted(&self, formatting: fso_tables::FSOFormatting) -> String {
+				let mut parser = fso_tables::FSOTableBuilder::new(formatting);
+				fso_tables::FSOTable::spew(self, &mut parser);
+				fso_tables::FSOStringBuilder::spew(parser)
+			}
+			//Writes the table as spew_formatted would, without holding all of it in memory
+			pub fn spew_into<W: std::io::Write>(&self, writer: W, formatting: fso_tables::FSOFormatting) -> std::io::Result<W> {
+				let mut parser = fso_tables::FSOStreamBuilder::new(writer, formatting);
+				fso_tables::FSOTable::spew(self, &mut parser);
+				parser.finish()
+			}
+			//Parts unchanged since parsing with a round trip parser are spewed exactly as they were read, the others as spew would.
+			//A changed field is spewed anew as a whole, so its own spacing is not kept
+			pub fn spew_round_trip(&self) -> String {
+				let mut parser = fso_tables::FSOTableBuilder::default();
+				fso_tables::FSOBuilder::get_state(&mut parser).round_trip = true;
+				fso_tables::FSOTable::spew(self, &mut parser);
+				fso_tables::FSOStringBuilder::spew(parser)
 			}
 		}
 	}))
@@ -32,7 +32,7 @@ pub(crate) fn deduce_type<'a>(name: &FSONaming, ty: &'a Type, to_spew_name: &Ide
 				if let GenericArgument::Type( inner ) = inner_types.args.first().unwrap() {
 					match typename.ident.to_string().as_str() {
 						"Vec" => {
-							let multiline = if let FSONaming::Named { multiline, ..} = name { *multiline } else if *name == FSONaming::Unnamed { true } else { false };
+							let multiline = if let FSONaming::Named { multiline, ..} = name { *multiline } else { *name == FSONaming::Unnamed };
 							
 							let (inner_type, make_containing, spew_containing) = deduce_type(name, inner, &format_ident!("__to_spew"), &format_ident!("__last_hanging_gobble"))?;
 							if let FSOValueType::Option { .. } = inner_type {
@@ -45,12 +45,18 @@ pub(crate) fn deduce_type<'a>(name: &FSONaming, ty: &'a Type, to_spew_name: &Ide
 									state.consume_whitespace_inline(&['(']);
 									let mut __comment_inner;
 									let mut __version_string_inner;
-									let mut __already_parsed_comments_inner = false;
 									let mut __last_hanging_gobble = None;
 									
 									loop {
+										let __remaining_before_element = state.get().len();
 										let __new_element_for_vec = #make_containing;
 										match __new_element_for_vec {
+											//An element that consumed nothing would be parsed again forever, so it ends the list instead
+											Ok(_) if state.get().len() == __remaining_before_element => {
+												__comment_inner = None;
+												__version_string_inner = None;
+												break;
+											}
 											Ok((__new_element_for_vec, __inner_gobble)) => { 
 												__last_hanging_gobble = __inner_gobble;
 												__vec_to_fill.push(__new_element_for_vec) 
@@ -58,16 +64,20 @@ pub(crate) fn deduce_type<'a>(name: &FSONaming, ty: &'a Type, to_spew_name: &Ide
 											Err(fso_tables::FSOParsingError{ comments, version_string, .. }) => {
 												__comment_inner = comments;
 												__version_string_inner = version_string;
-												__already_parsed_comments_inner = true;
 												break;
 											}
 										}
 									}
 									
 									state.consume_whitespace_inline(&[')']);
-									Ok((__vec_to_fill, if __already_parsed_comments_inner { 
-										Some(fso_tables::FSOParsingHangingGobble { comments: __comment_inner, version_string: __version_string_inner })
-									} else { None }))
+									//Elements don't necessarily consume the whitespace after the list (i.e. after a closing parenthesis), so make sure the handed-out gobble covers it
+									let (__comment_after, __version_string_after) = state.consume_whitespace(false);
+									__comment_inner = match (__comment_inner, __comment_after) {
+										(Some(before), Some(after)) => { Some(format!("{}\n{}", before, after)) }
+										(before, after) => { before.or(after) }
+									};
+									__version_string_inner = __version_string_after.or(__version_string_inner);
+									Ok((__vec_to_fill, Some(fso_tables::FSOParsingHangingGobble { comments: __comment_inner, version_string: __version_string_inner })))
 								}
 							};
 
@@ -93,13 +103,42 @@ pub(crate) fn deduce_type<'a>(name: &FSONaming, ty: &'a Type, to_spew_name: &Ide
 									state.append(")");
 								}
 							};
-							let newline = if multiline { quote!(state.append("\n");) } else { quote!() };
+							//Inline lists need a separator, as not every element type spews trailing whitespace
+							let separator = if multiline {
+								quote!{
+									state.start_line();
+									//Only the entries of outermost lists, such as $Name: entries, are spaced out
+									if __index_in_list > 0 && state.get_state().list_state.len() == 1 {
+										for _ in 0..state.get_state().formatting.blank_lines_between_entries {
+											state.newline();
+										}
+									}
+									state.indent(0);
+								}
+							}
+							else {
+								quote!(if __index_in_list > 0 { state.append(" "); })
+							};
 							
+							//Only lists of tables themselves can be flagsets
+							let sort = if let FSOValueType::Direct { .. } | FSOValueType::Generic { .. } = inner_type {
+								quote! {
+									if state.get_state().formatting.sort_flags {
+										__entries.sort_by_key(|__entry| fso_tables::FSOTable::flag_name(*__entry));
+									}
+								}
+							}
+							else {
+								quote!()
+							};
 							let spew_value = quote!{
 								{
 									#push
-									for __to_spew in #to_spew_name.iter() {
-										#newline
+									#[allow(unused_mut)]
+									let mut __entries = #to_spew_name.iter().collect::<Vec<_>>();
+									#sort
+									for (__index_in_list, __to_spew) in __entries.into_iter().enumerate() {
+										#separator
 										#spew_containing
 									}
 									#pop
@@ -156,9 +195,7 @@ pub(crate) fn deduce_type<'a>(name: &FSONaming, ty: &'a Type, to_spew_name: &Ide
 			let mut types: Vec<FSOValueType> = Vec::new();
 			let mut parser = quote!();
 			let mut spewer = quote!();
-			let mut count = 0;
-
-			for inner in elems {
+			for (count, inner) in elems.iter().enumerate() {
 				let (spew_comma, hanging_gobble) = if count == 0 {
 					(quote!(), hanging_gobble.clone())
 				}
@@ -186,8 +223,6 @@ pub(crate) fn deduce_type<'a>(name: &FSONaming, ty: &'a Type, to_spew_name: &Ide
 						#spew_containing
 					}
 				};
-
-				count += 1;
 			}
 
 			let parse_value = quote!{ (|| {
@@ -211,4 +246,35 @@ pub(crate) fn deduce_type<'a>(name: &FSONaming, ty: &'a Type, to_spew_name: &Ide
 			Err(Error::new(ty.span(), "FSO Tables can only process path and tuple types"))
 		}
 	}
-}
\ No newline at end of file
+}
+//Whether paths can lead into a value of this type, and if so, whether it needs to be dereferenced from a Box first.
+//Any table, lists and optional values of those, and pairs are supported
+pub(crate) fn pathed_type(ty: &Type) -> Option<bool> {
+	fn supported(ty: &Type) -> bool {
+		match ty {
+			Type::Path( TypePath { path: Path { segments, .. }, .. } ) => {
+				let Some(typename) = segments.last() else { return false; };
+				match &typename.arguments {
+					AngleBracketed(inner_types) => {
+						matches!(typename.ident.to_string().as_str(), "Vec" | "Option") &&
+							matches!(inner_types.args.first(), Some(GenericArgument::Type( inner )) if supported(inner))
+					}
+					_ => { true }
+				}
+			}
+			Type::Tuple( TypeTuple { elems, .. } ) => { elems.len() == 2 && elems.iter().all(supported) }
+			_ => { false }
+		}
+	}
+
+	if let Type::Path( TypePath { path: Path { segments, .. }, .. } ) = ty {
+		if let Some(typename) = segments.last().filter(|typename| typename.ident == "Box") {
+			if let AngleBracketed(inner_types) = &typename.arguments {
+				if let Some(GenericArgument::Type( inner )) = inner_types.args.first() {
+					return supported(inner).then_some(true);
+				}
+			}
+		}
+	}
+	supported(ty).then_some(false)
+}
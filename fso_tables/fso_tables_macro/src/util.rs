@@ -2,7 +2,7 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{Generics, WhereClause};
 
-pub fn fso_build_impl_generics(lifetime_req: &Vec<TokenStream>, generics: &Generics) -> TokenStream {
+pub fn fso_build_impl_generics(lifetime_req: &[TokenStream], generics: &Generics) -> TokenStream {
 	let mut impl_with_generics = TokenStream::new();
 	for lifetime in lifetime_req.iter() {
 		impl_with_generics = quote! {#impl_with_generics #lifetime, };
@@ -16,7 +16,7 @@ pub fn fso_build_impl_generics(lifetime_req: &Vec<TokenStream>, generics: &Gener
 	quote! {#impl_with_generics 'parser, Parser}
 }
 
-pub fn fso_build_where_clause(instancing_req: &Vec<TokenStream>, where_clause: &Option<&WhereClause>) -> TokenStream {
+pub fn fso_build_where_clause(instancing_req: &[TokenStream], where_clause: &Option<&WhereClause>) -> TokenStream {
 	let mut where_clause_with_parser = if let Some(where_clause) = where_clause {
 		let inner_where = where_clause.to_token_stream();
 		quote! {#inner_where, }
@@ -44,20 +44,33 @@ impl FSOTable for bool {
 	}
 }
 
-fn parse_number<'a, Parser: FSOParser<'a>, T: FromStr>(state: &Parser, allow_dot: bool, allow_minus: bool) -> Result<(T, Option<FSOParsingHangingGobble>), FSOParsingError> {
+//Floats may have an exponent, as in 1e-3, and may start or end with their dot, as in .5 or 1.
+pub(crate) fn parse_number<'a, Parser: FSOParser<'a>, T: FromStr>(state: &Parser, allow_dot: bool, allow_minus: bool) -> Result<(T, Option<FSOParsingHangingGobble>), FSOParsingError> {
 	state.consume_whitespace_inline(&[]);
 	let current = state.get();
 	let mut have_dot = !allow_dot;
+	let mut have_digit = false;
 	let mut to_consume = 0usize;
 
 	for c in current.chars() {
 		if c.is_ascii_digit() || ((c == '+' || (c == '-' && allow_minus)) && to_consume == 0) {
 			to_consume += 1;
+			have_digit |= c.is_ascii_digit();
 		}
 		else if c == '.' && !have_dot {
 			to_consume += 1;
 			have_dot = true;
 		}
+		else if (c == 'e' || c == 'E') && allow_dot && have_digit {
+			//Only an exponent if digits follow, otherwise the e belongs to whatever comes next
+			let exponent = &current[to_consume + 1..];
+			let sign = if exponent.starts_with(['+', '-']) { 1 } else { 0 };
+			let digits = exponent[sign..].chars().take_while(char::is_ascii_digit).count();
+			if digits > 0 {
+				to_consume += 1 + sign + digits;
+			}
+			break;
+		}
 		else {
 			break;
 		}
@@ -80,7 +93,11 @@ impl FSOTable for f32 {
 	}
 
 	fn spew(&self, state: &mut impl FSOBuilder) {
-		state.append(self.to_string().as_str());
+		let spewed = match state.get_state().formatting.float_precision {
+			Some(precision) => { format!("{:.*}", precision, self) }
+			None => { self.to_string() }
+		};
+		state.append(spewed.as_str());
 	}
 }
 
@@ -90,7 +107,11 @@ impl FSOTable for f64 {
 	}
 
 	fn spew(&self, state: &mut impl FSOBuilder) {
-		state.append(self.to_string().as_str());
+		let spewed = match state.get_state().formatting.float_precision {
+			Some(precision) => { format!("{:.*}", precision, self) }
+			None => { self.to_string() }
+		};
+		state.append(spewed.as_str());
 	}
 }
 
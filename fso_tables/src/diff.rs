@@ -0,0 +1,240 @@
+use std::fmt::{Display, Formatter};
+use crate::{FSOPath, FSOPathStep, FSOPathed, FSOShape};
+use crate::merge::align;
+
+//List entries are matched up by this field, as in $Name:, if all of them have a distinct one
+pub const FSO_ENTRY_KEY: &str = "name";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FSOChange {
+	Added(String),
+	Removed(String),
+	Modified { old: String, new: String }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FSODifference {
+	pub path: FSOPath,
+	pub change: FSOChange
+}
+
+//The differences between two versions of a table, down to the fields that changed
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct FSODiff(pub Vec<FSODifference>);
+
+//The key of each entry, if all entries have one and no two share it
+pub(crate) fn entry_keys(entries: &[&dyn FSOPathed]) -> Option<Vec<String>> {
+	let key = FSOPathStep::Field(FSO_ENTRY_KEY.to_string());
+	let keys = entries.iter().map(|entry| entry.step(&key).map(|name| name.get())).collect::<Option<Vec<String>>>()?;
+	keys.iter().enumerate().all(|(i, name)| !keys[..i].contains(name)).then_some(keys)
+}
+
+//How a diff matches up values that may have changed
+#[derive(Clone, Copy, Default)]
+struct FSODiffMode {
+	//Flagsets that only differ in the order of their flags count as the same
+	unordered_flags: bool,
+	//Entries of lists without keys are matched up as align does, and all paths are those in old
+	aligned_lists: bool
+}
+
+pub(crate) fn entry_step(key: &str) -> FSOPathStep {
+	FSOPathStep::Named { key: FSO_ENTRY_KEY.to_string(), value: key.to_string() }
+}
+
+impl FSODiff {
+	pub fn between(old: &dyn FSOPathed, new: &dyn FSOPathed) -> Self {
+		let mut diff = FSODiff::default();
+		diff.compare(&mut Vec::new(), old, new, FSODiffMode::default());
+		diff
+	}
+
+	//As between, but flagsets that only differ in the order of their flags count as the same.
+	//Paths into flagsets then index their flags in sorted order
+	pub fn between_unordered_flags(old: &dyn FSOPathed, new: &dyn FSOPathed) -> Self {
+		let mut diff = FSODiff::default();
+		diff.compare(&mut Vec::new(), old, new, FSODiffMode { unordered_flags: true, ..Default::default() });
+		diff
+	}
+
+	//As between, but entries of lists without keys are matched up by what stayed the same, so that removing one doesn't change all after it.
+	//All paths are those in old; entries added in front of an old one have its index, those added at the end count on from its length
+	pub(crate) fn between_aligned(old: &dyn FSOPathed, new: &dyn FSOPathed) -> Self {
+		let mut diff = FSODiff::default();
+		diff.compare(&mut Vec::new(), old, new, FSODiffMode { aligned_lists: true, ..Default::default() });
+		diff
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	fn push(&mut self, path: &[FSOPathStep], change: FSOChange) {
+		self.0.push(FSODifference { path: FSOPath(path.to_vec()), change });
+	}
+
+	fn compare(&mut self, path: &mut Vec<FSOPathStep>, old: &dyn FSOPathed, new: &dyn FSOPathed, mode: FSODiffMode) {
+		match (old.shape(), new.shape()) {
+			(FSOShape::Absent, FSOShape::Absent) => { }
+			(FSOShape::Absent, _) => { self.push(path, FSOChange::Added(new.get())); }
+			(_, FSOShape::Absent) => { self.push(path, FSOChange::Removed(old.get())); }
+			(FSOShape::List(old_entries), FSOShape::List(new_entries)) => { self.compare_lists(path, old_entries, new_entries, mode); }
+			(FSOShape::Table { variant: old_variant, fields: old_fields }, FSOShape::Table { variant: new_variant, fields: new_fields })
+			if old_variant == new_variant && old_fields.iter().map(|(name, _)| name).eq(new_fields.iter().map(|(name, _)| name)) => {
+				//Like in paths, a table wrapping a single table or list stands for it
+				let wrapper = old_fields.len() == 1 && matches!(old_fields[0].1.shape(), FSOShape::Table { .. } | FSOShape::List(_));
+				for ((name, old_field), (_, new_field)) in old_fields.into_iter().zip(new_fields) {
+					if wrapper {
+						self.compare(path, old_field, new_field, mode);
+						continue;
+					}
+					path.push(FSOPathStep::Field(name.to_string()));
+					self.compare(path, old_field, new_field, mode);
+					path.pop();
+				}
+			}
+			_ => {
+				let (old, new) = (old.get(), new.get());
+				if old != new {
+					self.push(path, FSOChange::Modified { old, new });
+				}
+			}
+		}
+	}
+
+	fn compare_lists(&mut self, path: &mut Vec<FSOPathStep>, mut old_entries: Vec<&dyn FSOPathed>, mut new_entries: Vec<&dyn FSOPathed>, mode: FSODiffMode) {
+		//Sorted the same way spewing sorts flagsets, so flags with equal names keep their order
+		if mode.unordered_flags && old_entries.iter().chain(&new_entries).all(|entry| entry.flag_name().is_some()) {
+			old_entries.sort_by_key(|entry| entry.flag_name());
+			new_entries.sort_by_key(|entry| entry.flag_name());
+		}
+		if let (Some(old_keys), Some(new_keys)) = (entry_keys(&old_entries), entry_keys(&new_entries)) {
+			for (old_key, old_entry) in old_keys.iter().zip(&old_entries) {
+				path.push(entry_step(old_key));
+				match new_keys.iter().position(|new_key| new_key == old_key) {
+					Some(index) => { self.compare(path, *old_entry, new_entries[index], mode); }
+					None => { self.push(path, FSOChange::Removed(old_entry.get())); }
+				}
+				path.pop();
+			}
+			for (new_key, new_entry) in new_keys.iter().zip(&new_entries).filter(|(new_key, _)| !old_keys.contains(new_key)) {
+				path.push(entry_step(new_key));
+				self.push(path, FSOChange::Added(new_entry.get()));
+				path.pop();
+			}
+		}
+		else if mode.aligned_lists {
+			let mapping = align(&old_entries, &new_entries);
+			for (index, (old_entry, new_index)) in old_entries.iter().zip(&mapping).enumerate() {
+				path.push(FSOPathStep::Index(index));
+				match new_index {
+					Some(new_index) => { self.compare(path, *old_entry, new_entries[*new_index], mode); }
+					None => { self.push(path, FSOChange::Removed(old_entry.get())); }
+				}
+				path.pop();
+			}
+			let mut appended = 0;
+			for (new_index, new_entry) in new_entries.iter().enumerate().filter(|(new_index, _)| !mapping.contains(&Some(*new_index))) {
+				let index = match mapping.iter().position(|mapped| mapped.is_some_and(|mapped| mapped > new_index)) {
+					Some(before) => { before }
+					None => {
+						appended += 1;
+						old_entries.len() + appended - 1
+					}
+				};
+				path.push(FSOPathStep::Index(index));
+				self.push(path, FSOChange::Added(new_entry.get()));
+				path.pop();
+			}
+		}
+		else {
+			for index in 0..old_entries.len().max(new_entries.len()) {
+				path.push(FSOPathStep::Index(index));
+				match (old_entries.get(index), new_entries.get(index)) {
+					(Some(old_entry), Some(new_entry)) => { self.compare(path, *old_entry, *new_entry, mode); }
+					(Some(old_entry), None) => { self.push(path, FSOChange::Removed(old_entry.get())); }
+					(None, Some(new_entry)) => { self.push(path, FSOChange::Added(new_entry.get())); }
+					(None, None) => { }
+				}
+				path.pop();
+			}
+		}
+	}
+
+	pub fn to_json(&self) -> String {
+		let entries = self.0.iter().map(|difference| {
+			let path = json_string(&difference.path.to_string());
+			match &difference.change {
+				FSOChange::Added(value) => { format!("{{\"path\":{},\"change\":\"added\",\"value\":{}}}", path, json_value(value)) }
+				FSOChange::Removed(value) => { format!("{{\"path\":{},\"change\":\"removed\",\"value\":{}}}", path, json_value(value)) }
+				FSOChange::Modified { old, new } => { format!("{{\"path\":{},\"change\":\"modified\",\"old\":{},\"new\":{}}}", path, json_value(old), json_value(new)) }
+			}
+		}).collect::<Vec<String>>();
+		format!("[{}]", entries.join(","))
+	}
+}
+
+//As in the text form, spewed values lose the whitespace at the end of their lines
+fn json_value(value: &str) -> String {
+	json_string(&value.split('\n').map(str::trim_end).collect::<Vec<&str>>().join("\n"))
+}
+
+fn json_string(text: &str) -> String {
+	let mut out = String::from("\"");
+	for c in text.chars() {
+		match c {
+			'"' => { out.push_str("\\\""); }
+			'\\' => { out.push_str("\\\\"); }
+			'\n' => { out.push_str("\\n"); }
+			'\r' => { out.push_str("\\r"); }
+			'\t' => { out.push_str("\\t"); }
+			c if (c as u32) < 0x20 => { out.push_str(&format!("\\u{:04x}", c as u32)); }
+			c => { out.push(c); }
+		}
+	}
+	out.push('"');
+	out
+}
+
+fn write_lines(f: &mut Formatter<'_>, sign: char, value: &str) -> std::fmt::Result {
+	value.lines().try_for_each(|line| write!(f, "\n\t{} {}", sign, line.trim_end()))
+}
+
+//Single line values go after the path, longer ones are indented below it
+fn write_value(f: &mut Formatter<'_>, sign: char, value: &str) -> std::fmt::Result {
+	if value.contains('\n') {
+		write_lines(f, sign, value)
+	}
+	else {
+		write!(f, " {}", value)
+	}
+}
+
+impl Display for FSODifference {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match &self.change {
+			FSOChange::Added(value) => {
+				write!(f, "+ {}:", self.path)?;
+				write_value(f, '+', value)
+			}
+			FSOChange::Removed(value) => {
+				write!(f, "- {}:", self.path)?;
+				write_value(f, '-', value)
+			}
+			FSOChange::Modified { old, new } if !old.contains('\n') && !new.contains('\n') => {
+				write!(f, "~ {}: {} -> {}", self.path, old, new)
+			}
+			FSOChange::Modified { old, new } => {
+				write!(f, "~ {}:", self.path)?;
+				write_lines(f, '-', old)?;
+				write_lines(f, '+', new)
+			}
+		}
+	}
+}
+
+impl Display for FSODiff {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		self.0.iter().try_for_each(|difference| writeln!(f, "{}", difference))
+	}
+}
@@ -0,0 +1,154 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Deref, Div, Mul, Neg, Sub};
+use crate::basic_types::parse_number;
+use crate::{FSOBuilder, FSOParser, FSOParsingError, FSOParsingHangingGobble, FSOTable};
+
+//A float that remembers how it was written, i.e. 0.50, 1. or 1e-3, and spews that again for as long as its value stays the same.
+//Arithmetic on it gives plain f32s
+#[derive(Clone, Debug, Default)]
+pub struct FSOFloat {
+	pub value: f32,
+	literal: Option<Box<str>>
+}
+
+impl FSOFloat {
+	pub fn new(value: f32) -> Self {
+		FSOFloat { value, literal: None }
+	}
+
+	//The text this was parsed from, unless the value was changed since
+	pub fn literal(&self) -> Option<&str> {
+		self.literal.as_deref().filter(|literal| literal.parse::<f32>() == Ok(self.value))
+	}
+
+	fn with_literal(value: f32, literal: &str) -> Self {
+		FSOFloat { value, literal: Some(literal.into()) }
+	}
+}
+
+impl From<f32> for FSOFloat {
+	fn from(value: f32) -> Self {
+		FSOFloat::new(value)
+	}
+}
+
+impl From<FSOFloat> for f32 {
+	fn from(value: FSOFloat) -> Self {
+		value.value
+	}
+}
+
+impl Deref for FSOFloat {
+	type Target = f32;
+
+	fn deref(&self) -> &f32 {
+		&self.value
+	}
+}
+
+impl PartialEq for FSOFloat {
+	fn eq(&self, other: &Self) -> bool {
+		self.value == other.value
+	}
+}
+
+impl PartialEq<f32> for FSOFloat {
+	fn eq(&self, other: &f32) -> bool {
+		self.value == *other
+	}
+}
+
+impl PartialOrd for FSOFloat {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		self.value.partial_cmp(&other.value)
+	}
+}
+
+impl PartialOrd<f32> for FSOFloat {
+	fn partial_cmp(&self, other: &f32) -> Option<Ordering> {
+		self.value.partial_cmp(other)
+	}
+}
+
+impl Neg for FSOFloat {
+	type Output = f32;
+
+	fn neg(self) -> f32 {
+		-self.value
+	}
+}
+
+macro_rules! fso_float_operator {
+	($operator:ident, $method:ident) => {
+		impl $operator for FSOFloat {
+			type Output = f32;
+			fn $method(self, other: FSOFloat) -> f32 { self.value.$method(other.value) }
+		}
+
+		impl $operator<f32> for FSOFloat {
+			type Output = f32;
+			fn $method(self, other: f32) -> f32 { self.value.$method(other) }
+		}
+
+		impl $operator<FSOFloat> for f32 {
+			type Output = f32;
+			fn $method(self, other: FSOFloat) -> f32 { self.$method(other.value) }
+		}
+	};
+}
+
+fso_float_operator!(Add, add);
+fso_float_operator!(Sub, sub);
+fso_float_operator!(Mul, mul);
+fso_float_operator!(Div, div);
+
+impl Display for FSOFloat {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self.literal() {
+			Some(literal) => { write!(f, "{}", literal) }
+			None => { write!(f, "{}", self.value) }
+		}
+	}
+}
+
+impl FSOTable for FSOFloat {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &Parser, _hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		state.consume_whitespace_inline(&[]);
+		let current = state.get();
+		let (value, _) = parse_number::<_, f32>(state, true, true)?;
+		let literal = &current[..current.len() - state.get().len()];
+		Ok((FSOFloat::with_literal(value, literal), None))
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		match (self.literal(), state.get_state().formatting.float_precision) {
+			(Some(literal), None) => { state.append(literal); }
+			_ => { self.value.spew(state); }
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{FSOFloat, FSOParser, FSOTable, FSOTableFileParser};
+
+	#[test]
+	fn literal() {
+		for literal in ["0.50", "1.", ".5", "1e-3", "-2.5E+2", "100000000000000000000", "0.333333333333333333333333333"] {
+			let parser = FSOTableFileParser::from_string(format!("{} rest", literal));
+			let (float, _) = FSOFloat::parse(&parser, None).unwrap();
+			assert_eq!(float.value, literal.parse::<f32>().unwrap());
+			assert_eq!(float.to_string(), literal);
+			assert_eq!(parser.get(), " rest");
+		}
+
+		let parser = FSOTableFileParser::from_string("2east");
+		let (mut float, _) = FSOFloat::parse(&parser, None).unwrap();
+		assert_eq!(parser.get(), "east");
+
+		float.value = 0.25;
+		assert_eq!(float.literal(), None);
+		assert_eq!(float.to_string(), "0.25");
+	}
+}
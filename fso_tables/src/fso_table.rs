@@ -5,9 +5,11 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
 use std::io::Read;
 use std::iter::Peekable;
+use std::ops::Range;
 use std::path::Path;
 use std::str::Chars;
 use regex::Regex;
+use crate::FSOPathed;
 
 #[derive(Debug)]
 pub struct FSOParsingError {
@@ -30,6 +32,19 @@ pub trait FSOParser<'a> {
 	fn line(&self) -> usize;
 
 	fn consume(&self, count: usize);
+
+	//Parsers keeping their source let tables remember the text of each part, so that spew_round_trip can reproduce it
+	fn keeps_source(&self) -> bool {
+		false
+	}
+
+	fn position(&self) -> usize {
+		0
+	}
+
+	fn source(&self, _range: Range<usize>) -> &str {
+		""
+	}
 	
 	//Returns (comments, version_string) in this whitespace. Will break immediately after a version string
 	fn consume_whitespace(&self, stop_on_newline: bool) -> (Option<String>, Option<String>) {
@@ -37,6 +52,7 @@ pub trait FSOParser<'a> {
 		
 		let mut comments: Option<String> = None;
 		let mut version: Option<String> = None;
+		//Whether the next comment starts on a new line, rather than following a multiline comment on the same line
 		let mut linebreak_since_comment = true;
 
 		loop {
@@ -44,6 +60,8 @@ pub trait FSOParser<'a> {
 			let current = self.get();
 			
 			let add_to_comment;
+			//Single line comments are read including their line break
+			let mut single_line = true;
 			
 			let mut current_char : Peekable<Chars> = current.chars().peekable();
 			match current_char.next() {
@@ -62,14 +80,12 @@ pub trait FSOParser<'a> {
 						break;
 					}
 					else {
-						add_to_comment = format!("{}", self.read_until_target("\n", true));
-						linebreak_since_comment = true;
+						add_to_comment = self.read_until_target("\n", true).to_string();
 					}
 				}
 				Some('/') if current_char.peek().is_some_and(|c| *c == '/')=> {
 					//Comment
-					add_to_comment = format!("{}", self.read_until_target("\n", true));
-					linebreak_since_comment = true;
+					add_to_comment = self.read_until_target("\n", true).to_string();
 				}
 				Some(start @ '!') | Some(start @ '/') if current_char.peek().is_some_and(|c| *c == '*') => {
 					//Mutliline comment
@@ -78,6 +94,7 @@ pub trait FSOParser<'a> {
 					let mut target = "*".to_string();
 					target.push(start);
 					add_to_comment = format!("{}*{}*{}", start, self.read_until_target(target.as_str(), true), start);
+					single_line = false;
 				}
 				_ => { break; }
 			}
@@ -85,12 +102,12 @@ pub trait FSOParser<'a> {
 				comments = Some(String::new());
 			}
 			if let Some(comment) = &mut comments {
-				*comment = format!("{}{}{}", comment, if linebreak_since_comment { "" } else { "\n" }, add_to_comment);
-				linebreak_since_comment = false;
+				*comment = format!("{}{}{}", comment, if linebreak_since_comment && !comment.is_empty() { "\n" } else { "" }, add_to_comment);
+				linebreak_since_comment = single_line;
 			}
 		}
 		
-		return (comments, version)
+		(comments, version)
 	}
 	
 	//Consumes whitespace and whitespace-likes (such as commas, tentatively)
@@ -109,7 +126,7 @@ pub trait FSOParser<'a> {
 	
 	fn read_until_target(&self, target: &str, consume_target: bool) -> &str {
 		let current = self.get();
-		let content_size = current.find(target).unwrap_or_else(|| current.len());
+		let content_size = current.find(target).unwrap_or(current.len());
 		self.consume(content_size + if consume_target { target.len() } else { 0 });
 		&current[..content_size]
 	}
@@ -138,11 +155,24 @@ pub trait FSOParser<'a> {
 		}
 		
 		self.consume(consume_until);
-		return &current[..last_non_whitespace]
+		&current[..last_non_whitespace]
 	}
 	
+	//Consumes a comment following on the current line, if only whitespace separates it. Leaves the line break
+	fn consume_trailing_comment(&self) -> Option<String> {
+		let current = self.get();
+		let rest = current.trim_start_matches([' ', '\t']);
+		if !((rest.starts_with(';') && !rest.starts_with(";;FSO")) || rest.starts_with("//")) {
+			return None;
+		}
+		let comment = rest[..rest.find('\n').unwrap_or(rest.len())].trim_end();
+		self.consume(current.len() - rest.len() + comment.len());
+		Some(comment.to_string())
+	}
+
+	//Like FSO itself, keys match regardless of case
 	fn consume_string(&self, expect: &str) -> Result<(), FSOParsingError> {
-		if self.get().starts_with(expect) {
+		if self.get().get(..expect.len()).is_some_and(|start| start.eq_ignore_ascii_case(expect)) {
 			self.consume(expect.len());
 			Ok(())
 		}
@@ -159,17 +189,110 @@ pub enum FSOBuilderListState {
 	InlineList
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FSOLineEnding {
+	#[default]
+	Lf,
+	CrLf
+}
+
+impl FSOLineEnding {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			FSOLineEnding::Lf => { "\n" }
+			FSOLineEnding::CrLf => { "\r\n" }
+		}
+	}
+}
+
+//How spewed tables are laid out. The default reproduces the plain layout of earlier versions
+#[derive(Clone, Debug, Default)]
+pub struct FSOFormatting {
+	//Prepended once per nesting level to + options, the options nested in them and the lines of multiline lists, i.e. "\t" or "    "
+	pub indentation: String,
+	//Empty lines added between the entries of outermost multiline lists, such as the $Name: entries of a table
+	pub blank_lines_between_entries: usize,
+	pub line_ending: FSOLineEnding,
+	//Digits after the decimal point of floats. If unset, floats are spewed as short as possible
+	pub float_precision: Option<usize>,
+	pub trim_trailing_whitespace: bool,
+	//Spew the flags of flagsets in alphabetical order
+	pub sort_flags: bool
+}
+
+impl FSOFormatting {
+	//The one layout fso-fmt gives all tables, so that formatting again changes nothing
+	pub fn canonical() -> Self {
+		FSOFormatting {
+			indentation: "\t".to_string(),
+			blank_lines_between_entries: 1,
+			line_ending: FSOLineEnding::Lf,
+			float_precision: None,
+			trim_trailing_whitespace: true,
+			sort_flags: true
+		}
+	}
+}
+
 #[derive(Default)]
 pub struct FSOBuilderState {
-	pub list_state: Vec<FSOBuilderListState>
+	pub list_state: Vec<FSOBuilderListState>,
+	//If set, S-expressions containing nested lists are spewed over multiple lines, indented by this per level
+	pub sexp_indentation: Option<String>,
+	pub formatting: FSOFormatting,
+	//How many levels of indentation the current line gets, before any the key itself adds
+	pub nesting: usize,
+	//If set, table parts that are unchanged since parsing are spewed as the text they were parsed from
+	pub round_trip: bool
+}
+
+//The text a part of a table was parsed from, including the whitespace and comments before it,
+//and how that part spewed right after parsing, to tell whether it was changed since
+#[derive(Clone, Debug)]
+pub struct FSOSource {
+	pub text: String,
+	pub fingerprint: String
 }
 
 pub trait FSOBuilder {
 	fn append(&mut self, content: &str);
 
-	fn spew(self) -> String;
-
 	fn get_state(&mut self) -> &mut FSOBuilderState;
+
+	fn newline(&mut self) {
+		self.append("\n");
+	}
+
+	//Starts a new line, unless nothing was spewed on the current one yet
+	fn start_line(&mut self) {
+		self.newline();
+	}
+
+	//Indents the current line by the current nesting plus extra levels
+	fn indent(&mut self, extra: usize) {
+		let state = self.get_state();
+		let indentation = state.formatting.indentation.repeat(state.nesting + extra);
+		self.append(indentation.as_str());
+	}
+
+	//Appends a comment ending the current line. Whatever is spewed after it must start on a new line, so it isn't commented out
+	fn append_trailing_comment(&mut self, comment: &str) {
+		self.append(comment);
+		self.newline();
+	}
+
+	//Appends original text of a table part. If it starts its own line, it will, even if what was spewed before it changed
+	fn append_source(&mut self, text: &str, starts_line: bool) {
+		if starts_line && !text.starts_with(char::is_whitespace) {
+			self.newline();
+		}
+		self.append(text);
+	}
+}
+
+//Builders that keep everything spewed into them, to hand it back as one string at the end
+pub trait FSOStringBuilder: FSOBuilder {
+	fn spew(self) -> String;
 }
 
 #[derive(Default)]
@@ -180,13 +303,14 @@ struct FSOParserState {
 
 pub struct FSOTableFileParser {
 	original: String,
-	state: RefCell<FSOParserState>
+	state: RefCell<FSOParserState>,
+	keep_source: bool
 }
 impl FSOTableFileParser {
 	pub fn new(path: &Path) -> Result<Self, FSOParsingError>{
 		let mut s = String::new();
 		
-		let mut file = match File::open(&path) {
+		let mut file = match File::open(path) {
 			Ok(file) => { file }
 			Err(err) => { return Err( FSOParsingError { reason: format!("Could not open file {}! Reason: {}.", path.to_string_lossy(), err), line: 0, comments: None, version_string: None }) }
 		};
@@ -196,12 +320,21 @@ impl FSOTableFileParser {
 			Err(err) => { return Err( FSOParsingError { reason: format!("Could not read from file {}! Reason: {}.", path.to_string_lossy(), err), line: 0, comments: None, version_string: None }) }
 		};
 
-		let parser = FSOTableFileParser {
-			original: s,
-			state: RefCell::new(FSOParserState::default())
-		};
+		Ok(Self::from_string(s))
+	}
 
-		Ok(parser)
+	pub fn from_string(content: impl Into<String>) -> Self {
+		FSOTableFileParser {
+			original: content.into(),
+			state: RefCell::new(FSOParserState::default()),
+			keep_source: false
+		}
+	}
+
+	//Parse tables so that spew_round_trip gives back the parsed text
+	pub fn with_round_trip(mut self) -> Self {
+		self.keep_source = true;
+		self
 	}
 }
 
@@ -226,26 +359,161 @@ impl FSOParser<'_> for FSOTableFileParser {
 		state.pos += count;
 		state.line += newlines;
 	}
+
+	fn keeps_source(&self) -> bool {
+		self.keep_source
+	}
+
+	fn position(&self) -> usize {
+		self.state.borrow().pos
+	}
+
+	fn source(&self, range: Range<usize>) -> &str {
+		&self.original[range]
+	}
 }
 
 #[derive(Default)]
 pub struct FSOTableBuilder {
 	buffer: String,
-	state: FSOBuilderState
+	state: FSOBuilderState,
+	//Indentation is only written once something follows on the line, so that empty lines stay empty
+	pending_indentation: Option<String>,
+	//Set while the buffer ends in original whitespace, which replaces the line break and indentation spewed next
+	after_source_whitespace: bool,
+	//Set while the current line ends in a comment
+	after_trailing_comment: bool,
+	//Set once the start of the buffer was handed out to be written elsewhere
+	drained: bool
 }
 
-impl FSOBuilder for FSOTableBuilder {
-	fn append(&mut self, content: &str) {
-		self.buffer.push_str(content);
+impl FSOTableBuilder {
+	pub fn new(formatting: FSOFormatting) -> Self {
+		FSOTableBuilder { state: FSOBuilderState { formatting, ..FSOBuilderState::default() }, ..FSOTableBuilder::default() }
 	}
 
-	fn spew(self) -> String {
-		self.buffer
+	fn finish_buffer(&mut self) {
+		if self.state.formatting.trim_trailing_whitespace {
+			self.end_line();
+		}
+	}
+
+	fn at_start(&self) -> bool {
+		self.buffer.is_empty() && !self.drained
+	}
+
+	//Takes everything up to the trailing whitespace out of the buffer. Later appends only ever change the whitespace at its end
+	pub(crate) fn drain_settled(&mut self) -> String {
+		let settled = self.buffer.trim_end().len();
+		if settled > 0 {
+			self.drained = true;
+		}
+		self.buffer.drain(..settled).collect()
+	}
+
+	//Round trips never leave trailing whitespace behind on lines they spew themselves
+	fn end_line(&mut self) {
+		if self.state.formatting.trim_trailing_whitespace || (self.state.round_trip && !self.after_source_whitespace) {
+			self.buffer.truncate(self.buffer.trim_end_matches([' ', '\t', '\r']).len());
+		}
+	}
+}
+
+impl FSOBuilder for FSOTableBuilder {
+	fn append(&mut self, content: &str) {
+		let content = if self.after_source_whitespace { content.trim_start_matches(['\r', '\n']) } else { content };
+		if content.is_empty() {
+			return;
+		}
+		self.after_source_whitespace = false;
+		if self.after_trailing_comment {
+			//Spaces meant to separate something from the comment aren't needed, anything else goes to the next line
+			if content.trim_start_matches([' ', '\t']).is_empty() {
+				return;
+			}
+			self.after_trailing_comment = false;
+			if !content.starts_with(['\r', '\n']) {
+				self.end_line();
+				self.buffer.push_str(self.state.formatting.line_ending.as_str());
+			}
+		}
+		let mut lines = content.split('\n').peekable();
+		let mut first = true;
+		while let Some(line) = lines.next() {
+			if !first {
+				self.pending_indentation = None;
+				self.end_line();
+				self.buffer.push_str(self.state.formatting.line_ending.as_str());
+			}
+			first = false;
+			//Text taken over from CRLF files, such as comments, brings its own line endings
+			let line = if lines.peek().is_some() { line.strip_suffix('\r').unwrap_or(line) } else { line };
+			if !line.is_empty() {
+				if let Some(indentation) = self.pending_indentation.take() {
+					self.buffer.push_str(indentation.as_str());
+				}
+				self.buffer.push_str(line);
+			}
+		}
 	}
 
 	fn get_state(&mut self) -> &mut FSOBuilderState {
 		&mut self.state
 	}
+
+	fn start_line(&mut self) {
+		if !self.after_source_whitespace && !self.at_start() && !self.buffer.ends_with('\n') {
+			self.newline();
+		}
+	}
+
+	fn indent(&mut self, extra: usize) {
+		if self.after_source_whitespace {
+			return;
+		}
+		self.pending_indentation = Some(self.state.formatting.indentation.repeat(self.state.nesting + extra));
+	}
+
+	fn append_trailing_comment(&mut self, comment: &str) {
+		if !self.buffer.ends_with([' ', '\t', '\n']) {
+			self.append(" ");
+		}
+		self.append(comment);
+		self.after_trailing_comment = true;
+	}
+
+	fn append_source(&mut self, text: &str, starts_line: bool) {
+		if std::mem::take(&mut self.after_trailing_comment) && !text.starts_with(['\r', '\n']) {
+			self.buffer.push_str(self.state.formatting.line_ending.as_str());
+		}
+		//Original text brings its own indentation and line endings. Its whitespace replaces what was spewed right before it
+		self.pending_indentation = None;
+		if text.starts_with(char::is_whitespace) && self.after_source_whitespace {
+			//The whitespace of a changed part, of which this is the inner part
+			self.buffer.truncate(self.buffer.trim_end().len());
+		}
+		else if text.starts_with(char::is_whitespace) {
+			if text.starts_with(['\r', '\n']) && self.buffer.ends_with('\n') {
+				self.buffer.pop();
+				if self.buffer.ends_with('\r') {
+					self.buffer.pop();
+				}
+			}
+			self.buffer.truncate(self.buffer.trim_end_matches([' ', '\t']).len());
+		}
+		self.after_source_whitespace = !text.is_empty() && text.trim_start().is_empty();
+		if starts_line && !self.at_start() && !self.buffer.ends_with(char::is_whitespace) && !text.starts_with(char::is_whitespace) {
+			self.buffer.push_str(self.state.formatting.line_ending.as_str());
+		}
+		self.buffer.push_str(text);
+	}
+}
+
+impl FSOStringBuilder for FSOTableBuilder {
+	fn spew(mut self) -> String {
+		self.finish_buffer();
+		self.buffer
+	}
 }
 
 pub struct FSOParsingHangingGobble {
@@ -256,4 +524,37 @@ pub struct FSOParsingHangingGobble {
 pub trait FSOTable {
 	fn parse<'parser, Parser: FSOParser<'parser>>(state: &'parser Parser, hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> where Self: Sized;
 	fn spew(&self, state: &mut impl FSOBuilder);
+
+	//The field of this table with the given rust name, for paths into it. Plain values have none
+	fn field(&self, _name: &str) -> Option<&dyn FSOPathed> {
+		None
+	}
+
+	fn field_mut(&mut self, _name: &str) -> Option<&mut dyn FSOPathed> {
+		None
+	}
+
+	//The rust names of the fields paths can lead into, in declaration order
+	fn field_names(&self) -> Vec<&'static str> {
+		Vec::new()
+	}
+
+	//The name of the current flag, for flagsets
+	fn flag_name(&self) -> Option<&'static str> {
+		None
+	}
+
+	//The rust name of the current variant, for enums
+	fn variant(&self) -> Option<&'static str> {
+		None
+	}
+
+	//Tables with a single field stand for it in paths, i.e. a subtable wrapping a list can be indexed directly
+	fn only_field(&self) -> Option<&dyn FSOPathed> {
+		None
+	}
+
+	fn only_field_mut(&mut self) -> Option<&mut dyn FSOPathed> {
+		None
+	}
 }
\ No newline at end of file
@@ -1,5 +1,19 @@
 mod fso_table;
 mod basic_types;
+mod diff;
+mod float;
+mod merge;
+mod path;
+mod respew;
+mod sexp;
+mod stream;
 
 pub use fso_tables_macro::*;
 pub use fso_table::*;
+pub use diff::*;
+pub use float::*;
+pub use merge::*;
+pub use path::*;
+pub use respew::*;
+pub use sexp::*;
+pub use stream::*;
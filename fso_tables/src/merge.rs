@@ -0,0 +1,187 @@
+use std::fmt::{Display, Formatter};
+use crate::{FSOChange, FSODiff, FSODifference, FSOParsingError, FSOPath, FSOPathStep, FSOPathed, FSOShape};
+
+//A value both sides changed differently. Merging keeps ours
+#[derive(Clone, Debug, PartialEq)]
+pub struct FSOConflict {
+	pub path: FSOPath,
+	pub base: Option<String>,
+	pub ours: Option<String>,
+	pub theirs: Option<String>
+}
+
+impl Display for FSOConflict {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "! {}:", self.path)?;
+		for (side, value) in [("base", &self.base), ("ours", &self.ours), ("theirs", &self.theirs)] {
+			match value {
+				Some(value) if value.contains('\n') => {
+					write!(f, "\n\t{}:", side)?;
+					value.lines().try_for_each(|line| write!(f, "\n\t\t{}", line.trim_end()))?;
+				}
+				Some(value) => { write!(f, "\n\t{}: {}", side, value)?; }
+				None => { write!(f, "\n\t{}: (none)", side)?; }
+			}
+		}
+		Ok(())
+	}
+}
+
+//The entries of the list a value is or wraps, as paths see them
+fn list_entries(value: &dyn FSOPathed) -> Option<Vec<&dyn FSOPathed>> {
+	match value.shape() {
+		FSOShape::List(entries) => { Some(entries) }
+		FSOShape::Table { fields, .. } if fields.len() == 1 => { list_entries(fields[0].1) }
+		_ => { None }
+	}
+}
+
+//Where each base entry of a list without keys went in ours. Entries that stayed the same are matched up in order,
+//the changed ones in between by position if both sides have as many of them. Entries ours removed, or can't be told apart, map to nothing
+pub(crate) fn align(base: &[&dyn FSOPathed], ours: &[&dyn FSOPathed]) -> Vec<Option<usize>> {
+	let (base, ours) = (base.iter().map(|entry| entry.get()).collect::<Vec<String>>(), ours.iter().map(|entry| entry.get()).collect::<Vec<String>>());
+	let mut common = vec![vec![0usize; ours.len() + 1]; base.len() + 1];
+	for i in (0..base.len()).rev() {
+		for j in (0..ours.len()).rev() {
+			common[i][j] = if base[i] == ours[j] { common[i + 1][j + 1] + 1 } else { common[i + 1][j].max(common[i][j + 1]) };
+		}
+	}
+
+	//The entries that stayed the same, ending with both ends of the lists
+	let mut anchors = Vec::new();
+	let (mut i, mut j) = (0, 0);
+	while i < base.len() && j < ours.len() {
+		if base[i] == ours[j] {
+			anchors.push((i, j));
+			(i, j) = (i + 1, j + 1);
+		}
+		else if common[i + 1][j] >= common[i][j + 1] {
+			i += 1;
+		}
+		else {
+			j += 1;
+		}
+	}
+	anchors.push((base.len(), ours.len()));
+
+	let mut mapping = vec![None; base.len()];
+	let mut gap_start = (0, 0);
+	for (i, j) in anchors {
+		if i - gap_start.0 == j - gap_start.1 {
+			(gap_start.0..i).zip(gap_start.1..j).for_each(|(i, j)| mapping[i] = Some(j));
+		}
+		if i < base.len() {
+			mapping[i] = Some(j);
+		}
+		gap_start = (i + 1, j + 1);
+	}
+	mapping
+}
+
+//The path in ours that leads to what the path leads to in base. Entries of lists without keys may have moved in ours, entries added at the end of a list in base go to the end of it in ours
+fn remap(path: &FSOPath, base: &dyn FSOPathed, ours: &dyn FSOPathed) -> Option<FSOPath> {
+	let mut steps = Vec::new();
+	let (mut base, mut ours) = (Some(base), Some(ours));
+	for step in &path.0 {
+		let remapped = match step {
+			FSOPathStep::Index(index) => {
+				let (base_entries, our_entries) = (list_entries(base?)?, list_entries(ours?)?);
+				let mapping = align(&base_entries, &our_entries);
+				match index.checked_sub(base_entries.len()) {
+					Some(past_end) => {
+						let end = if base_entries.is_empty() { Some(0) } else { mapping[base_entries.len() - 1].map(|last| last + 1) };
+						FSOPathStep::Index(end? + past_end)
+					}
+					None => { FSOPathStep::Index(mapping[*index]?) }
+				}
+			}
+			_ => { step.clone() }
+		};
+		base = base.and_then(|base| base.step(step));
+		ours = ours.and_then(|ours| ours.step(&remapped));
+		steps.push(remapped);
+	}
+	Some(FSOPath(steps))
+}
+
+fn apply(ours: &mut dyn FSOPathed, path: &FSOPath, change: &FSOChange) -> Result<(), FSOParsingError> {
+	match change {
+		FSOChange::Modified { new, .. } => { path.set(ours, new) }
+		FSOChange::Removed(_) => { path.remove(ours) }
+		//New list entries found by name go to the end of the list, others where they were added
+		FSOChange::Added(value) => match path.0.split_last() {
+			Some((FSOPathStep::Named { .. }, parent)) => { FSOPath(parent.to_vec()).insert(ours, value) }
+			_ => { path.insert(ours, value) }
+		}
+	}
+}
+
+//Which value a merge leaves where both sides changed one differently
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FSOResolution {
+	#[default]
+	Ours,
+	Theirs,
+	Base
+}
+
+//Merges the changes from base to theirs into ours, entry by entry and field by field.
+//Changes to a value that ours changed as well are left out and returned as conflicts, unless both made the same one.
+//Entries of lists without keys are followed to where ours moved them, see align
+pub fn fso_merge(base: &dyn FSOPathed, ours: &mut dyn FSOPathed, theirs: &dyn FSOPathed) -> Vec<FSOConflict> {
+	fso_merge_resolving(base, ours, theirs, FSOResolution::Ours)
+}
+
+//As fso_merge, but conflicting values are set to the given side's where ours still has a place for them.
+//Merging with each resolution gives tables that only differ in their conflicts
+pub fn fso_merge_resolving(base: &dyn FSOPathed, ours: &mut dyn FSOPathed, theirs: &dyn FSOPathed, resolution: FSOResolution) -> Vec<FSOConflict> {
+	//Paths of their changes are those in base, so that entries both sides removed from a list without keys are still the same ones
+	let their_changes = FSODiff::between_aligned(base, theirs);
+	let mut conflicts = Vec::new();
+
+	//Removals by index go last to first, so they don't shift the entries still to be removed
+	let (removals, changes): (Vec<&FSODifference>, Vec<&FSODifference>) = their_changes.0.iter().partition(|difference| matches!(difference.change, FSOChange::Removed(_)));
+	for difference in changes.into_iter().chain(removals.into_iter().rev()) {
+		let remapped = remap(&difference.path, base, ours);
+		let mut our_value = remapped.as_ref().and_then(|path| path.get(ours).ok());
+		let mut base_value = difference.path.get(base).ok();
+		//Entries added in front of one in base take nobody's place. Ours made the same change if it has the entry in front of it as well
+		if let (FSOChange::Added(value), Some(_), Some(path)) = (&difference.change, &base_value, &remapped) {
+			let in_front = |path: &FSOPath, table: &dyn FSOPathed| match path.0.split_last() {
+				Some((FSOPathStep::Index(index), parent)) if *index > 0 => {
+					FSOPath(parent.iter().cloned().chain([FSOPathStep::Index(index - 1)]).collect()).get(table).ok()
+				}
+				_ => { None }
+			};
+			let already_added = in_front(path, ours).as_ref() == Some(value) && in_front(&difference.path, base).as_ref() != Some(value);
+			(base_value, our_value) = (None, if already_added { Some(value.clone()) } else { None });
+		}
+		let their_value = match &difference.change {
+			FSOChange::Added(value) | FSOChange::Modified { new: value, .. } => { Some(value.clone()) }
+			FSOChange::Removed(_) => { None }
+		};
+		match remapped {
+			//Ours already made the same change, which for removals means ours has nothing there either
+			_ if our_value == their_value => { }
+			Some(path) if our_value == base_value && apply(ours, &path, &difference.change).is_ok() => { }
+			_ => {
+				let resolved = match resolution {
+					FSOResolution::Ours => { None }
+					FSOResolution::Theirs => { Some(&their_value) }
+					FSOResolution::Base => { Some(&base_value) }
+				};
+				if let (Some(resolved), Some(path)) = (resolved, &remapped) {
+					//What can't be resolved stays as ours
+					let _ = match (resolved, &our_value) {
+						(Some(value), Some(_)) => { path.set(ours, value) }
+						(None, Some(_)) => { path.remove(ours) }
+						(Some(value), None) => { apply(ours, path, &FSOChange::Added(value.clone())) }
+						(None, None) => { Ok(()) }
+					};
+				}
+				conflicts.push(FSOConflict { path: difference.path.clone(), base: base_value, ours: our_value, theirs: their_value });
+			}
+		}
+	}
+	conflicts
+}
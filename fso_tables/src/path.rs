@@ -0,0 +1,380 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::{FSOParser, FSOParsingError, FSOStringBuilder, FSOTable, FSOTableBuilder, FSOTableFileParser};
+
+fn path_error(reason: String) -> FSOParsingError {
+	FSOParsingError { line: 0, reason, comments: None, version_string: None }
+}
+
+//One step into a table: a field by its rust name, a list entry by index, or the list entry whose key field spews as the value
+#[derive(Clone, Debug, PartialEq)]
+pub enum FSOPathStep {
+	Field(String),
+	Index(usize),
+	Named { key: String, value: String }
+}
+
+impl Display for FSOPathStep {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FSOPathStep::Field(name) => { write!(f, ".{}", name) }
+			FSOPathStep::Index(index) => { write!(f, "[{}]", index) }
+			FSOPathStep::Named { key, value } => { write!(f, "[{}={}]", key, value) }
+		}
+	}
+}
+
+//A path to a value in a table, as in animations[name=Door].segment.time or curves[0].keyframes[1].pos.1
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct FSOPath(pub Vec<FSOPathStep>);
+
+impl FromStr for FSOPath {
+	type Err = FSOParsingError;
+
+	fn from_str(path: &str) -> Result<Self, Self::Err> {
+		let mut steps = Vec::new();
+		let mut rest = path.trim();
+		while !rest.is_empty() {
+			if let Some(inner) = rest.strip_prefix('[') {
+				let end = inner.find(']').ok_or_else(|| path_error(format!("Unclosed [ in path {}.", path)))?;
+				let step = &inner[..end];
+				steps.push(match step.split_once('=') {
+					Some((key, value)) => { FSOPathStep::Named { key: key.trim().to_string(), value: value.trim().to_string() } }
+					None => { FSOPathStep::Index(step.trim().parse().map_err(|_| path_error(format!("Expected an index or key=value, got [{}] in path {}.", step, path)))?) }
+				});
+				rest = &inner[end + 1..];
+			}
+			else {
+				let field = if steps.is_empty() { rest } else { rest.strip_prefix('.').ok_or_else(|| path_error(format!("Expected . or [ before {} in path {}.", rest, path)))? };
+				let end = field.find(['.', '[']).unwrap_or(field.len());
+				if field[..end].trim().is_empty() {
+					return Err(path_error(format!("Expected a field name in path {}.", path)));
+				}
+				steps.push(FSOPathStep::Field(field[..end].trim().to_string()));
+				rest = &field[end..];
+			}
+		}
+		Ok(FSOPath(steps))
+	}
+}
+
+impl Display for FSOPath {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		for (i, step) in self.0.iter().enumerate() {
+			match step {
+				FSOPathStep::Field(name) if i == 0 => { write!(f, "{}", name)?; }
+				_ => { write!(f, "{}", step)?; }
+			}
+		}
+		Ok(())
+	}
+}
+
+impl FSOPath {
+	fn resolve<'a>(&self, steps: &[FSOPathStep], root: &'a dyn FSOPathed) -> Result<&'a dyn FSOPathed, FSOParsingError> {
+		steps.iter().try_fold(root, |value, step| value.step(step).ok_or_else(|| path_error(format!("Nothing at {} in path {}.", step, self))))
+	}
+
+	fn resolve_mut<'a>(&self, steps: &[FSOPathStep], root: &'a mut dyn FSOPathed) -> Result<&'a mut dyn FSOPathed, FSOParsingError> {
+		steps.iter().try_fold(root, |value, step| value.step_mut(step).ok_or_else(|| path_error(format!("Nothing at {} in path {}.", step, self))))
+	}
+
+	//The value at the path, as it would be spewed
+	pub fn get(&self, root: &dyn FSOPathed) -> Result<String, FSOParsingError> {
+		self.resolve(&self.0, root).map(|value| value.get())
+	}
+
+	//Replaces the value at the path with one parsed from text
+	pub fn set(&self, root: &mut dyn FSOPathed, value: &str) -> Result<(), FSOParsingError> {
+		self.resolve_mut(&self.0, root)?.set(value)
+	}
+
+	//Adds a list entry parsed from text. A path ending in an index inserts before that entry, any other appends to the list it leads to
+	pub fn insert(&self, root: &mut dyn FSOPathed, value: &str) -> Result<(), FSOParsingError> {
+		match self.0.split_last() {
+			Some((FSOPathStep::Index(index), parent)) => { self.resolve_mut(parent, root)?.insert(Some(*index), value) }
+			_ => { self.resolve_mut(&self.0, root)?.insert(None, value) }
+		}
+	}
+
+	//Removes a list entry, or unsets an optional value
+	pub fn remove(&self, root: &mut dyn FSOPathed) -> Result<(), FSOParsingError> {
+		let (last, parent) = self.0.split_last().ok_or_else(|| path_error("Cannot remove the whole table.".to_string()))?;
+		self.resolve_mut(parent, root)?.remove(last)
+	}
+}
+
+//How a value is made up, for walking through tables without knowing their types
+pub enum FSOShape<'a> {
+	Value,
+	Absent,
+	Table { variant: Option<&'static str>, fields: Vec<(&'static str, &'a dyn FSOPathed)> },
+	List(Vec<&'a dyn FSOPathed>)
+}
+
+//Values that paths can lead into and edit. All tables are, as well as lists, optional values and pairs of them
+pub trait FSOPathed {
+	fn step(&self, step: &FSOPathStep) -> Option<&dyn FSOPathed>;
+
+	fn step_mut(&mut self, step: &FSOPathStep) -> Option<&mut dyn FSOPathed>;
+
+	//The value as it would be spewed
+	fn get(&self) -> String;
+
+	fn set(&mut self, value: &str) -> Result<(), FSOParsingError>;
+
+	fn insert(&mut self, _index: Option<usize>, _value: &str) -> Result<(), FSOParsingError> {
+		Err(path_error("Only lists and optional values can have values inserted.".to_string()))
+	}
+
+	fn remove(&mut self, step: &FSOPathStep) -> Result<(), FSOParsingError> {
+		self.step_mut(step).ok_or_else(|| path_error(format!("Nothing at {} to remove.", step)))?.clear()
+	}
+
+	//Empties lists and unsets optional values
+	fn clear(&mut self) -> Result<(), FSOParsingError> {
+		Err(path_error("Only list entries and optional values can be removed.".to_string()))
+	}
+
+	fn shape(&self) -> FSOShape<'_> {
+		FSOShape::Value
+	}
+
+	//The name of the current flag, for entries of flagsets
+	fn flag_name(&self) -> Option<&'static str> {
+		None
+	}
+
+	fn parse_value<'parser, Parser: FSOParser<'parser>>(state: &'parser Parser) -> Result<Self, FSOParsingError> where Self: Sized;
+
+	fn get_path(&self, path: &str) -> Result<String, FSOParsingError> where Self: Sized {
+		path.parse::<FSOPath>()?.get(self)
+	}
+
+	fn set_path(&mut self, path: &str, value: &str) -> Result<(), FSOParsingError> where Self: Sized {
+		path.parse::<FSOPath>()?.set(self, value)
+	}
+
+	fn insert_path(&mut self, path: &str, value: &str) -> Result<(), FSOParsingError> where Self: Sized {
+		path.parse::<FSOPath>()?.insert(self, value)
+	}
+
+	fn remove_path(&mut self, path: &str) -> Result<(), FSOParsingError> where Self: Sized {
+		path.parse::<FSOPath>()?.remove(self)
+	}
+}
+
+//Parses a whole text as a value, failing if anything but whitespace and comments is left over
+fn parse_text<T: FSOPathed>(text: &str) -> Result<T, FSOParsingError> {
+	let parser = FSOTableFileParser::from_string(text);
+	let value = T::parse_value(&parser)?;
+	parser.consume_whitespace(false);
+	if !parser.get().is_empty() {
+		return Err(path_error(format!("Unexpected \"{}\" after the value.", parser.get().lines().next().unwrap_or_default())));
+	}
+	Ok(value)
+}
+
+impl<T: FSOTable> FSOPathed for T {
+	fn step(&self, step: &FSOPathStep) -> Option<&dyn FSOPathed> {
+		match step {
+			FSOPathStep::Field(name) if FSOTable::field(self, name).is_some() => { FSOTable::field(self, name) }
+			_ => { FSOTable::only_field(self)?.step(step) }
+		}
+	}
+
+	fn step_mut(&mut self, step: &FSOPathStep) -> Option<&mut dyn FSOPathed> {
+		match step {
+			FSOPathStep::Field(name) if FSOTable::field(self, name).is_some() => { FSOTable::field_mut(self, name) }
+			_ => { FSOTable::only_field_mut(self)?.step_mut(step) }
+		}
+	}
+
+	fn get(&self) -> String {
+		let mut builder = FSOTableBuilder::default();
+		self.spew(&mut builder);
+		builder.spew().trim().to_string()
+	}
+
+	fn set(&mut self, value: &str) -> Result<(), FSOParsingError> {
+		*self = parse_text(value)?;
+		Ok(())
+	}
+
+	fn insert(&mut self, index: Option<usize>, value: &str) -> Result<(), FSOParsingError> {
+		FSOTable::only_field_mut(self).ok_or_else(|| path_error("Only lists and optional values can have values inserted.".to_string()))?.insert(index, value)
+	}
+
+	fn remove(&mut self, step: &FSOPathStep) -> Result<(), FSOParsingError> {
+		match step {
+			FSOPathStep::Field(name) if FSOTable::field(self, name).is_some() => { FSOTable::field_mut(self, name).unwrap().clear() }
+			_ => { FSOTable::only_field_mut(self).ok_or_else(|| path_error(format!("Nothing at {} to remove.", step)))?.remove(step) }
+		}
+	}
+
+	fn shape(&self) -> FSOShape<'_> {
+		let fields = FSOTable::field_names(self).into_iter().filter_map(|name| Some((name, FSOTable::field(self, name)?))).collect::<Vec<_>>();
+		if fields.is_empty() {
+			FSOShape::Value
+		}
+		else {
+			FSOShape::Table { variant: FSOTable::variant(self), fields }
+		}
+	}
+
+	fn flag_name(&self) -> Option<&'static str> {
+		FSOTable::flag_name(self)
+	}
+
+	fn parse_value<'parser, Parser: FSOParser<'parser>>(state: &'parser Parser) -> Result<Self, FSOParsingError> {
+		T::parse(state, None).map(|(value, _)| value)
+	}
+}
+
+fn position_in<T: FSOPathed>(entries: &[T], step: &FSOPathStep) -> Option<usize> {
+	match step {
+		FSOPathStep::Index(index) => { (*index < entries.len()).then_some(*index) }
+		FSOPathStep::Named { key, value } => {
+			let key = FSOPathStep::Field(key.clone());
+			entries.iter().position(|entry| entry.step(&key).is_some_and(|field| field.get() == *value))
+		}
+		FSOPathStep::Field(_) => { None }
+	}
+}
+
+impl<T: FSOPathed> FSOPathed for Vec<T> {
+	fn step(&self, step: &FSOPathStep) -> Option<&dyn FSOPathed> {
+		position_in(self, step).map(|index| &self[index] as &dyn FSOPathed)
+	}
+
+	fn step_mut(&mut self, step: &FSOPathStep) -> Option<&mut dyn FSOPathed> {
+		position_in(self, step).map(|index| &mut self[index] as &mut dyn FSOPathed)
+	}
+
+	fn get(&self) -> String {
+		self.iter().map(|entry| entry.get()).collect::<Vec<String>>().join("\n")
+	}
+
+	fn set(&mut self, _value: &str) -> Result<(), FSOParsingError> {
+		Err(path_error("Lists cannot be set as a whole, insert or remove their entries instead.".to_string()))
+	}
+
+	fn insert(&mut self, index: Option<usize>, value: &str) -> Result<(), FSOParsingError> {
+		let index = index.unwrap_or(self.len());
+		if index > self.len() {
+			return Err(path_error(format!("Cannot insert at {} into a list of {} entries.", index, self.len())));
+		}
+		Vec::insert(self, index, parse_text(value)?);
+		Ok(())
+	}
+
+	fn remove(&mut self, step: &FSOPathStep) -> Result<(), FSOParsingError> {
+		let index = position_in(self, step).ok_or_else(|| path_error(format!("Nothing at {} to remove.", step)))?;
+		Vec::remove(self, index);
+		Ok(())
+	}
+
+	fn clear(&mut self) -> Result<(), FSOParsingError> {
+		Vec::clear(self);
+		Ok(())
+	}
+
+	fn shape(&self) -> FSOShape<'_> {
+		FSOShape::List(self.iter().map(|entry| entry as &dyn FSOPathed).collect())
+	}
+
+	fn parse_value<'parser, Parser: FSOParser<'parser>>(state: &'parser Parser) -> Result<Self, FSOParsingError> {
+		let mut entries = Vec::new();
+		state.consume_whitespace_inline(&['(']);
+		state.consume_whitespace(false);
+		//Entries go on until the end of the text or of an inline list, anything in between that doesn't parse is an error
+		while !state.get().is_empty() && !state.get().starts_with(')') {
+			let remaining = state.get().len();
+			let entry = T::parse_value(state)?;
+			if state.get().len() == remaining {
+				return Err(path_error(format!("Expected a list entry, got \"{}\".", state.get().lines().next().unwrap_or_default())));
+			}
+			entries.push(entry);
+			state.consume_whitespace(false);
+		}
+		state.consume_whitespace_inline(&[')']);
+		Ok(entries)
+	}
+}
+
+impl<T: FSOPathed> FSOPathed for Option<T> {
+	fn step(&self, step: &FSOPathStep) -> Option<&dyn FSOPathed> {
+		self.as_ref()?.step(step)
+	}
+
+	fn step_mut(&mut self, step: &FSOPathStep) -> Option<&mut dyn FSOPathed> {
+		self.as_mut()?.step_mut(step)
+	}
+
+	fn get(&self) -> String {
+		self.as_ref().map(|value| value.get()).unwrap_or_default()
+	}
+
+	fn set(&mut self, value: &str) -> Result<(), FSOParsingError> {
+		*self = Some(parse_text(value)?);
+		Ok(())
+	}
+
+	fn insert(&mut self, _index: Option<usize>, value: &str) -> Result<(), FSOParsingError> {
+		self.set(value)
+	}
+
+	fn remove(&mut self, step: &FSOPathStep) -> Result<(), FSOParsingError> {
+		self.as_mut().ok_or_else(|| path_error(format!("Nothing at {} to remove.", step)))?.remove(step)
+	}
+
+	fn clear(&mut self) -> Result<(), FSOParsingError> {
+		*self = None;
+		Ok(())
+	}
+
+	fn shape(&self) -> FSOShape<'_> {
+		self.as_ref().map_or(FSOShape::Absent, |value| value.shape())
+	}
+
+	fn parse_value<'parser, Parser: FSOParser<'parser>>(state: &'parser Parser) -> Result<Self, FSOParsingError> {
+		T::parse_value(state).map(Some)
+	}
+}
+
+impl<A: FSOPathed, B: FSOPathed> FSOPathed for (A, B) {
+	fn step(&self, step: &FSOPathStep) -> Option<&dyn FSOPathed> {
+		match step {
+			FSOPathStep::Index(0) => { Some(&self.0) }
+			FSOPathStep::Index(1) => { Some(&self.1) }
+			FSOPathStep::Field(name) => { self.step(&FSOPathStep::Index(name.parse().ok()?)) }
+			_ => { None }
+		}
+	}
+
+	fn step_mut(&mut self, step: &FSOPathStep) -> Option<&mut dyn FSOPathed> {
+		match step {
+			FSOPathStep::Index(0) => { Some(&mut self.0) }
+			FSOPathStep::Index(1) => { Some(&mut self.1) }
+			FSOPathStep::Field(name) => { self.step_mut(&FSOPathStep::Index(name.parse().ok()?)) }
+			_ => { None }
+		}
+	}
+
+	fn get(&self) -> String {
+		format!("({}, {})", self.0.get(), self.1.get())
+	}
+
+	fn set(&mut self, value: &str) -> Result<(), FSOParsingError> {
+		*self = parse_text(value)?;
+		Ok(())
+	}
+
+	fn parse_value<'parser, Parser: FSOParser<'parser>>(state: &'parser Parser) -> Result<Self, FSOParsingError> {
+		state.consume_whitespace_inline(&['(']);
+		let first = A::parse_value(state)?;
+		let second = B::parse_value(state)?;
+		state.consume_whitespace_inline(&[')']);
+		Ok((first, second))
+	}
+}
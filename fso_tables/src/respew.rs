@@ -0,0 +1,67 @@
+use std::fmt::{Display, Formatter};
+use crate::{FSODiff, FSOFormatting, FSOParser, FSOParsingError, FSOStringBuilder, FSOTable, FSOTableBuilder, FSOTableFileParser};
+
+//Why spewing a value and parsing the result did not give back the same value
+#[derive(Debug)]
+pub enum FSORespewProblem {
+	Unparseable { spewed: String, error: FSOParsingError },
+	Leftover { spewed: String, rest: String },
+	Changed { spewed: String, diff: FSODiff }
+}
+
+impl FSORespewProblem {
+	pub fn spewed(&self) -> &str {
+		match self {
+			FSORespewProblem::Unparseable { spewed, .. } | FSORespewProblem::Leftover { spewed, .. } | FSORespewProblem::Changed { spewed, .. } => { spewed }
+		}
+	}
+}
+
+impl Display for FSORespewProblem {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FSORespewProblem::Unparseable { error, .. } => { write!(f, "The spewed table does not parse: {}", error)?; }
+			FSORespewProblem::Leftover { rest, .. } => { write!(f, "Parsing the spewed table stopped before \"{}\"", rest.lines().next().unwrap_or_default())?; }
+			FSORespewProblem::Changed { diff, .. } => { write!(f, "The spewed table parses differently:\n{}", diff)?; }
+		}
+		write!(f, "\nSpewed table:\n{}", self.spewed())
+	}
+}
+
+//Shows a table by its spew where Debug is needed, e.g. in test failures
+#[derive(Clone)]
+pub struct FSOSpewed<T: FSOTable>(pub T);
+
+impl<T: FSOTable> std::fmt::Debug for FSOSpewed<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let mut builder = FSOTableBuilder::new(FSOFormatting::default());
+		self.0.spew(&mut builder);
+		write!(f, "{}", builder.spew())
+	}
+}
+
+//Spews a value, parses the result and checks that it is the same value again, to find spew output that FSO would misread.
+//With sort_flags, flagsets come back sorted, so the order of their flags is not compared
+pub fn fso_check_respew<T: FSOTable>(value: &T, formatting: FSOFormatting) -> Result<(), FSORespewProblem> {
+	let sort_flags = formatting.sort_flags;
+	let mut builder = FSOTableBuilder::new(formatting);
+	value.spew(&mut builder);
+	let spewed = builder.spew();
+
+	let parser = FSOTableFileParser::from_string(spewed.as_str());
+	let reparsed = match T::parse(&parser, None) {
+		Ok((reparsed, _)) => { reparsed }
+		Err(error) => { return Err(FSORespewProblem::Unparseable { spewed, error }); }
+	};
+	parser.consume_whitespace(false);
+	if !parser.get().is_empty() {
+		let rest = parser.get().to_string();
+		return Err(FSORespewProblem::Leftover { spewed, rest });
+	}
+
+	let diff = if sort_flags { FSODiff::between_unordered_flags(value, &reparsed) } else { FSODiff::between(value, &reparsed) };
+	if !diff.is_empty() {
+		return Err(FSORespewProblem::Changed { spewed, diff });
+	}
+	Ok(())
+}
@@ -0,0 +1,241 @@
+use std::fmt::{Display, Formatter};
+use crate::{FSOBuilder, FSOParser, FSOParsingError, FSOParsingHangingGobble, FSOTable};
+
+//An S-expression, as used by missions and some tables, i.e. ( when ( true ) ( send-message "#Command" "High" "Msg1" ) )
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sexp {
+	List(Vec<Sexp>),
+	//Unquoted tokens, such as operator names
+	Atom(String),
+	String(String),
+	Number(i64),
+	//@name[default], quoted for string variables and unquoted for number variables
+	Variable { name: String, default: Option<String>, quoted: bool },
+	//Comments within a list are kept in place, so that they can be spewed where they were
+	Comment(String)
+}
+
+impl Sexp {
+	pub fn operator(&self) -> Option<&str> {
+		match self.elements().first() {
+			Some(Sexp::Atom(operator)) => { Some(operator.as_str()) }
+			_ => { None }
+		}
+	}
+
+	//The elements of a list without comments, including the operator
+	pub fn elements(&self) -> Vec<&Sexp> {
+		match self {
+			Sexp::List(list) => { list.iter().filter(|element| !matches!(element, Sexp::Comment(_))).collect() }
+			_ => { Vec::new() }
+		}
+	}
+
+	//The elements of a list without comments and without the operator
+	pub fn arguments(&self) -> Vec<&Sexp> {
+		let mut elements = self.elements();
+		if self.operator().is_some() {
+			elements.remove(0);
+		}
+		elements
+	}
+
+	fn parse_variable(token: &str, quoted: bool) -> Sexp {
+		let token = &token[1..];
+		match token.split_once('[') {
+			Some((name, default)) => { Sexp::Variable { name: name.to_string(), default: Some(default.trim_end_matches(']').to_string()), quoted } }
+			None => { Sexp::Variable { name: token.to_string(), default: None, quoted } }
+		}
+	}
+
+	fn parse_element<'a, Parser: FSOParser<'a>>(state: &'a Parser) -> Result<Sexp, FSOParsingError> {
+		let fail = |reason: String| FSOParsingError { line: state.line(), reason, comments: None, version_string: None };
+
+		match state.get().chars().next() {
+			Some('(') => {
+				state.consume(1);
+				let mut list = Vec::new();
+				loop {
+					let (comments, _) = state.consume_whitespace(false);
+					if let Some(comments) = comments {
+						list.push(Sexp::Comment(comments));
+					}
+					match state.get().chars().next() {
+						Some(')') => {
+							state.consume(1);
+							break;
+						}
+						None => { return Err(fail("Unterminated S-expression.".to_string())); }
+						_ => { list.push(Self::parse_element(state)?); }
+					}
+				}
+				Ok(Sexp::List(list))
+			}
+			Some('"') => {
+				state.consume(1);
+				let current = state.get();
+				match current.find('"') {
+					Some(end) => {
+						state.consume(end + 1);
+						let string = &current[..end];
+						if string.starts_with('@') {
+							Ok(Self::parse_variable(string, true))
+						}
+						else {
+							Ok(Sexp::String(string.to_string()))
+						}
+					}
+					None => { Err(fail("Unterminated string in S-expression.".to_string())) }
+				}
+			}
+			Some(_) => {
+				let current = state.get();
+				let end = current.find(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == '"' || c == ';').unwrap_or(current.len());
+				state.consume(end);
+				let token = &current[..end];
+				if token.starts_with('@') {
+					Ok(Self::parse_variable(token, false))
+				}
+				else {
+					Ok(token.parse::<i64>().map_or_else(|_| Sexp::Atom(token.to_string()), Sexp::Number))
+				}
+			}
+			None => { Err(fail("Expected S-expression.".to_string())) }
+		}
+	}
+
+	//Spews lists containing other lists over multiple lines, with each argument on its own line, indented by indent per nesting level
+	//Lists of only atoms stay on one line
+	pub fn to_string_pretty(&self, indent: &str) -> String {
+		let mut out = String::new();
+		self.write_pretty(&mut out, indent, 0);
+		out
+	}
+
+	fn write_pretty(&self, out: &mut String, indent: &str, depth: usize) {
+		match self {
+			Sexp::List(list) if list.iter().any(|element| matches!(element, Sexp::List(_) | Sexp::Comment(_))) => {
+				out.push('(');
+				let mut elements = list.iter().peekable();
+				//The operator stays on the line of the opening parenthesis
+				if let Some(operator @ Sexp::Atom(_)) = elements.peek() {
+					out.push(' ');
+					operator.write_pretty(out, indent, depth + 1);
+					elements.next();
+				}
+				for element in elements {
+					out.push('\n');
+					out.push_str(&indent.repeat(depth + 1));
+					element.write_pretty(out, indent, depth + 1);
+				}
+				out.push('\n');
+				out.push_str(&indent.repeat(depth));
+				out.push(')');
+			}
+			Sexp::Comment(comment) => {
+				//Multi-line comments need every line indented
+				out.push_str(&comment.replace('\n', &format!("\n{}", indent.repeat(depth))));
+			}
+			_ => { out.push_str(&self.to_string()); }
+		}
+	}
+}
+
+impl Display for Sexp {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Sexp::List(list) => {
+				write!(f, "(")?;
+				for element in list {
+					write!(f, " {}", element)?;
+				}
+				write!(f, " )")
+			}
+			Sexp::Atom(atom) => { write!(f, "{}", atom) }
+			Sexp::String(string) => { write!(f, "\"{}\"", string) }
+			Sexp::Number(number) => { write!(f, "{}", number) }
+			Sexp::Variable { name, default, quoted } => {
+				let quote = if *quoted { "\"" } else { "" };
+				match default {
+					Some(default) => { write!(f, "{}@{}[{}]{}", quote, name, default, quote) }
+					None => { write!(f, "{}@{}{}", quote, name, quote) }
+				}
+			}
+			//Comments run until the end of the line, so whatever follows must go on the next one
+			Sexp::Comment(comment) => { writeln!(f, "{}", comment) }
+		}
+	}
+}
+
+impl FSOTable for Sexp {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, _hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		state.consume_whitespace_inline(&[]);
+		//Some files, such as campaigns, put the expression on the line after its option
+		if state.get().starts_with(['\n', '\r']) {
+			state.consume_whitespace(false);
+		}
+		if !state.get().starts_with('(') {
+			let current = state.get();
+			return Err(FSOParsingError { line: state.line(), reason: format!("Expected S-expression, got {}!", &current[..current.char_indices().nth(4).map_or(current.len(), |(i, _)| i)]), comments: None, version_string: None });
+		}
+		Ok((Self::parse_element(state)?, None))
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		let spewed = match &state.get_state().sexp_indentation {
+			Some(indent) => { self.to_string_pretty(indent) }
+			None => { self.to_string() }
+		};
+		state.append(spewed.as_str());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{FSOBuilder, FSOParser, FSOStringBuilder, FSOTable, FSOTableBuilder, FSOTableFileParser, Sexp};
+
+	const FORMULA: &str = "( when
+   ; Only once the player is in range
+   ( <
+      ( distance \"Alpha 1\" \"GTC Fenris 1\" )
+      @Range[1000]
+   )
+   ( send-message \"#Command\" \"High\" \"@Greeting[Msg1]\" )
+)";
+
+	fn parse(source: &str) -> Sexp {
+		let parser = FSOTableFileParser::from_string(source);
+		let (sexp, _) = Sexp::parse(&parser, None).unwrap();
+		assert!(parser.get().is_empty());
+		sexp
+	}
+
+	#[test]
+	fn parse_tree() {
+		let sexp = parse(FORMULA);
+
+		assert_eq!(sexp.operator(), Some("when"));
+		let arguments = sexp.arguments();
+		assert_eq!(arguments.len(), 2);
+		assert_eq!(arguments[0].operator(), Some("<"));
+		assert_eq!(arguments[0].arguments()[1], &Sexp::Variable { name: "Range".to_string(), default: Some("1000".to_string()), quoted: false });
+		assert_eq!(arguments[1].arguments()[2], &Sexp::Variable { name: "Greeting".to_string(), default: Some("Msg1".to_string()), quoted: true });
+
+		let Sexp::List(list) = &sexp else { panic!("Expected a list") };
+		assert_eq!(list[1], Sexp::Comment("; Only once the player is in range".to_string()));
+	}
+
+	#[test]
+	fn spew_indented() {
+		let sexp = parse(FORMULA);
+
+		let mut builder = FSOTableBuilder::default();
+		builder.get_state().sexp_indentation = Some("   ".to_string());
+		sexp.spew(&mut builder);
+		assert_eq!(builder.spew(), FORMULA);
+
+		let single_line = sexp.to_string();
+		assert_eq!(parse(&single_line), sexp);
+		assert_eq!(parse(&sexp.to_string_pretty("\t")), sexp);
+	}
+}
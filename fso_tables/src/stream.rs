@@ -0,0 +1,68 @@
+use std::io::Write;
+use crate::{FSOBuilder, FSOBuilderState, FSOFormatting, FSOStringBuilder, FSOTableBuilder};
+
+//Spews into a writer as it goes rather than into one string, with the same output as FSOTableBuilder.
+//Only the whitespace at the end is held back, as later parts may still replace it, so end with finish to write it
+pub struct FSOStreamBuilder<W: Write> {
+	builder: FSOTableBuilder,
+	writer: W,
+	error: Option<std::io::Error>
+}
+
+impl<W: Write> FSOStreamBuilder<W> {
+	pub fn new(writer: W, formatting: FSOFormatting) -> Self {
+		FSOStreamBuilder { builder: FSOTableBuilder::new(formatting), writer, error: None }
+	}
+
+	//After the first failed write, nothing more is written
+	fn write_settled(&mut self) {
+		let settled = self.builder.drain_settled();
+		if self.error.is_none() && !settled.is_empty() {
+			self.error = self.writer.write_all(settled.as_bytes()).err();
+		}
+	}
+
+	//Writes what is left and hands back the writer, or the first error writing to it
+	pub fn finish(mut self) -> std::io::Result<W> {
+		let rest = std::mem::take(&mut self.builder).spew();
+		if let Some(error) = self.error {
+			return Err(error);
+		}
+		self.writer.write_all(rest.as_bytes())?;
+		self.writer.flush()?;
+		Ok(self.writer)
+	}
+}
+
+impl<W: Write> FSOBuilder for FSOStreamBuilder<W> {
+	fn append(&mut self, content: &str) {
+		self.builder.append(content);
+		self.write_settled();
+	}
+
+	fn get_state(&mut self) -> &mut FSOBuilderState {
+		self.builder.get_state()
+	}
+
+	fn newline(&mut self) {
+		self.builder.newline();
+	}
+
+	fn start_line(&mut self) {
+		self.builder.start_line();
+	}
+
+	fn indent(&mut self, extra: usize) {
+		self.builder.indent(extra);
+	}
+
+	fn append_trailing_comment(&mut self, comment: &str) {
+		self.builder.append_trailing_comment(comment);
+		self.write_settled();
+	}
+
+	fn append_source(&mut self, text: &str, starts_line: bool) {
+		self.builder.append_source(text, starts_line);
+		self.write_settled();
+	}
+}
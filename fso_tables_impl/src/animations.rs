@@ -1,3 +1,436 @@
+mod check;
+mod ik;
+mod playback;
 mod table;
+mod timeline;
 
-pub use table::*;
\ No newline at end of file
+pub use check::*;
+pub use ik::*;
+pub use playback::*;
+pub use table::*;
+pub use timeline::*;
+
+#[cfg(test)]
+mod tests {
+	use fso_tables::{fso_check_respew, FSOChange, FSOConflict, FSODiff, FSOFloat, FSOFormatting, FSOPathed, FSOSpewed, FSOTableFileParser};
+	use proptest::prelude::*;
+	use crate::animations::*;
+	use crate::models::PofModel;
+	use crate::{format_table_file, merge_table_files, Angles, Matrix, TableKind, Vec3D};
+
+	const ANIMATIONS: &str = "#Animations
+$Name: Door
+$Type: docking-stage-2
++Triggered By: Cargo Port
+$Segment Parallel:
+$Rotation:
++Angle: 0, 90, 0
++Time: 2
++Submodel: door01
+$Rotation:
++Angle: 0, -90, 0
++Time: 2
++Submodel: door02
++End Segment
+$Name: Turret
+$Type: turret-firing
++Triggered By: turret02
+$Set Angle:
++Angle: 45
++Turret Arm: turret01
+$Name: Missiles
+$Type: secondary-bank
++Triggered By: 2
+$Wait:
++Time: 1
+#End
+";
+
+	#[test]
+	fn check_model() {
+		let table = AnimationTable::parse(FSOTableFileParser::from_string(ANIMATIONS)).unwrap();
+		let model = PofModel::read(&crate::models::tests::test_pof()).unwrap();
+
+		assert_eq!(table.check_model(&model), vec![
+			AnimationModelDiagnostic { animation: "Door".to_string(), problem: AnimationModelProblem::UnknownSubmodel("door02".to_string()) },
+			AnimationModelDiagnostic { animation: "Turret".to_string(), problem: AnimationModelProblem::UnknownTurret("turret02".to_string()) },
+			AnimationModelDiagnostic { animation: "Missiles".to_string(), problem: AnimationModelProblem::UnknownSecondaryBank(2) },
+		]);
+	}
+
+	#[test]
+	fn lists() {
+		//Inline flag lists, with comments after the closing parenthesis, and nested segment lists ended by +End Segment
+		let table = "#Animations\n$Name: Hatch\n$Type: initial\n$Flags: ( \"auto reverse\" \"loop\" ) ; both ways\n$Segment Sequential:\n$Wait:\n+Time: 1\n$Segment Parallel:\n$Wait:\n+Time: 2\n+End Segment\n+End Segment\n$Name: Bay\n$Type: fighterbay\n$Wait:\n+Time: 3\n#End\n";
+		let parsed = AnimationTable::parse(FSOTableFileParser::from_string(table)).unwrap();
+		let animations = &parsed.animations.animations;
+		assert_eq!(animations.len(), 2);
+		assert!(matches!(animations[0].flags.as_deref(), Some([AnimationFlag::AutoReverse, AnimationFlag::Looping])));
+		let AnimationSegment::SegmentSequential { segment } = &animations[0].segment else { panic!("Not a sequential segment") };
+		assert_eq!(segment.segments.len(), 2);
+		assert!(matches!(&segment.segments[1], AnimationSegment::SegmentParallel { segment } if segment.segments.len() == 1));
+
+		let spewed = parsed.spew();
+		assert!(spewed.contains("$Flags: (\"auto reverse\" \"loop\") ; both ways\n"), "{}", spewed);
+		let respewed = AnimationTable::parse(FSOTableFileParser::from_string(spewed.as_str())).unwrap().spew();
+		assert_eq!(respewed, spewed);
+	}
+
+	#[test]
+	fn gobble_line() {
+		//+End Segment after a submodel name would be read as part of the name if it stayed on the same line
+		let table = "#Animations\n$Name: Door\n$Type: initial\n$Segment Parallel:\n$Set Angle:\n+Angle: 30\n+Submodel: door01\n+End Segment\n#End\n";
+		let parsed = AnimationTable::parse(FSOTableFileParser::from_string(table)).unwrap();
+
+		let spewed = parsed.spew();
+		let lines = spewed.lines().map(str::trim).collect::<Vec<&str>>();
+		assert!(lines.windows(2).any(|pair| pair == ["+Submodel: door01", "+End Segment"]), "{}", spewed);
+		let reparsed = AnimationTable::parse(FSOTableFileParser::from_string(spewed.as_str())).unwrap();
+		let AnimationSegment::SegmentParallel { segment } = &reparsed.animations.animations[0].segment else { panic!("Not a parallel segment") };
+		assert!(matches!(&segment.segments[..], [AnimationSegment::SetAngle { segment }] if matches!(&segment.submodel, Some(AnimationTarget::Submodel { submodel_name }) if submodel_name == "door01")));
+	}
+
+	#[test]
+	fn timeline() {
+		let table = AnimationTable::parse(FSOTableFileParser::from_string("#Animations
+$Name: Open
+$Type: initial
+$Segment Sequential:
++Submodel: door01
+$Rotation:
++Angle: 0, 90, 0
++Velocity: 0, 45, 0
+$Wait:
++Time: 1
+$Translation:
++Vector: 0, 0, 10
++Time: 4
++Acceleration: 0, 0, 5
++End Segment
+#End
+")).unwrap();
+		let animation = &table.animations.animations[0];
+		let door = AnimatedSubmodel::Submodel("door01".to_string());
+
+		assert_eq!(animation.duration(), 7f32);
+		assert!((animation.pose_at(1f32)[&door].orientation.to_angles().heading - 45f32).abs() < 1e-3);
+		assert_eq!(animation.pose_at(2.5f32)[&door].offset.z, 0f32);
+		//Accelerating and decelerating symmetrically puts the midpoint in time at the midpoint in space
+		assert!((animation.pose_at(5f32)[&door].offset.z - 5f32).abs() < 1e-3);
+		assert!((animation.pose_at(10f32)[&door].offset.z - 10f32).abs() < 1e-3);
+
+		let trapezoid = MotionProfile::new(Some(-10f32), Some(4f32), None, Some(2f32));
+		assert_eq!(trapezoid.duration, 4.5f32);
+		assert_eq!(trapezoid.position(1f32), -1f32);
+		assert_eq!(trapezoid.position(4.5f32), -10f32);
+	}
+
+	#[test]
+	fn playback() {
+		let table = AnimationTable::parse(FSOTableFileParser::from_string("#Animations
+$Name: Door
+$Type: docking-stage-1
++Triggered By: Cargo Port
+$Rotation:
++Angle: 0, 90, 0
++Time: 2
++Submodel: door01
+$Name: Radar
+$Type: afterburner
+$Axis Rotation:
++Axis: 0, 1, 0
++Velocity: 90
++Time: 4
++Submodel: radar
+#End
+")).unwrap();
+		let door = &table.animations.animations[0];
+
+		let mut player = AnimationPlayer::new(door);
+		assert!(!player.trigger(&AnimationEvent::forward(AnimationTriggerType::DockingStage1, Some("Other Port"))));
+		assert!(player.trigger(&AnimationEvent::forward(AnimationTriggerType::DockingStage1, Some("cargo port"))));
+		player.update(1f32);
+		assert_eq!((player.playback, player.phase()), (AnimationPlayback::Forward, 0.5f32));
+		player.update(2f32);
+		assert_eq!((player.playback, player.time), (AnimationPlayback::Completed, 2f32));
+		player.trigger(&AnimationEvent::backward(AnimationTriggerType::DockingStage1, Some("Cargo Port")));
+		player.update(0.5f32);
+		assert_eq!((player.direction(), player.time), (-1, 1.5f32));
+
+		let mut bouncing = door.clone();
+		bouncing.flags = Some(vec![AnimationFlag::AutoReverse]);
+		let mut player = AnimationPlayer::new(&bouncing);
+		player.trigger(&AnimationEvent::forward(AnimationTriggerType::DockingStage1, Some("Cargo Port")));
+		player.update(2.5f32);
+		assert_eq!((player.playback, player.time), (AnimationPlayback::Backward, 1.5f32));
+		player.update(2f32);
+		assert_eq!((player.playback, player.time), (AnimationPlayback::Stopped, 0f32));
+
+		let mut radar = table.animations.animations[1].clone();
+		radar.flags = Some(vec![AnimationFlag::Looping, AnimationFlag::SeamlessWithStartup { startup_time: 1f32.into() }, AnimationFlag::PauseOnReverse]);
+		let mut player = AnimationPlayer::new(&radar);
+		player.trigger(&AnimationEvent::forward(AnimationTriggerType::Afterburner, None));
+		player.update(4.5f32);
+		assert_eq!((player.playback, player.time), (AnimationPlayback::Forward, 1.5f32));
+		player.trigger(&AnimationEvent::backward(AnimationTriggerType::Afterburner, None));
+		player.update(1f32);
+		assert_eq!((player.playback, player.time), (AnimationPlayback::Paused, 1.5f32));
+		player.trigger(&AnimationEvent::forward(AnimationTriggerType::Afterburner, None));
+		assert_eq!(player.direction(), 1);
+
+		radar.flags = Some(vec![AnimationFlag::RandomStartingPhase]);
+		let mut player = AnimationPlayer::new(&radar).with_seed(7);
+		player.trigger(&AnimationEvent::forward(AnimationTriggerType::Afterburner, None));
+		assert!(player.time > 0f32 && player.time < 4f32);
+	}
+
+	#[test]
+	fn inverse_kinematics() {
+		let link = |name: &str, offset: Vec3D, constraint: IkConstraint| IkLink { submodel: AnimatedSubmodel::Submodel(name.to_string()), offset, constraint };
+		let hinge = || IkConstraint::Hinge(Vec3D::new(1f32, 0f32, 0f32));
+		let arm = IkChain { links: vec![
+			link("shoulder", Vec3D::zero(), hinge()),
+			link("elbow", Vec3D::new(0f32, 0f32, 10f32), hinge()),
+			link("hand", Vec3D::new(0f32, 0f32, 10f32), IkConstraint::Free)
+		] };
+
+		let solution = arm.solve(&Vec3D::new(0f32, 10f32, 10f32), Some(&Matrix::identity()));
+		assert!(solution.error < 1e-2);
+		//Hinges only allow rotating around their axis, which therefore stays put
+		for orientation in &solution.orientations[..2] {
+			assert!((orientation.rvec.x - 1f32).abs() < 1e-3);
+		}
+		//The hand is turned back to face forward
+		let hand = solution.orientations.iter().fold(Matrix::identity(), |parent, local| parent.rotated_by(local));
+		assert!((hand.fvec.z - 1f32).abs() < 1e-3);
+
+		let mut stiff = arm.clone();
+		stiff.links[0].constraint = IkConstraint::Window(Angles::new(10f32, 10f32, 0f32));
+		let solution = stiff.solve(&Vec3D::new(0f32, 20f32, 0f32), None);
+		assert!(solution.error > 1f32);
+		assert!(solution.orientations[0].to_angles().pitch.abs() <= 10.01f32);
+
+		let table = AnimationTable::parse(FSOTableFileParser::from_string("#Animations
+$Name: Aim
+$Type: initial
+$Inverse Kinematics:
++Target Position: 0, 5, 5
++Time: 1
+$Chain Link:
++Submodel: turret01
+$Chain Link:
++Turret Arm: turret01
+#End
+")).unwrap();
+		let AnimationSegment::InverseKinematics { segment, .. } = &table.animations.animations[0].segment else { panic!("Expected an IK segment") };
+		let model = PofModel::read(&crate::models::tests::test_pof()).unwrap();
+		let chain = IkChain::from_segment(segment, &model).unwrap();
+		assert_eq!(chain.links[1].submodel, AnimatedSubmodel::TurretArm("turret01".to_string()));
+		assert_eq!(segment.solve(&model).unwrap().orientations.len(), 2);
+
+		//Links may skip submodels in between, whose offsets then count towards the later link, but must descend from each other
+		let chain_of = |first: &str, second: &str| {
+			let mut segment = segment.clone();
+			segment.links[0].submodel = AnimationTarget::Submodel { submodel_name: first.to_string() };
+			segment.links[1].submodel = AnimationTarget::Submodel { submodel_name: second.to_string() };
+			IkChain::from_segment(&segment, &model)
+		};
+		let skipping = chain_of("detail0", "turret01a").unwrap();
+		assert_eq!((skipping.links[1].offset.y, skipping.links[1].offset.z), (6f32, 2f32));
+		assert!(matches!(chain_of("door01", "turret01a"), Err(AnimationModelProblem::UnrelatedChainLinks(parent, child)) if parent == "door01" && child == "turret01a"));
+		assert_eq!(table.check_model(&model), vec![]);
+
+		//A hinged last link only follows the part of the target orientation around its axis
+		let mut wrist = arm.clone();
+		wrist.links[2].constraint = hinge();
+		let solution = wrist.solve(&Vec3D::new(0f32, 10f32, 10f32), Some(&Matrix::from_angles(&Angles::new(30f32, 45f32, 0f32))));
+		assert!((solution.orientations[2].rvec.x - 1f32).abs() < 1e-3);
+		let turn = Matrix::from_axis_angle(&Vec3D::new(1f32, 0f32, 0f32), 30f32);
+		let solution = wrist.solve(&Vec3D::new(0f32, 0f32, 20f32), Some(&turn));
+		assert!(solution.orientations[2].fvec.sub(&turn.fvec).length() < 1e-3);
+	}
+
+	#[test]
+	fn edit() {
+		let mut table = AnimationTable::parse(FSOTableFileParser::from_string(ANIMATIONS).with_round_trip()).unwrap();
+		assert_eq!(table.get_path("animations[name=Turret].segment.angle").unwrap(), "45");
+		assert_eq!(table.get_path("animations[1].triggered_by.triggered_by").unwrap(), "turret02");
+		assert!(table.get_path("animations[name=Nothing]").is_err());
+
+		//Only the edited lines change
+		table.set_path("animations[name=Door].segment.segments[0].time", "3").unwrap();
+		table.set_path("animations[name=Turret].segment.angle", "30").unwrap();
+		assert_eq!(table.spew_round_trip(), ANIMATIONS.replacen("+Time: 2", "+Time: 3", 1).replace("+Angle: 45", "+Angle: 30"));
+
+		table.remove_path("animations[name=Missiles]").unwrap();
+		assert!(table.spew_round_trip().ends_with("+Turret Arm: turret01\n#End\n"));
+		table.insert_path("animations[0]", "$Name: Hatch\n$Type: initial\n$Wait:\n+Time: 4").unwrap();
+		assert!(table.set_path("animations[name=Hatch].segment.time", "soon").is_err());
+		//Lists are set entry by entry, an entry that doesn't parse fails the whole list with its own error
+		table.set_path("animations[name=Hatch].flags", "( \"auto reverse\" \"loop\" )").unwrap();
+		assert!(matches!(table.animations.animations[0].flags.as_deref(), Some([AnimationFlag::AutoReverse, AnimationFlag::Looping])));
+		let error = table.set_path("animations[name=Hatch].flags", "( \"auto reverse\" \"sometimes\" )").unwrap_err();
+		assert!(error.reason.starts_with("Expected one of"), "{}", error.reason);
+		assert!(matches!(table.animations.animations[0].flags.as_deref(), Some([AnimationFlag::AutoReverse, AnimationFlag::Looping])));
+		table.set_path("animations[name=Hatch].flags", "()").unwrap();
+		assert!(matches!(table.animations.animations[0].flags.as_deref(), Some([])));
+		table.animations.animations[0].flags = None;
+
+		let respewed = AnimationTable::parse(FSOTableFileParser::from_string(table.spew_round_trip())).unwrap();
+		assert_eq!(respewed.animations.animations.len(), 3);
+		assert_eq!(respewed.get_path("animations[0].segment.time").unwrap(), "4");
+		assert_eq!(respewed.get_path("animations[name=Door].segment.segments[0].time").unwrap(), "3");
+	}
+
+	#[test]
+	fn diff() {
+		let old = AnimationTable::parse(FSOTableFileParser::from_string(ANIMATIONS)).unwrap();
+		let mut new = AnimationTable::parse(FSOTableFileParser::from_string(ANIMATIONS)).unwrap();
+		new.set_path("animations[name=Door].segment.segments[0].time", "3").unwrap();
+		new.set_path("animations[name=Turret].segment.angle", "30").unwrap();
+		new.remove_path("animations[name=Missiles]").unwrap();
+		new.insert_path("animations[0]", "$Name: Hatch\n$Type: initial\n$Wait:\n+Time: 4").unwrap();
+
+		assert!(FSODiff::between(&old, &old).is_empty());
+		let diff = FSODiff::between(&old, &new);
+		let changes = diff.0.iter().map(|difference| difference.path.to_string()).collect::<Vec<String>>();
+		assert_eq!(changes, vec!["animations[name=Door].segment.segments[0].time", "animations[name=Turret].segment.angle", "animations[name=Missiles]", "animations[name=Hatch]"]);
+		assert_eq!(diff.0[0].change, FSOChange::Modified { old: "2".to_string(), new: "3".to_string() });
+		assert!(matches!(&diff.0[2].change, FSOChange::Removed(value) if value.starts_with("$Name: Missiles")));
+		assert!(matches!(&diff.0[3].change, FSOChange::Added(value) if value.starts_with("$Name: Hatch")));
+
+		//Reported paths lead to the changed values
+		assert_eq!(new.get_path(&changes[1]).unwrap(), "30");
+		assert!(diff.to_string().starts_with("~ animations[name=Door].segment.segments[0].time: 2 -> 3\n~ animations[name=Turret].segment.angle: 45 -> 30\n- animations[name=Missiles]:\n\t- $Name: Missiles\n"));
+		assert!(diff.to_json().starts_with("[{\"path\":\"animations[name=Door].segment.segments[0].time\",\"change\":\"modified\",\"old\":\"2\",\"new\":\"3\"},"));
+		assert!(!diff.to_json().contains(" \\n") && !diff.to_json().contains("\\t\\n"));
+	}
+
+	#[test]
+	fn merge() {
+		let missiles = "$Name: Missiles\n$Type: secondary-bank\n+Triggered By: 2\n$Wait:\n+Time: 1\n";
+		let ours = ANIMATIONS.replacen("+Time: 2", "+Time: 3", 1).replace(missiles, "");
+		let theirs = ANIMATIONS.replacen("+Time: 2", "+Time: 5", 1).replace("+Angle: 45", "+Angle: 30").replace(missiles, "")
+			.replace("#End", "$Name: Hatch\n$Type: initial\n$Wait:\n+Time: 4\n#End");
+		let (merged, conflicts) = merge_table_files(TableKind::Animations, ANIMATIONS, &ours, &theirs).unwrap();
+
+		//Ours wins the conflict, everything else is merged in
+		assert_eq!(conflicts, vec![FSOConflict {
+			path: "animations[name=Door].segment.segments[0].time".parse().unwrap(),
+			base: Some("2".to_string()),
+			ours: Some("3".to_string()),
+			theirs: Some("5".to_string())
+		}]);
+		let merged = AnimationTable::parse(FSOTableFileParser::from_string(merged)).unwrap();
+		assert_eq!(merged.get_path("animations[name=Door].segment.segments[0].time").unwrap(), "3");
+		assert_eq!(merged.get_path("animations[name=Turret].segment.angle").unwrap(), "30");
+		assert!(merged.get_path("animations[name=Missiles]").is_err());
+		assert_eq!(merged.get_path("animations[2].segment.time").unwrap(), "4");
+	}
+
+	#[test]
+	fn canonical() {
+		let messy = "#Animations\n$name: Door\n$type: initial\n$flags: ( \"Loop\" \"auto reverse\" \"pause on reverse\" )\n$wait:\n+time: 2\n$Name: Hatch ;comment\n$Type: initial\n$Wait: +Time: 1\n#End\n";
+		let formatted = format_table_file(TableKind::Animations, messy).unwrap();
+		assert_eq!(formatted, "#Animations\n$Name: Door\n$Type: initial\n$Flags: (\"auto reverse\" \"loop\" \"pause on reverse\")\n$Wait:\n\t+Time: 2\n\n$Name: Hatch ;comment\n$Type: initial\n$Wait:\n\t+Time: 1\n\n#End\n");
+
+		//Formatting again changes nothing
+		assert_eq!(format_table_file(TableKind::Animations, &formatted).unwrap(), formatted);
+		assert_eq!(format_table_file(TableKind::Animations, ANIMATIONS).unwrap(), format_table_file(TableKind::Animations, &format_table_file(TableKind::Animations, ANIMATIONS).unwrap()).unwrap());
+
+		//Sorting the flags is no change to the table
+		let unsorted = AnimationTable::parse(FSOTableFileParser::from_string(messy)).unwrap();
+		assert!(fso_check_respew(&unsorted, FSOFormatting::canonical()).is_ok());
+		let sorted = AnimationTable::parse(FSOTableFileParser::from_string(formatted.as_str())).unwrap();
+		assert!(!FSODiff::between(&unsorted, &sorted).is_empty());
+		assert!(FSODiff::between_unordered_flags(&unsorted, &sorted).is_empty());
+	}
+
+	fn name() -> impl Strategy<Value = String> {
+		"[A-Za-z][A-Za-z0-9_]{0,10}"
+	}
+
+	fn angles() -> impl Strategy<Value = FSOSpewed<Angles>> {
+		(-360f32..360f32, -360f32..360f32, -360f32..360f32).prop_map(|(pitch, heading, bank)| FSOSpewed(Angles::new(pitch, heading, bank)))
+	}
+
+	fn vector() -> impl Strategy<Value = FSOSpewed<Vec3D>> {
+		(-1e3f32..1e3f32, -1e3f32..1e3f32, -1e3f32..1e3f32).prop_map(|(x, y, z)| FSOSpewed(Vec3D::new(x, y, z)))
+	}
+
+	fn target() -> impl Strategy<Value = Option<FSOSpewed<AnimationTarget>>> {
+		proptest::option::of(prop_oneof![
+			name().prop_map(|submodel_name| FSOSpewed(AnimationTarget::Submodel { submodel_name })),
+			name().prop_map(|subsystem_name| FSOSpewed(AnimationTarget::TurretBase { subsystem_name })),
+			name().prop_map(|subsystem_name| FSOSpewed(AnimationTarget::TurretArm { subsystem_name }))
+		])
+	}
+
+	fn trigger() -> impl Strategy<Value = FSOSpewed<AnimationTrigger>> {
+		let docking = proptest::option::of(name().prop_map(|triggered_by| FSOSpewed(AnimationTriggerDocking::new(triggered_by))));
+		let bank = proptest::option::of((0u32..4).prop_map(|triggered_by| FSOSpewed(AnimationTriggerWeaponBank::new(triggered_by))));
+		let turret = proptest::option::of(name().prop_map(|triggered_by| FSOSpewed(AnimationTriggerTurret::new(triggered_by))));
+		prop_oneof![
+			Just(FSOSpewed(AnimationTrigger::Initial)),
+			Just(FSOSpewed(AnimationTrigger::OnSpawn)),
+			Just(FSOSpewed(AnimationTrigger::Afterburner)),
+			docking.prop_map(|trigger| FSOSpewed(AnimationTrigger::DockingStage2 { trigger: trigger.map(|trigger| trigger.0) })),
+			bank.prop_map(|trigger| FSOSpewed(AnimationTrigger::PrimaryFired { trigger: trigger.map(|trigger| trigger.0) })),
+			turret.prop_map(|trigger| FSOSpewed(AnimationTrigger::TurretFiring { trigger: trigger.map(|trigger| trigger.0) }))
+		]
+	}
+
+	fn flag() -> impl Strategy<Value = FSOSpewed<AnimationFlag>> {
+		prop_oneof![
+			Just(FSOSpewed(AnimationFlag::AutoReverse)),
+			Just(FSOSpewed(AnimationFlag::ResetAtCompletion)),
+			Just(FSOSpewed(AnimationFlag::Looping)),
+			Just(FSOSpewed(AnimationFlag::RandomStartingPhase)),
+			Just(FSOSpewed(AnimationFlag::PauseOnReverse)),
+			(0f32..10f32).prop_map(|startup_time| FSOSpewed(AnimationFlag::SeamlessWithStartup { startup_time: startup_time.into() }))
+		]
+	}
+
+	fn segment() -> impl Strategy<Value = FSOSpewed<AnimationSegment>> {
+		let time = proptest::option::of(0f32..100f32);
+		let leaf = prop_oneof![
+			(-360f32..360f32, target()).prop_map(|(angle, submodel)| {
+				FSOSpewed(AnimationSegment::SetAngle { segment: AnimationSegmentSetAngle::new(angle.into(), submodel.map(|submodel| submodel.0)) })
+			}),
+			(proptest::option::of(angles()), any::<bool>(), proptest::option::of(angles()), time.clone(), proptest::option::of(angles()), target())
+				.prop_map(|(angle, absolute, velocity, time, acceleration, submodel)| FSOSpewed(AnimationSegment::Rotation { segment: AnimationSegmentRotation::new(
+					angle.map(|angle| angle.0), absolute, velocity.map(|velocity| velocity.0), time.map(FSOFloat::from), acceleration.map(|acceleration| acceleration.0), submodel.map(|submodel| submodel.0)
+				) })),
+			(proptest::option::of(vector()), any::<bool>(), time, target()).prop_map(|(vector, absolute, time, submodel)| {
+				FSOSpewed(AnimationSegment::Translation { segment: AnimationSegmentTranslation::new(vector.map(|vector| vector.0), absolute, None, time.map(FSOFloat::from), None, None, submodel.map(|submodel| submodel.0)) })
+			}),
+			(0f32..100f32).prop_map(|time| FSOSpewed(AnimationSegment::Wait { segment: AnimationSegmentWait::new(time.into()) }))
+		];
+		leaf.prop_recursive(3, 12, 4, |segment| {
+			(target(), prop::collection::vec(segment, 0..4), any::<bool>()).prop_map(|(submodel, segments, parallel)| {
+				let list = AnimationSegmentList::new(submodel.map(|submodel| submodel.0), segments.into_iter().map(|segment| segment.0).collect());
+				FSOSpewed(if parallel { AnimationSegment::SegmentParallel { segment: list } } else { AnimationSegment::SegmentSequential { segment: list } })
+			})
+		})
+	}
+
+	fn animation_table() -> impl Strategy<Value = FSOSpewed<AnimationTable>> {
+		let animation = (name(), trigger(), proptest::option::of(prop::collection::vec(flag(), 1..4)), segment()).prop_map(|(name, trigger, flags, segment)| {
+			FSOSpewed(Animation::new(name, trigger.0, flags.map(|flags| flags.into_iter().map(|flag| flag.0).collect()), segment.0))
+		});
+		prop::collection::vec(animation, 0..4).prop_map(|animations| {
+			FSOSpewed(AnimationTable::new(AnimationSubtable::new(animations.into_iter().map(|animation| animation.0).collect()), None))
+		})
+	}
+
+	proptest! {
+		#[test]
+		fn respew(table in animation_table()) {
+			for formatting in [FSOFormatting::default(), FSOFormatting::canonical()] {
+				if let Err(problem) = fso_check_respew(&table.0, formatting) {
+					return Err(TestCaseError::fail(problem.to_string()));
+				}
+			}
+		}
+	}
+}
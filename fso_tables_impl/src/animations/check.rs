@@ -0,0 +1,142 @@
+use std::fmt::{Display, Formatter};
+use crate::animations::{Animation, AnimationSegment, AnimationTable, AnimationTarget, AnimationTrigger, IkChain};
+use crate::models::PofModel;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationModelProblem {
+	UnknownSubmodel(String),
+	UnknownTurret(String),
+	UnknownDockingPoint(String),
+	UnknownFighterbay(String),
+	UnknownPrimaryBank(u32),
+	UnknownSecondaryBank(u32),
+	//IK chain links, each of which has to be a descendant of the link before it in the submodel hierarchy
+	UnrelatedChainLinks(String, String)
+}
+
+impl Display for AnimationModelProblem {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AnimationModelProblem::UnknownSubmodel(name) => { write!(f, "The model has no submodel \"{}\"", name) }
+			AnimationModelProblem::UnknownTurret(name) => { write!(f, "The model has no turret \"{}\"", name) }
+			AnimationModelProblem::UnknownDockingPoint(name) => { write!(f, "The model has no docking point \"{}\"", name) }
+			AnimationModelProblem::UnknownFighterbay(name) => { write!(f, "The model has no fighterbay \"{}\"", name) }
+			AnimationModelProblem::UnknownPrimaryBank(bank) => { write!(f, "The model has no primary bank {}", bank) }
+			AnimationModelProblem::UnknownSecondaryBank(bank) => { write!(f, "The model has no secondary bank {}", bank) }
+			AnimationModelProblem::UnrelatedChainLinks(parent, child) => { write!(f, "The chain link \"{}\" is not a descendant of the chain link \"{}\"", child, parent) }
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationModelDiagnostic {
+	pub animation: String,
+	pub problem: AnimationModelProblem
+}
+
+impl Display for AnimationModelDiagnostic {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: {}", self.animation, self.problem)
+	}
+}
+
+fn check_target(target: &AnimationTarget, model: &PofModel, problems: &mut Vec<AnimationModelProblem>) {
+	match target {
+		AnimationTarget::Submodel { submodel_name, .. } if model.submodel(submodel_name).is_none() => {
+			problems.push(AnimationModelProblem::UnknownSubmodel(submodel_name.clone()));
+		}
+		AnimationTarget::TurretBase { subsystem_name, .. } | AnimationTarget::TurretArm { subsystem_name, .. } if model.turret(subsystem_name).is_none() => {
+			problems.push(AnimationModelProblem::UnknownTurret(subsystem_name.clone()));
+		}
+		_ => { }
+	}
+}
+
+fn check_segment(segment: &AnimationSegment, model: &PofModel, problems: &mut Vec<AnimationModelProblem>) {
+	let target = match segment {
+		AnimationSegment::SetOrientation { segment, .. } => { segment.submodel.as_ref() }
+		AnimationSegment::SetAngle { segment, .. } => { segment.submodel.as_ref() }
+		AnimationSegment::Rotation { segment, .. } => { segment.submodel.as_ref() }
+		AnimationSegment::AxisRotation { segment, .. } => { segment.submodel.as_ref() }
+		AnimationSegment::Translation { segment, .. } => { segment.submodel.as_ref() }
+		AnimationSegment::InverseKinematics { segment, .. } => {
+			let known = problems.len();
+			for link in &segment.links {
+				check_target(&link.submodel, model, problems);
+			}
+			if problems.len() == known {
+				if let Err(problem) = IkChain::from_segment(segment, model) {
+					problems.push(problem);
+				}
+			}
+			None
+		}
+		AnimationSegment::SoundDuring { segment, .. } => {
+			check_segment(&segment.segment, model, problems);
+			segment.submodel.as_ref()
+		}
+		AnimationSegment::Wait { .. } => { None }
+		AnimationSegment::SegmentSequential { segment, .. } | AnimationSegment::SegmentParallel { segment, .. } => {
+			for inner in &segment.segments {
+				check_segment(inner, model, problems);
+			}
+			segment.submodel.as_ref()
+		}
+	};
+	if let Some(target) = target {
+		check_target(target, model, problems);
+	}
+}
+
+fn check_trigger(trigger: &AnimationTrigger, model: &PofModel, problems: &mut Vec<AnimationModelProblem>) {
+	match trigger {
+		AnimationTrigger::DockingStage1 { trigger: Some(docking), .. } |
+		AnimationTrigger::DockingStage2 { trigger: Some(docking), .. } |
+		AnimationTrigger::DockingStage3 { trigger: Some(docking), .. } |
+		AnimationTrigger::Docked { trigger: Some(docking), .. } if model.docking_point(&docking.triggered_by).is_none() => {
+			problems.push(AnimationModelProblem::UnknownDockingPoint(docking.triggered_by.clone()));
+		}
+		AnimationTrigger::PrimaryBank { trigger: Some(bank), .. } |
+		AnimationTrigger::PrimaryFired { trigger: Some(bank), .. } if bank.triggered_by as usize >= model.gun_banks.len() => {
+			problems.push(AnimationModelProblem::UnknownPrimaryBank(bank.triggered_by));
+		}
+		AnimationTrigger::SecondaryBank { trigger: Some(bank), .. } |
+		AnimationTrigger::SecondaryFired { trigger: Some(bank), .. } if bank.triggered_by as usize >= model.missile_banks.len() => {
+			problems.push(AnimationModelProblem::UnknownSecondaryBank(bank.triggered_by));
+		}
+		AnimationTrigger::Fighterbay { trigger: Some(bay), .. } => {
+			let bays = model.fighterbays();
+			//Fighterbays are numbered from 1, matching their $bayXX path names
+			let exists = match bay.triggered_by.trim().parse::<usize>() {
+				Ok(number) => { number >= 1 && number <= bays.len() }
+				Err(_) => { bays.iter().any(|path| path.name.trim_start_matches('$').eq_ignore_ascii_case(bay.triggered_by.trim().trim_start_matches('$'))) }
+			};
+			if !exists {
+				problems.push(AnimationModelProblem::UnknownFighterbay(bay.triggered_by.clone()));
+			}
+		}
+		AnimationTrigger::TurretFiring { trigger: Some(turret), .. } |
+		AnimationTrigger::TurretFired { trigger: Some(turret), .. } if model.turret(&turret.triggered_by).is_none() => {
+			problems.push(AnimationModelProblem::UnknownTurret(turret.triggered_by.clone()));
+		}
+		_ => { }
+	}
+}
+
+impl Animation {
+	pub fn check_model(&self, model: &PofModel) -> Vec<AnimationModelProblem> {
+		let mut problems = Vec::new();
+		check_trigger(&self.triggered_by, model, &mut problems);
+		check_segment(&self.segment, model, &mut problems);
+		problems
+	}
+}
+
+impl AnimationTable {
+	//Checks that everything the animations refer to (submodels, turrets, docking points, fighterbays and weapon banks) exists in the ship's model
+	pub fn check_model(&self, model: &PofModel) -> Vec<AnimationModelDiagnostic> {
+		self.animations.animations.iter().flat_map(|animation| {
+			animation.check_model(model).into_iter().map(|problem| AnimationModelDiagnostic { animation: animation.name.clone(), problem })
+		}).collect()
+	}
+}
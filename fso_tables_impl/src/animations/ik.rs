@@ -0,0 +1,175 @@
+use crate::{Angles, Matrix, Vec3D};
+use crate::animations::{AnimatedSubmodel, AnimationModelProblem, AnimationSegmentIK, AnimationSegmentIKConstraint, AnimationTarget};
+use crate::models::PofModel;
+
+#[derive(Clone)]
+pub enum IkConstraint {
+	Free,
+	//Maximum pitch, heading and bank away from the rest orientation, in degrees
+	Window(Angles),
+	//Only rotates around this axis, given in the link's parent frame at rest
+	Hinge(Vec3D)
+}
+
+impl From<&AnimationSegmentIKConstraint> for IkConstraint {
+	fn from(constraint: &AnimationSegmentIKConstraint) -> Self {
+		match constraint {
+			AnimationSegmentIKConstraint::Window { size, .. } => { IkConstraint::Window(size.window_size.clone()) }
+			AnimationSegmentIKConstraint::Hinge { axis, .. } => { IkConstraint::Hinge(axis.axis.clone()) }
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct IkLink {
+	pub submodel: AnimatedSubmodel,
+	//Position of the link's pivot relative to the previous link's pivot, in the previous link's frame. For the first link, relative to the chain's parent
+	pub offset: Vec3D,
+	pub constraint: IkConstraint
+}
+
+//A chain of submodels, each the child of the one before. The chain ends at the pivot of the last link, which is what gets moved to the target
+#[derive(Clone)]
+pub struct IkChain {
+	pub links: Vec<IkLink>
+}
+
+#[derive(Clone)]
+pub struct IkSolution {
+	//Per link, the orientation relative to its rest orientation
+	pub orientations: Vec<Matrix>,
+	//Distance between the end of the chain and the target position
+	pub error: f32,
+	pub iterations: usize
+}
+
+const IK_MAX_ITERATIONS: usize = 64;
+const IK_TOLERANCE: f32 = 1e-3;
+
+impl IkChain {
+	//Resolves the chain links of an $Inverse Kinematics: segment against the model's submodel hierarchy.
+	//Submodels between two links don't move, so their offsets are folded into the offset of the later link
+	pub fn from_segment(segment: &AnimationSegmentIK, model: &PofModel) -> Result<Self, AnimationModelProblem> {
+		let mut links: Vec<IkLink> = Vec::new();
+		let mut previous: Option<usize> = None;
+		for link in &segment.links {
+			let index = match &link.submodel {
+				AnimationTarget::Submodel { submodel_name, .. } => { model.submodels.iter().position(|submodel| submodel.name.eq_ignore_ascii_case(submodel_name)).ok_or_else(|| AnimationModelProblem::UnknownSubmodel(submodel_name.clone()))? }
+				AnimationTarget::TurretBase { subsystem_name, .. } => { model.turret(subsystem_name).map(|turret| turret.base).ok_or_else(|| AnimationModelProblem::UnknownTurret(subsystem_name.clone()))? }
+				AnimationTarget::TurretArm { subsystem_name, .. } => { model.turret(subsystem_name).map(|turret| turret.arm).ok_or_else(|| AnimationModelProblem::UnknownTurret(subsystem_name.clone()))? }
+			};
+
+			let mut offset = model.submodels[index].offset.clone();
+			if let Some(previous) = previous {
+				let mut ancestor = model.submodels[index].parent;
+				while ancestor != Some(previous) {
+					let Some(intermediate) = ancestor else {
+						return Err(AnimationModelProblem::UnrelatedChainLinks(model.submodels[previous].name.clone(), model.submodels[index].name.clone()));
+					};
+					offset = offset.add(&model.submodels[intermediate].offset);
+					ancestor = model.submodels[intermediate].parent;
+				}
+			}
+			previous = Some(index);
+			links.push(IkLink { submodel: AnimatedSubmodel::from(&link.submodel), offset, constraint: link.constraint.as_ref().map_or(IkConstraint::Free, IkConstraint::from) });
+		}
+		Ok(IkChain { links })
+	}
+
+	//World orientation and pivot position of every link, in the frame of the chain's parent
+	fn forward(&self, orientations: &[Matrix]) -> Vec<(Matrix, Vec3D)> {
+		let mut frames: Vec<(Matrix, Vec3D)> = Vec::new();
+		for (link, local) in self.links.iter().zip(orientations) {
+			let (parent_orientation, parent_position) = frames.last().cloned().unwrap_or((Matrix::identity(), Vec3D::zero()));
+			let position = parent_position.add(&parent_orientation.unrotate(&link.offset));
+			frames.push((parent_orientation.rotated_by(local), position));
+		}
+		frames
+	}
+
+	fn constrain(constraint: &IkConstraint, orientation: Matrix) -> Matrix {
+		match constraint {
+			IkConstraint::Window(window) => {
+				let angles = orientation.to_angles();
+				Matrix::from_angles(&Angles::new(angles.pitch.clamp(-window.pitch.abs(), window.pitch.abs()), angles.heading.clamp(-window.heading.abs(), window.heading.abs()), angles.bank.clamp(-window.bank.abs(), window.bank.abs())))
+			}
+			//Keeps only the turn around the axis, measured on a direction perpendicular to it
+			IkConstraint::Hinge(axis) => {
+				let axis = axis.normalized();
+				let across = if axis.x.abs() < 0.9f32 { Vec3D::new(1f32, 0f32, 0f32) } else { Vec3D::new(0f32, 1f32, 0f32) };
+				let from = across.sub(&axis.scale(axis.dot(&across))).normalized();
+				let turned = orientation.unrotate(&from);
+				let to = turned.sub(&axis.scale(axis.dot(&turned)));
+				if to.length() < f32::EPSILON {
+					return Matrix::identity();
+				}
+				Matrix::from_axis_angle(&axis, from.cross(&to).dot(&axis).atan2(from.dot(&to)).to_degrees())
+			}
+			IkConstraint::Free => { orientation }
+		}
+	}
+
+	//Cyclic coordinate descent: repeatedly turns each link, from the end of the chain towards its root, so that the end points at the target.
+	//The target orientation, if given, is applied to the last link afterwards, as it does not move the end of the chain
+	pub fn solve(&self, target_position: &Vec3D, target_orientation: Option<&Matrix>) -> IkSolution {
+		let mut orientations = vec![Matrix::identity(); self.links.len()];
+		let end = |orientations: &[Matrix]| self.forward(orientations).last().map_or(Vec3D::zero(), |(_, position)| position.clone());
+
+		let mut iterations = 0;
+		while iterations < IK_MAX_ITERATIONS && end(&orientations).sub(target_position).length() > IK_TOLERANCE {
+			iterations += 1;
+			for index in (0..self.links.len().saturating_sub(1)).rev() {
+				let frames = self.forward(&orientations);
+				let (orientation, pivot) = &frames[index];
+				let end_position = &frames[frames.len() - 1].1;
+				//Both directions in the link's current frame
+				let to_end = orientation.rotate(&end_position.sub(pivot));
+				let to_target = orientation.rotate(&target_position.sub(pivot));
+
+				let rotation = match &self.links[index].constraint {
+					IkConstraint::Hinge(axis) => {
+						//The hinge axis is fixed in the parent frame, so express it in the link's frame
+						let axis = orientations[index].rotate(axis).normalized();
+						let project = |v: &Vec3D| v.sub(&axis.scale(axis.dot(v)));
+						let (from, to) = (project(&to_end), project(&to_target));
+						if from.length() < f32::EPSILON || to.length() < f32::EPSILON {
+							continue;
+						}
+						let angle = from.cross(&to).dot(&axis).atan2(from.dot(&to));
+						Matrix::from_axis_angle(&axis, angle.to_degrees())
+					}
+					IkConstraint::Free | IkConstraint::Window(_) => {
+						let axis = to_end.cross(&to_target);
+						if axis.length() < f32::EPSILON {
+							continue;
+						}
+						let angle = axis.length().atan2(to_end.dot(&to_target));
+						Matrix::from_axis_angle(&axis, angle.to_degrees())
+					}
+				};
+				orientations[index] = Self::constrain(&self.links[index].constraint, orientations[index].rotated_by(&rotation));
+			}
+		}
+
+		if let (Some(target_orientation), Some(last)) = (target_orientation, self.links.len().checked_sub(1)) {
+			let parent = if last == 0 { Matrix::identity() } else { self.forward(&orientations)[last - 1].0.clone() };
+			//The orientation the last link needs relative to its parent to end up at the target orientation
+			let local = Matrix::new(parent.rotate(&target_orientation.rvec), parent.rotate(&target_orientation.uvec), parent.rotate(&target_orientation.fvec));
+			orientations[last] = Self::constrain(&self.links[last].constraint, local);
+		}
+
+		let error = end(&orientations).sub(target_position).length();
+		IkSolution { orientations, error, iterations }
+	}
+}
+
+impl AnimationSegmentIK {
+	//Solves the segment's chain for its target. Without a target position, the chain end stays where it is at rest
+	pub fn solve(&self, model: &PofModel) -> Result<IkSolution, AnimationModelProblem> {
+		let chain = IkChain::from_segment(self, model)?;
+		let rest = vec![Matrix::identity(); chain.links.len()];
+		let target_position = self.target_position.clone().unwrap_or_else(|| chain.forward(&rest).last().map_or(Vec3D::zero(), |(_, position)| position.clone()));
+		let target_orientation = self.target_orientation.as_ref().map(Matrix::from_angles);
+		Ok(chain.solve(&target_position, target_orientation.as_ref()))
+	}
+}
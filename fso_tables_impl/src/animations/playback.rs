@@ -0,0 +1,230 @@
+use crate::animations::{Animation, AnimationFlag, AnimationPose, AnimationTrigger};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationTriggerType {
+	Initial,
+	OnSpawn,
+	DockingStage1,
+	DockingStage2,
+	DockingStage3,
+	Docked,
+	PrimaryBank,
+	PrimaryFired,
+	SecondaryBank,
+	SecondaryFired,
+	Fighterbay,
+	Afterburner,
+	TurretFiring,
+	TurretFired,
+	Scripted
+}
+
+impl AnimationTrigger {
+	pub fn trigger_type(&self) -> AnimationTriggerType {
+		match self {
+			AnimationTrigger::Initial => { AnimationTriggerType::Initial }
+			AnimationTrigger::OnSpawn => { AnimationTriggerType::OnSpawn }
+			AnimationTrigger::DockingStage1 { .. } => { AnimationTriggerType::DockingStage1 }
+			AnimationTrigger::DockingStage2 { .. } => { AnimationTriggerType::DockingStage2 }
+			AnimationTrigger::DockingStage3 { .. } => { AnimationTriggerType::DockingStage3 }
+			AnimationTrigger::Docked { .. } => { AnimationTriggerType::Docked }
+			AnimationTrigger::PrimaryBank { .. } => { AnimationTriggerType::PrimaryBank }
+			AnimationTrigger::PrimaryFired { .. } => { AnimationTriggerType::PrimaryFired }
+			AnimationTrigger::SecondaryBank { .. } => { AnimationTriggerType::SecondaryBank }
+			AnimationTrigger::SecondaryFired { .. } => { AnimationTriggerType::SecondaryFired }
+			AnimationTrigger::Fighterbay { .. } => { AnimationTriggerType::Fighterbay }
+			AnimationTrigger::Afterburner => { AnimationTriggerType::Afterburner }
+			AnimationTrigger::TurretFiring { .. } => { AnimationTriggerType::TurretFiring }
+			AnimationTrigger::TurretFired { .. } => { AnimationTriggerType::TurretFired }
+			AnimationTrigger::Scripted { .. } => { AnimationTriggerType::Scripted }
+		}
+	}
+
+	//The docking port, weapon bank, fighterbay, turret or script name the animation is restricted to, if any
+	pub fn parameter(&self) -> Option<String> {
+		match self {
+			AnimationTrigger::DockingStage1 { trigger: Some(docking), .. } |
+			AnimationTrigger::DockingStage2 { trigger: Some(docking), .. } |
+			AnimationTrigger::DockingStage3 { trigger: Some(docking), .. } |
+			AnimationTrigger::Docked { trigger: Some(docking), .. } => { Some(docking.triggered_by.clone()) }
+			AnimationTrigger::PrimaryBank { trigger: Some(bank), .. } |
+			AnimationTrigger::PrimaryFired { trigger: Some(bank), .. } |
+			AnimationTrigger::SecondaryBank { trigger: Some(bank), .. } |
+			AnimationTrigger::SecondaryFired { trigger: Some(bank), .. } => { Some(bank.triggered_by.to_string()) }
+			AnimationTrigger::Fighterbay { trigger: Some(bay), .. } => { Some(bay.triggered_by.clone()) }
+			AnimationTrigger::TurretFiring { trigger: Some(turret), .. } |
+			AnimationTrigger::TurretFired { trigger: Some(turret), .. } => { Some(turret.triggered_by.clone()) }
+			AnimationTrigger::Scripted { trigger: Some(script), .. } => { Some(script.triggered_by.clone()) }
+			_ => { None }
+		}
+	}
+}
+
+//Something that happened to the ship, i.e. docking stage 1 at port "Cargo Port" starting (forward) or undocking (backward)
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationEvent {
+	pub trigger: AnimationTriggerType,
+	pub parameter: Option<String>,
+	pub forward: bool
+}
+
+impl AnimationEvent {
+	pub fn forward(trigger: AnimationTriggerType, parameter: Option<&str>) -> Self {
+		AnimationEvent { trigger, parameter: parameter.map(str::to_string), forward: true }
+	}
+
+	pub fn backward(trigger: AnimationTriggerType, parameter: Option<&str>) -> Self {
+		AnimationEvent { trigger, parameter: parameter.map(str::to_string), forward: false }
+	}
+
+	//Animations without a parameter react to every event of their trigger type
+	pub fn triggers(&self, animation: &Animation) -> bool {
+		if animation.triggered_by.trigger_type() != self.trigger {
+			return false;
+		}
+		match (animation.triggered_by.parameter(), &self.parameter) {
+			(Some(expected), Some(parameter)) => { expected.trim().eq_ignore_ascii_case(parameter.trim()) }
+			(Some(_), None) => { false }
+			(None, _) => { true }
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationPlayback {
+	//At the start, waiting to be triggered
+	Stopped,
+	Forward,
+	Backward,
+	//Reversed while PauseOnReverse is set, holding where it was
+	Paused,
+	//At the end, holding there until reversed
+	Completed
+}
+
+//Plays one animation in response to trigger events, following the behavior its flags ask for
+pub struct AnimationPlayer<'a> {
+	pub animation: &'a Animation,
+	pub playback: AnimationPlayback,
+	//Seconds into the animation
+	pub time: f32,
+	duration: f32,
+	random_state: u64
+}
+
+impl<'a> AnimationPlayer<'a> {
+	pub fn new(animation: &'a Animation) -> Self {
+		AnimationPlayer { animation, playback: AnimationPlayback::Stopped, time: 0f32, duration: animation.duration(), random_state: 0x853c49e6748fea9b }
+	}
+
+	//Seeds the random starting phase, for reproducible playback
+	pub fn with_seed(mut self, seed: u64) -> Self {
+		//Spread small seeds over all bits, xorshift never recovers from a zero state
+		self.random_state = seed.wrapping_mul(0x9e3779b97f4a7c15) | 1;
+		self
+	}
+
+	fn has_flag(&self, flag: fn(&AnimationFlag) -> bool) -> bool {
+		self.animation.flags.as_ref().is_some_and(|flags| flags.iter().any(flag))
+	}
+
+	fn random(&mut self) -> f32 {
+		//xorshift64, enough for picking a phase
+		self.random_state ^= self.random_state << 13;
+		self.random_state ^= self.random_state >> 7;
+		self.random_state ^= self.random_state << 17;
+		(self.random_state >> 40) as f32 / (1u64 << 24) as f32
+	}
+
+	//Where looping animations start over. Seamless ones only play their startup part once
+	fn loop_start(&self) -> f32 {
+		self.animation.flags.as_ref().and_then(|flags| flags.iter().find_map(|flag| match flag {
+			AnimationFlag::SeamlessWithStartup { startup_time, .. } => { Some(startup_time.clamp(0f32, self.duration)) }
+			_ => { None }
+		})).unwrap_or(0f32)
+	}
+
+	//+1 while playing forward, -1 while playing backward, 0 otherwise
+	pub fn direction(&self) -> i32 {
+		match self.playback {
+			AnimationPlayback::Forward => { 1 }
+			AnimationPlayback::Backward => { -1 }
+			_ => { 0 }
+		}
+	}
+
+	//How far into the animation the player is, from 0 to 1
+	pub fn phase(&self) -> f32 {
+		if self.duration > 0f32 { self.time / self.duration } else if self.time > 0f32 { 1f32 } else { 0f32 }
+	}
+
+	pub fn pose(&self) -> AnimationPose {
+		self.animation.pose_at(self.time)
+	}
+
+	//Returns whether the event concerned this animation
+	pub fn trigger(&mut self, event: &AnimationEvent) -> bool {
+		if !event.triggers(self.animation) {
+			return false;
+		}
+
+		let pause_on_reverse = self.has_flag(|flag| matches!(flag, AnimationFlag::PauseOnReverse));
+		match (event.forward, self.playback) {
+			//Initial animations are applied as soon as the model exists, without playing
+			(true, _) if event.trigger == AnimationTriggerType::Initial => {
+				self.time = self.duration;
+				self.playback = AnimationPlayback::Completed;
+			}
+			(true, AnimationPlayback::Stopped) => {
+				if self.has_flag(|flag| matches!(flag, AnimationFlag::RandomStartingPhase)) {
+					self.time = self.random() * self.duration;
+				}
+				self.playback = AnimationPlayback::Forward;
+			}
+			(true, AnimationPlayback::Backward | AnimationPlayback::Paused) => { self.playback = AnimationPlayback::Forward; }
+			(false, AnimationPlayback::Forward | AnimationPlayback::Completed) if pause_on_reverse => { self.playback = AnimationPlayback::Paused; }
+			(false, AnimationPlayback::Forward | AnimationPlayback::Completed) => { self.playback = AnimationPlayback::Backward; }
+			(false, AnimationPlayback::Paused) if !pause_on_reverse => { self.playback = AnimationPlayback::Backward; }
+			_ => { }
+		}
+		true
+	}
+
+	pub fn update(&mut self, elapsed: f32) {
+		match self.playback {
+			AnimationPlayback::Forward => {
+				self.time += elapsed;
+				if self.time < self.duration {
+					return;
+				}
+				let overflow = self.time - self.duration;
+				let loop_start = self.loop_start();
+
+				if self.has_flag(|flag| matches!(flag, AnimationFlag::Looping)) && self.duration > loop_start {
+					self.time = loop_start + overflow % (self.duration - loop_start);
+				}
+				else if self.has_flag(|flag| matches!(flag, AnimationFlag::AutoReverse)) {
+					self.time = self.duration - overflow;
+					self.playback = AnimationPlayback::Backward;
+					self.update(0f32);
+				}
+				else if self.has_flag(|flag| matches!(flag, AnimationFlag::ResetAtCompletion)) {
+					self.time = 0f32;
+					self.playback = AnimationPlayback::Stopped;
+				}
+				else {
+					self.time = self.duration;
+					self.playback = AnimationPlayback::Completed;
+				}
+			}
+			AnimationPlayback::Backward => {
+				self.time -= elapsed;
+				if self.time <= 0f32 {
+					self.time = 0f32;
+					self.playback = AnimationPlayback::Stopped;
+				}
+			}
+			AnimationPlayback::Stopped | AnimationPlayback::Paused | AnimationPlayback::Completed => { }
+		}
+	}
+}
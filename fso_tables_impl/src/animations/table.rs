@@ -1,4 +1,4 @@
-use fso_tables::{fso_table, FSOBuilder, FSOParser, FSOParsingError, FSOParsingHangingGobble, FSOTable};
+use fso_tables::{fso_table, FSOBuilder, FSOFloat, FSOParser, FSOParsingError, FSOParsingHangingGobble, FSOTable};
 use crate::{Angles, Vec3D};
 
 #[fso_table(toplevel)]
@@ -110,7 +110,7 @@ pub enum AnimationFlag {
 	Looping,
 	RandomStartingPhase,
 	PauseOnReverse,
-	SeamlessWithStartup{ startup_time: f32 }
+	SeamlessWithStartup{ startup_time: FSOFloat }
 }
 
 #[fso_table(prefix="+", suffix=":")]
@@ -145,7 +145,7 @@ pub struct AnimationSegmentSetOrientation {
 
 #[fso_table(prefix="+")]
 pub struct AnimationSegmentSetAngle {
-	pub angle: f32,
+	pub angle: FSOFloat,
 	#[unnamed]
 	pub submodel: Option<AnimationTarget>
 }
@@ -156,7 +156,7 @@ pub struct AnimationSegmentRotation {
 	#[existence]
 	pub absolute: bool,
 	pub velocity: Option<Angles>,
-	pub time: Option<f32>,
+	pub time: Option<FSOFloat>,
 	pub acceleration: Option<Angles>,
 	#[unnamed]
 	pub submodel: Option<AnimationTarget>
@@ -165,10 +165,10 @@ pub struct AnimationSegmentRotation {
 #[fso_table(prefix="+")]
 pub struct AnimationSegmentAxisRotation {
 	pub axis: Vec3D,
-	pub angle: Option<f32>,
-	pub velocity: Option<f32>,
-	pub time: Option<f32>,
-	pub acceleration: Option<f32>,
+	pub angle: Option<FSOFloat>,
+	pub velocity: Option<FSOFloat>,
+	pub time: Option<FSOFloat>,
+	pub acceleration: Option<FSOFloat>,
 	#[unnamed]
 	pub submodel: Option<AnimationTarget>
 }
@@ -178,7 +178,7 @@ pub struct AnimationSegmentTranslation {
 	#[existence]
 	pub absolute: bool,
 	pub velocity: Option<Vec3D>,
-	pub time: Option<f32>,
+	pub time: Option<FSOFloat>,
 	pub acceleration: Option<Vec3D>,
 	pub coordinate_system: Option<AnimationTranslationCoordinateSystem>,
 	#[unnamed]
@@ -198,7 +198,7 @@ pub enum AnimationTranslationCoordinateSystem {
 pub struct AnimationSegmentIK {
 	pub target_position: Option<Vec3D>,
 	pub target_orientation: Option<Angles>,
-	pub time: f32,
+	pub time: FSOFloat,
 	#[unnamed]
 	pub links: Vec<AnimationSegmentIKChainLink>
 }
@@ -235,7 +235,7 @@ pub struct AnimationSegmentSoundDuring {
 	#[fso_name="+Loop:"]
 	pub loop_sound: Option<String>,
 	pub end: Option<String>,
-	pub radius: f32,
+	pub radius: FSOFloat,
 	pub position: Option<Vec3D>,
 	#[existence]
 	pub flip_when_reversed: bool,
@@ -248,7 +248,7 @@ pub struct AnimationSegmentSoundDuring {
 
 #[fso_table(prefix="+")]
 pub struct AnimationSegmentWait {
-	pub time: f32
+	pub time: FSOFloat
 }
 
 #[fso_table(prefix="+")]
@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use crate::{Angles, Matrix, Vec3D};
+use crate::animations::{Animation, AnimationSegment, AnimationTarget, AnimationTranslationCoordinateSystem};
+
+//What an animation segment moves. Turret bases and arms are named by their turret subsystem
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnimatedSubmodel {
+	Submodel(String),
+	TurretBase(String),
+	TurretArm(String)
+}
+
+impl From<&AnimationTarget> for AnimatedSubmodel {
+	fn from(target: &AnimationTarget) -> Self {
+		match target {
+			AnimationTarget::Submodel { submodel_name, .. } => { AnimatedSubmodel::Submodel(submodel_name.clone()) }
+			AnimationTarget::TurretBase { subsystem_name, .. } => { AnimatedSubmodel::TurretBase(subsystem_name.clone()) }
+			AnimationTarget::TurretArm { subsystem_name, .. } => { AnimatedSubmodel::TurretArm(subsystem_name.clone()) }
+		}
+	}
+}
+
+//Orientation and offset of a submodel relative to its rest pose
+#[derive(Clone)]
+pub struct SubmodelPose {
+	pub orientation: Matrix,
+	pub offset: Vec3D,
+	//Angle around the submodel's own rotation axis as defined in the model, set by $Set Angle:
+	pub axis_angle: f32
+}
+
+impl Default for SubmodelPose {
+	fn default() -> Self {
+		SubmodelPose { orientation: Matrix::identity(), offset: Vec3D::zero(), axis_angle: 0f32 }
+	}
+}
+
+pub type AnimationPose = BTreeMap<AnimatedSubmodel, SubmodelPose>;
+
+//How a single value moves from 0 to distance: accelerating up to velocity, coasting, then decelerating to a stop
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MotionProfile {
+	pub distance: f32,
+	pub velocity: f32,
+	pub acceleration: Option<f32>,
+	pub duration: f32
+}
+
+impl MotionProfile {
+	//Without a target, the value moves at velocity for time.
+	//Otherwise, time takes precedence over velocity, and acceleration shapes the motion but never makes it overshoot
+	pub fn new(distance: Option<f32>, velocity: Option<f32>, time: Option<f32>, acceleration: Option<f32>) -> Self {
+		let velocity = velocity.map(f32::abs).filter(|velocity| *velocity > 0f32);
+		let time = time.filter(|time| *time > 0f32);
+		let acceleration = acceleration.map(f32::abs).filter(|acceleration| *acceleration > 0f32);
+
+		let distance = match distance {
+			Some(distance) => { distance }
+			None => { return MotionProfile { distance: velocity.unwrap_or(0f32) * time.unwrap_or(0f32), velocity: velocity.unwrap_or(0f32), acceleration: None, duration: time.unwrap_or(0f32) }; }
+		};
+		let length = distance.abs();
+		if length == 0f32 {
+			return MotionProfile { distance, velocity: 0f32, acceleration: None, duration: time.unwrap_or(0f32) };
+		}
+
+		match (acceleration, time, velocity) {
+			(None, Some(time), _) => { MotionProfile { distance, velocity: length / time, acceleration: None, duration: time } }
+			(None, None, Some(velocity)) => { MotionProfile { distance, velocity, acceleration: None, duration: length / velocity } }
+			(None, None, None) => { MotionProfile { distance, velocity: 0f32, acceleration: None, duration: 0f32 } }
+			(Some(acceleration), Some(time), _) if acceleration * time * time >= 4f32 * length => {
+				//The cruise velocity at which accelerating, coasting and decelerating takes exactly the given time
+				let velocity = (acceleration * time - (acceleration * acceleration * time * time - 4f32 * acceleration * length).sqrt()) / 2f32;
+				MotionProfile { distance, velocity, acceleration: Some(acceleration), duration: time }
+			}
+			(Some(acceleration), None, Some(velocity)) if length >= velocity * velocity / acceleration => {
+				MotionProfile { distance, velocity, acceleration: Some(acceleration), duration: length / velocity + velocity / acceleration }
+			}
+			//Too short to reach the velocity, or to make it in time, so accelerate halfway and decelerate the rest
+			(Some(acceleration), _, _) => {
+				MotionProfile { distance, velocity: (length * acceleration).sqrt(), acceleration: Some(acceleration), duration: 2f32 * (length / acceleration).sqrt() }
+			}
+		}
+	}
+
+	pub fn position(&self, t: f32) -> f32 {
+		let t = t.clamp(0f32, self.duration);
+		let length = match self.acceleration {
+			_ if self.duration <= 0f32 => { self.distance.abs() }
+			None => { self.velocity * t }
+			Some(acceleration) => {
+				let ramp = self.velocity / acceleration;
+				if t < ramp {
+					0.5f32 * acceleration * t * t
+				}
+				else if t <= self.duration - ramp {
+					0.5f32 * acceleration * ramp * ramp + self.velocity * (t - ramp)
+				}
+				else {
+					let remaining = self.duration - t;
+					self.distance.abs() - 0.5f32 * acceleration * remaining * remaining
+				}
+			}
+		};
+		length.min(self.distance.abs()) * self.distance.signum()
+	}
+}
+
+fn angle_profiles(angle: Option<&Angles>, velocity: Option<&Angles>, time: Option<f32>, acceleration: Option<&Angles>) -> [MotionProfile; 3] {
+	let axis = |get: fn(&Angles) -> f32| MotionProfile::new(angle.map(get), velocity.map(get), time, acceleration.map(get));
+	[axis(|a| a.pitch), axis(|a| a.heading), axis(|a| a.bank)]
+}
+
+fn vector_profiles(vector: Option<&Vec3D>, velocity: Option<&Vec3D>, time: Option<f32>, acceleration: Option<&Vec3D>) -> [MotionProfile; 3] {
+	let axis = |get: fn(&Vec3D) -> f32| MotionProfile::new(vector.map(get), velocity.map(get), time, acceleration.map(get));
+	[axis(|v| v.x), axis(|v| v.y), axis(|v| v.z)]
+}
+
+fn longest(profiles: &[MotionProfile; 3]) -> f32 {
+	profiles.iter().map(|profile| profile.duration).fold(0f32, f32::max)
+}
+
+impl AnimationSegment {
+	fn target(&self) -> Option<&AnimationTarget> {
+		match self {
+			AnimationSegment::SetOrientation { segment, .. } => { segment.submodel.as_ref() }
+			AnimationSegment::SetAngle { segment, .. } => { segment.submodel.as_ref() }
+			AnimationSegment::Rotation { segment, .. } => { segment.submodel.as_ref() }
+			AnimationSegment::AxisRotation { segment, .. } => { segment.submodel.as_ref() }
+			AnimationSegment::Translation { segment, .. } => { segment.submodel.as_ref() }
+			AnimationSegment::SoundDuring { segment, .. } => { segment.submodel.as_ref() }
+			AnimationSegment::SegmentSequential { segment, .. } | AnimationSegment::SegmentParallel { segment, .. } => { segment.submodel.as_ref() }
+			AnimationSegment::InverseKinematics { .. } | AnimationSegment::Wait { .. } => { None }
+		}
+	}
+
+	pub fn duration(&self) -> f32 {
+		match self {
+			AnimationSegment::SetOrientation { .. } | AnimationSegment::SetAngle { .. } => { 0f32 }
+			AnimationSegment::Rotation { segment, .. } => {
+				longest(&angle_profiles(segment.angle.as_ref(), segment.velocity.as_ref(), segment.time.as_deref().copied(), segment.acceleration.as_ref()))
+			}
+			AnimationSegment::AxisRotation { segment, .. } => {
+				MotionProfile::new(segment.angle.as_deref().copied(), segment.velocity.as_deref().copied(), segment.time.as_deref().copied(), segment.acceleration.as_deref().copied()).duration
+			}
+			AnimationSegment::Translation { segment, .. } => {
+				longest(&vector_profiles(segment.vector.as_ref(), segment.velocity.as_ref(), segment.time.as_deref().copied(), segment.acceleration.as_ref()))
+			}
+			AnimationSegment::InverseKinematics { segment, .. } => { *segment.time }
+			AnimationSegment::SoundDuring { segment, .. } => { segment.segment.duration() }
+			AnimationSegment::Wait { segment, .. } => { *segment.time }
+			AnimationSegment::SegmentSequential { segment, .. } => { segment.segments.iter().map(AnimationSegment::duration).sum() }
+			AnimationSegment::SegmentParallel { segment, .. } => { segment.segments.iter().map(AnimationSegment::duration).fold(0f32, f32::max) }
+		}
+	}
+
+	//Applies the state of this segment at time t (relative to the segment's start) to the pose.
+	//Segments without a submodel of their own act on the one of the enclosing segment list.
+	//Inverse kinematics segments only take up time here, as they need the model's hierarchy to be solved
+	pub fn apply(&self, t: f32, inherited: Option<&AnimatedSubmodel>, pose: &mut AnimationPose) {
+		let own = self.target().map(AnimatedSubmodel::from);
+		let target = own.as_ref().or(inherited);
+
+		match self {
+			AnimationSegment::SegmentSequential { segment, .. } => {
+				let mut start = 0f32;
+				for inner in &segment.segments {
+					if t < start {
+						break;
+					}
+					inner.apply(t - start, target, pose);
+					start += inner.duration();
+				}
+				return;
+			}
+			AnimationSegment::SegmentParallel { segment, .. } => {
+				for inner in &segment.segments {
+					inner.apply(t, target, pose);
+				}
+				return;
+			}
+			AnimationSegment::SoundDuring { segment, .. } => {
+				segment.segment.apply(t, target, pose);
+				return;
+			}
+			AnimationSegment::InverseKinematics { .. } | AnimationSegment::Wait { .. } => { return; }
+			_ => { }
+		}
+
+		let Some(target) = target else { return; };
+		let submodel = pose.entry(target.clone()).or_default();
+
+		match self {
+			AnimationSegment::SetOrientation { segment, .. } => {
+				let orientation = Matrix::from_angles(&segment.angle);
+				submodel.orientation = if segment.absolute { orientation } else { submodel.orientation.rotated_by(&orientation) };
+			}
+			AnimationSegment::SetAngle { segment, .. } => {
+				submodel.axis_angle = *segment.angle;
+			}
+			AnimationSegment::Rotation { segment, .. } => {
+				let start = submodel.orientation.to_angles();
+				//Absolute rotations move from wherever the submodel is to the given angles
+				let angle = segment.angle.as_ref().map(|angle| if segment.absolute { Angles::new(angle.pitch - start.pitch, angle.heading - start.heading, angle.bank - start.bank) } else { angle.clone() });
+				let [pitch, heading, bank] = angle_profiles(angle.as_ref(), segment.velocity.as_ref(), segment.time.as_deref().copied(), segment.acceleration.as_ref());
+				let delta = Angles::new(pitch.position(t), heading.position(t), bank.position(t));
+				submodel.orientation = if segment.absolute {
+					Matrix::from_angles(&Angles::new(start.pitch + delta.pitch, start.heading + delta.heading, start.bank + delta.bank))
+				}
+				else {
+					submodel.orientation.rotated_by(&Matrix::from_angles(&delta))
+				};
+			}
+			AnimationSegment::AxisRotation { segment, .. } => {
+				let angle = MotionProfile::new(segment.angle.as_deref().copied(), segment.velocity.as_deref().copied(), segment.time.as_deref().copied(), segment.acceleration.as_deref().copied()).position(t);
+				submodel.orientation = submodel.orientation.rotated_by(&Matrix::from_axis_angle(&segment.axis, angle));
+			}
+			AnimationSegment::Translation { segment, .. } => {
+				let vector = segment.vector.as_ref().map(|vector| if segment.absolute { vector.sub(&submodel.offset) } else { vector.clone() });
+				let [x, y, z] = vector_profiles(vector.as_ref(), segment.velocity.as_ref(), segment.time.as_deref().copied(), segment.acceleration.as_ref());
+				let delta = Vec3D::new(x.position(t), y.position(t), z.position(t));
+				//Segments are applied in order, so the orientation is the one at the start of this segment, except for rotations running in parallel before it
+				let delta = match segment.coordinate_system {
+					Some(AnimationTranslationCoordinateSystem::LocalAtStart) | Some(AnimationTranslationCoordinateSystem::LocalCurrent) => { submodel.orientation.unrotate(&delta) }
+					Some(AnimationTranslationCoordinateSystem::Parent) | None => { delta }
+				};
+				submodel.offset = submodel.offset.add(&delta);
+			}
+			_ => { }
+		}
+	}
+}
+
+impl Animation {
+	pub fn duration(&self) -> f32 {
+		self.segment.duration()
+	}
+
+	//The pose of every submodel the animation moves, t seconds after it started playing forwards
+	pub fn pose_at(&self, t: f32) -> AnimationPose {
+		let mut pose = AnimationPose::new();
+		self.segment.apply(t, None, &mut pose);
+		pose
+	}
+}
@@ -0,0 +1,108 @@
+mod filename;
+mod index;
+mod vp;
+
+pub use filename::*;
+pub use index::*;
+pub use vp::*;
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use std::fs;
+	use std::ops::Deref;
+	use std::path::{Path, PathBuf};
+	use crate::assets::*;
+
+	//A directory in the system's temp directory, removed again when dropped
+	pub(crate) struct ScratchDir(PathBuf);
+
+	impl Deref for ScratchDir {
+		type Target = Path;
+		fn deref(&self) -> &Path {
+			&self.0
+		}
+	}
+
+	impl Drop for ScratchDir {
+		fn drop(&mut self) {
+			let _ = fs::remove_dir_all(&self.0);
+		}
+	}
+
+	pub(crate) fn scratch_dir(name: &str) -> ScratchDir {
+		let dir = std::env::temp_dir().join(format!("fso_tables_impl_{}_{}", name, std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		fs::create_dir_all(&dir).unwrap();
+		ScratchDir(dir)
+	}
+
+	pub(crate) fn write_vp(path: &std::path::Path, files: &[(&str, &[u8])]) {
+		//Files are placed in data/sounds/, mirroring how retail VPs are laid out
+		let mut data = Vec::new();
+		let mut directory = Vec::new();
+		let dir_entry = |offset: i32, size: i32, name: &str, timestamp: i32| {
+			let mut entry = Vec::new();
+			entry.extend_from_slice(&offset.to_le_bytes());
+			entry.extend_from_slice(&size.to_le_bytes());
+			let mut name_bytes = [0u8; 32];
+			name_bytes[..name.len()].copy_from_slice(name.as_bytes());
+			entry.extend_from_slice(&name_bytes);
+			entry.extend_from_slice(&timestamp.to_le_bytes());
+			entry
+		};
+
+		directory.push(dir_entry(0, 0, "data", 0));
+		directory.push(dir_entry(0, 0, "sounds", 0));
+		for (name, content) in files {
+			directory.push(dir_entry(16 + data.len() as i32, content.len() as i32, name, 1));
+			data.extend_from_slice(content);
+		}
+		directory.push(dir_entry(0, 0, "..", 0));
+		directory.push(dir_entry(0, 0, "..", 0));
+
+		let mut out = Vec::new();
+		out.extend_from_slice(b"VPVP");
+		out.extend_from_slice(&2i32.to_le_bytes());
+		out.extend_from_slice(&(16 + data.len() as i32).to_le_bytes());
+		out.extend_from_slice(&(directory.len() as i32).to_le_bytes());
+		out.extend_from_slice(&data);
+		for entry in directory {
+			out.extend_from_slice(&entry);
+		}
+		fs::write(path, out).unwrap();
+	}
+
+	#[test]
+	fn vp_index() {
+		let dir = scratch_dir("vp_index");
+		write_vp(&dir.join("sounds.vp"), &[("Beep.wav", b"RIFF"), ("boop.ogg", b"OggS")]);
+
+		let archive = VPArchive::open(&dir.join("sounds.vp")).unwrap();
+		let paths = archive.files.iter().map(|f| f.path.as_str()).collect::<Vec<&str>>();
+		assert_eq!(paths, vec!["data/sounds/Beep.wav", "data/sounds/boop.ogg"]);
+		assert_eq!(archive.files[1].size, 4);
+
+		let mut index = AssetIndex::default();
+		index.add_vp(&dir.join("sounds.vp")).unwrap();
+
+		assert!(index.resolve("beep.wav").is_some());
+		assert!(index.resolve("boop.wav").is_none());
+		assert!(index.resolve_kind("boop.wav", AssetKind::Sound).is_some());
+		assert!(matches!(index.resolve("BOOP.OGG"), Some(AssetLocation::Packed { .. })));
+	}
+
+	#[test]
+	fn mod_directory() {
+		let dir = scratch_dir("mod_directory");
+		fs::create_dir_all(dir.join("data").join("music")).unwrap();
+		fs::write(dir.join("data").join("music").join("Brief1.ogg"), b"OggS").unwrap();
+		write_vp(&dir.join("core.vp"), &[("beep.wav", b"RIFF")]);
+
+		let mut index = AssetIndex::default();
+		index.add_mod_directory(&dir).unwrap();
+
+		assert!(matches!(index.resolve_kind("brief1.wav", AssetKind::Music), Some(AssetLocation::Loose(_))));
+		assert!(index.resolve("beep.wav").is_some());
+		assert!(index.resolve("missing.wav").is_none());
+	}
+}
@@ -0,0 +1,45 @@
+use fso_tables::{FSOBuilder, FSOParser, FSOParsingError, FSOParsingHangingGobble, FSOTable};
+
+//A bare file name, as it is used in tables that put further values on the same line after a file name
+//Unlike a String, parsing stops at the first whitespace or comma, unless the name is quoted
+//Tokens that start like table syntax ($Option:, +Option:, #Section, comments) are never accepted as a file name
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filename(pub String);
+
+impl Filename {
+	pub fn as_str(&self) -> &str {
+		self.0.as_str()
+	}
+
+	//FSO treats these as "no file" rather than as a reference to a file
+	pub fn is_none(&self) -> bool {
+		self.0.is_empty() || self.0.eq_ignore_ascii_case("none") || self.0.eq_ignore_ascii_case("none.wav") || self.0.eq_ignore_ascii_case("<none>")
+	}
+}
+
+impl FSOTable for Filename {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, _hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		state.consume_whitespace_inline(&[]);
+		match state.get().chars().next() {
+			Some('"') => {
+				state.consume(1);
+				Ok((Filename(state.read_until_target("\"", true).to_string()), None))
+			}
+			Some(c) if !c.is_whitespace() && !['$', '+', '#', ';'].contains(&c) => {
+				Ok((Filename(state.read_until_whitespace()), None))
+			}
+			_ => {
+				Err(FSOParsingError { line: state.line(), reason: "Expected file name.".to_string(), comments: None, version_string: None })
+			}
+		}
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		if self.0.contains(|c: char| c.is_whitespace() || c == ',') {
+			state.append(format!("\"{}\"", self.0).as_str());
+		}
+		else {
+			state.append(self.0.as_str());
+		}
+	}
+}
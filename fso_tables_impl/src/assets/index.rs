@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use fso_tables::FSOParsingError;
+use crate::assets::VPArchive;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AssetKind {
+	Sound,
+	Music,
+	Mission,
+	Other
+}
+
+impl AssetKind {
+	//Extensions the engine will try for a file of this kind, in addition to the one given in the table
+	pub fn extensions(&self) -> &'static [&'static str] {
+		match self {
+			AssetKind::Sound | AssetKind::Music => { &["ogg", "wav"] }
+			AssetKind::Mission => { &["fs2"] }
+			AssetKind::Other => { &[] }
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AssetLocation {
+	Loose(PathBuf),
+	Packed { archive: PathBuf, path: String }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetReference {
+	pub filename: String,
+	pub kind: AssetKind,
+	//Human readable description of where in the table the file is referenced
+	pub referenced_by: String
+}
+
+pub trait ReferencesAssets {
+	fn referenced_assets(&self) -> Vec<AssetReference>;
+}
+
+//Case-insensitive lookup of asset file names, as the engine resolves them regardless of the subdirectory they are in
+#[derive(Clone, Debug, Default)]
+pub struct AssetIndex {
+	files: HashMap<String, AssetLocation>
+}
+
+impl AssetIndex {
+	//Indexes all loose files below the directory, as well as the contents of all VPs directly in it
+	pub fn add_mod_directory(&mut self, root: &Path) -> Result<(), FSOParsingError> {
+		let fail = |path: &Path, err: std::io::Error| FSOParsingError { line: 0, reason: format!("Could not read directory {}! Reason: {}.", path.to_string_lossy(), err), comments: None, version_string: None };
+
+		let mut vps = Vec::new();
+		for entry in fs::read_dir(root).map_err(|err| fail(root, err))? {
+			let path = entry.map_err(|err| fail(root, err))?.path();
+			if path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vp")) {
+				vps.push(path);
+			}
+		}
+		vps.sort();
+		for vp in vps {
+			self.add_vp(&vp)?;
+		}
+
+		//Loose files take precedence over packed ones. Files directly in the mod root (mod.ini, the VPs themselves) are not game data
+		let mut to_visit = vec![root.to_path_buf()];
+		while let Some(dir) = to_visit.pop() {
+			for entry in fs::read_dir(&dir).map_err(|err| fail(&dir, err))? {
+				let path = entry.map_err(|err| fail(&dir, err))?.path();
+				if path.is_dir() {
+					to_visit.push(path);
+				}
+				else if dir != root {
+					if let Some(name) = path.file_name() {
+						self.files.insert(name.to_string_lossy().to_lowercase(), AssetLocation::Loose(path.clone()));
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	pub fn add_vp(&mut self, path: &Path) -> Result<(), FSOParsingError> {
+		let archive = VPArchive::open(path)?;
+		for file in archive.files {
+			self.files.entry(file.filename().to_lowercase()).or_insert_with(|| AssetLocation::Packed { archive: archive.path.clone(), path: file.path.clone() });
+		}
+		Ok(())
+	}
+
+	pub fn resolve(&self, filename: &str) -> Option<&AssetLocation> {
+		self.files.get(&filename.to_lowercase())
+	}
+
+	pub fn resolve_kind(&self, filename: &str, kind: AssetKind) -> Option<&AssetLocation> {
+		self.resolve(filename).or_else(|| {
+			let stem = filename.rsplit_once('.').map_or(filename, |(stem, _)| stem);
+			kind.extensions().iter().find_map(|ext| self.resolve(&format!("{}.{}", stem, ext)))
+		})
+	}
+
+	pub fn find_missing(&self, table: &impl ReferencesAssets) -> Vec<AssetReference> {
+		table.referenced_assets().into_iter().filter(|asset| self.resolve_kind(&asset.filename, asset.kind).is_none()).collect()
+	}
+}
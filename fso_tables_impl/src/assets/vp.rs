@@ -0,0 +1,84 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use fso_tables::FSOParsingError;
+
+#[derive(Clone, Debug)]
+pub struct VPFile {
+	//Full path inside the archive, using '/' as separator, e.g. data/sounds/beep.wav
+	pub path: String,
+	pub offset: u32,
+	pub size: u32,
+	pub timestamp: u32
+}
+
+impl VPFile {
+	pub fn filename(&self) -> &str {
+		self.path.rsplit('/').next().unwrap_or(&self.path)
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct VPArchive {
+	pub path: PathBuf,
+	pub files: Vec<VPFile>
+}
+
+const VP_HEADER_SIZE: u64 = 16;
+const VP_DIRENTRY_SIZE: usize = 44;
+
+impl VPArchive {
+	//Only reads the directory of the archive, file contents stay on disk
+	pub fn open(path: &Path) -> Result<Self, FSOParsingError> {
+		let fail = |reason: String| FSOParsingError { line: 0, reason: format!("Could not read VP {}! Reason: {}.", path.to_string_lossy(), reason), comments: None, version_string: None };
+
+		let file = File::open(path).map_err(|err| fail(err.to_string()))?;
+		let mut reader = BufReader::new(file);
+
+		let mut header = [0u8; VP_HEADER_SIZE as usize];
+		reader.read_exact(&mut header).map_err(|err| fail(err.to_string()))?;
+		if &header[0..4] != b"VPVP" {
+			return Err(fail("Missing VPVP signature".to_string()));
+		}
+		let read_i32 = |bytes: &[u8]| i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+		let dir_offset = read_i32(&header[8..12]);
+		let dir_entries = read_i32(&header[12..16]);
+		if dir_offset < VP_HEADER_SIZE as i32 || dir_entries < 0 {
+			return Err(fail("Corrupt header".to_string()));
+		}
+
+		reader.seek(SeekFrom::Start(dir_offset as u64)).map_err(|err| fail(err.to_string()))?;
+
+		let mut files = Vec::new();
+		let mut current_dir: Vec<String> = Vec::new();
+		let mut entry = [0u8; VP_DIRENTRY_SIZE];
+		for _ in 0..dir_entries {
+			reader.read_exact(&mut entry).map_err(|err| fail(err.to_string()))?;
+			let offset = read_i32(&entry[0..4]) as u32;
+			let size = read_i32(&entry[4..8]) as u32;
+			let name_end = entry[8..40].iter().position(|b| *b == 0).unwrap_or(32);
+			let name = String::from_utf8_lossy(&entry[8..8 + name_end]).to_string();
+			let timestamp = read_i32(&entry[40..44]) as u32;
+
+			//Directories are entries without size and timestamp, ".." closes the current one
+			if size == 0 && timestamp == 0 {
+				if name == ".." {
+					current_dir.pop();
+				}
+				else {
+					current_dir.push(name);
+				}
+				continue;
+			}
+
+			let mut path = current_dir.join("/");
+			if !path.is_empty() {
+				path.push('/');
+			}
+			path.push_str(&name);
+			files.push(VPFile { path, offset, size, timestamp });
+		}
+
+		Ok(VPArchive { path: path.to_path_buf(), files })
+	}
+}
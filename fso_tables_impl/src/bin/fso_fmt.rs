@@ -0,0 +1,48 @@
+use std::path::Path;
+use std::process::ExitCode;
+use fso_tables_impl::{format_table_file, TableKind};
+
+//Rewrites table files in the canonical layout. With --check, only lists the files that aren't formatted and fails if there are any
+fn main() -> ExitCode {
+	let mut check = false;
+	let mut files = Vec::new();
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"--check" => { check = true; }
+			_ => { files.push(arg); }
+		}
+	}
+	if files.is_empty() {
+		eprintln!("Usage: fso_fmt [--check] <files>...");
+		return ExitCode::from(2);
+	}
+
+	let mut failed = false;
+	for file in &files {
+		let Some(kind) = TableKind::from_filename(Path::new(file)) else {
+			eprintln!("{}: Unknown kind of table.", file);
+			failed = true;
+			continue;
+		};
+		let formatted = std::fs::read_to_string(file).map_err(|err| err.to_string())
+			.and_then(|text| format_table_file(kind, &text).map(|formatted| (formatted != text, formatted)).map_err(|err| err.to_string()));
+		match formatted {
+			Ok((false, _)) => { }
+			Ok((true, _)) if check => {
+				println!("{}", file);
+				failed = true;
+			}
+			Ok((true, formatted)) => {
+				if let Err(err) = std::fs::write(file, formatted) {
+					eprintln!("{}: {}", file, err);
+					failed = true;
+				}
+			}
+			Err(err) => {
+				eprintln!("{}: {}", file, err);
+				failed = true;
+			}
+		}
+	}
+	if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+use std::process::{Command, ExitCode};
+use fso_tables_impl::{merge_table_files_resolving, FSOResolution, TableKind};
+
+//Three-way merge of table files by entry and field, for use as a git merge driver:
+//	git config merge.fso.driver "fso_merge %O %A %B %P"
+//	echo "curves.tbl merge=fso" >> .gitattributes
+//The merged table is written over ours. Conflicting values are put between conflict markers, listed on stderr and fail the merge.
+//Files of unknown kinds, or that don't parse, are merged as text by git merge-file instead
+fn main() -> ExitCode {
+	let args = std::env::args().skip(1).collect::<Vec<String>>();
+	let [base, ours, theirs, rest @ ..] = args.as_slice() else {
+		eprintln!("Usage: fso_merge <base> <ours> <theirs> [<path in repository>]");
+		return ExitCode::from(2);
+	};
+	let name = rest.first().unwrap_or(ours);
+	let Some(kind) = TableKind::from_filename(Path::new(name)) else {
+		eprintln!("{}: Unknown kind of table, merging as text.", name);
+		return merge_text(ours, base, theirs);
+	};
+
+	let read = |file: &String| std::fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err));
+	let texts = match (read(base), read(ours), read(theirs)) {
+		(Ok(base), Ok(ours), Ok(theirs)) => { (base, ours, theirs) }
+		(Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => {
+			eprintln!("{}", err);
+			return ExitCode::from(2);
+		}
+	};
+	let merge = |resolution| merge_table_files_resolving(kind, &texts.0, &texts.1, &texts.2, resolution);
+	let (merged, conflicts) = match merge(FSOResolution::Ours) {
+		Ok(merged) => { merged }
+		Err(err) => {
+			eprintln!("{}: {}, merging as text.", name, err);
+			return merge_text(ours, base, theirs);
+		}
+	};
+	if let Err(err) = std::fs::write(ours, merged) {
+		eprintln!("{}: {}", ours, err);
+		return ExitCode::from(2);
+	}
+	if conflicts.is_empty() {
+		return ExitCode::SUCCESS;
+	}
+
+	for conflict in &conflicts {
+		eprintln!("{}: {}", name, conflict);
+	}
+	//The merges resolving to each side only differ in the conflicting values, so merging them as text puts markers around just those
+	let sides = [FSOResolution::Base, FSOResolution::Theirs].map(|resolution| merge(resolution).map(|(merged, _)| merged));
+	if let [Ok(base_resolved), Ok(theirs_resolved)] = &sides {
+		let scratch = |side: &str| std::env::temp_dir().join(format!("fso_merge_{}_{}", std::process::id(), side));
+		let (base_path, theirs_path) = (scratch("base"), scratch("theirs"));
+		match std::fs::write(&base_path, base_resolved).and_then(|_| std::fs::write(&theirs_path, theirs_resolved)) {
+			Ok(()) => { merge_text(ours, &base_path, &theirs_path); }
+			Err(err) => { eprintln!("{}: {}", name, err); }
+		}
+		let _ = std::fs::remove_file(base_path);
+		let _ = std::fs::remove_file(theirs_path);
+	}
+	ExitCode::FAILURE
+}
+
+//Merges as git would without a driver, leaving conflict markers in ours
+fn merge_text(ours: impl AsRef<Path>, base: impl AsRef<Path>, theirs: impl AsRef<Path>) -> ExitCode {
+	let status = Command::new("git").args(["merge-file", "-L", "ours", "-L", "base", "-L", "theirs"])
+		.args([ours.as_ref(), base.as_ref(), theirs.as_ref()]).status();
+	match status {
+		Ok(status) if status.success() => { ExitCode::SUCCESS }
+		//A positive exit code counts the conflicts
+		Ok(status) if status.code().is_some_and(|code| code > 0) => { ExitCode::FAILURE }
+		Ok(status) => {
+			eprintln!("git merge-file failed: {}", status);
+			ExitCode::from(2)
+		}
+		Err(err) => {
+			eprintln!("git merge-file: {}", err);
+			ExitCode::from(2)
+		}
+	}
+}
@@ -0,0 +1,161 @@
+mod table;
+mod graph;
+mod assets;
+
+pub use table::*;
+pub use graph::*;
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use fso_tables::{FSOTableFileParser, Sexp};
+	use crate::assets::*;
+	use crate::campaigns::*;
+
+	const CAMPAIGN: &str = "$Name: Test Campaign
+$Type: single
++Description:
+XSTR(\"A short test campaign\", -1)
+$end_multi_text
+$Flags: 0
+
++Starting Ships: ( \"GTF Ulysses\" )
++Starting Weapons: ( \"ML-16 Laser\" )
+
+#Missions
+
+$Mission: m1.fs2
++Flags: 0
++Main Hall: 0
++Debriefing Persona Index: 1
++Formula:
+( cond
+   (
+      ( is-previous-event-true \"m1.fs2\" \"Greeting\" )
+      ( next-mission \"m2.fs2\" )
+   )
+   (
+      ( true )
+      ( next-mission \"M3\" )
+   )
+)
++Mission Loop:
++Mission Loop Text:
+XSTR(\"Take a detour?\", -1)
+$end_multi_text
++Mission Loop Brief Anim: <default>
++Formula:
+( cond
+   (
+      ( true )
+      ( next-mission \"loop.fs2\" )
+   )
+)
++Level: 0
++Position: 0
+
+$Mission: m2.fs2
++Flags: 0
++Formula:
+( cond
+   (
+      ( true )
+      ( end-of-campaign )
+   )
+)
++Level: 1
++Position: 0
+
+$Mission: m3.fs2
++Formula:
+( cond
+   (
+      ( true )
+      ( next-mission \"m2.fs2\" )
+   )
+)
++Level: 1
++Position: 1
+
+$Mission: secret.fs2
++Formula:
+( cond
+   (
+      ( true )
+      ( end-of-campaign )
+   )
+)
++Level: 2
++Position: 0
+
+#End
+";
+
+	#[test]
+	fn parse() {
+		let campaign = Campaign::parse(FSOTableFileParser::from_string(CAMPAIGN)).unwrap();
+
+		assert_eq!(campaign.name, "Test Campaign");
+		assert!(matches!(campaign.campaign_type, CampaignType::Single));
+		assert_eq!(campaign.description.as_ref().unwrap().0.text, "A short test campaign");
+		assert_eq!(campaign.starting_ships.as_ref().unwrap().0, vec!["GTF Ulysses"]);
+
+		let missions = &campaign.missions.missions;
+		assert_eq!(missions.len(), 4);
+		assert_eq!(missions[0].debriefing_persona_index, Some(1));
+		assert_eq!(missions[0].formula.as_ref().unwrap().operator(), Some("cond"));
+		assert_eq!(missions[0].mission_loop.as_ref().unwrap().brief_anim.as_deref(), Some("<default>"));
+		assert_eq!(missions[0].level, Some(0));
+		assert_eq!(missions[2].position, Some(1));
+
+		let respewed = Campaign::parse(FSOTableFileParser::from_string(campaign.spew())).unwrap();
+		assert_eq!(respewed.missions.missions[0].formula, missions[0].formula);
+		assert_eq!(respewed.missions.missions[3].level, Some(2));
+	}
+
+	#[test]
+	fn comments() {
+		//The campaign only has a table end, whose comments have their own place next to those of the last field
+		let commented = CAMPAIGN.replacen("#Missions", "; the missions\n#Missions", 1).replacen("\n#End", "\n; closing\n#End", 1);
+		let spewed = Campaign::parse(FSOTableFileParser::from_string(commented.as_str())).unwrap().spew();
+		let missions = spewed.find("; the missions").unwrap();
+		let closing = spewed.find("; closing").unwrap();
+		assert!(missions < spewed.find("#Missions").unwrap() && closing < spewed.find("#End").unwrap() && missions < closing, "{}", spewed);
+	}
+
+	#[test]
+	fn graph() {
+		let campaign = Campaign::parse(FSOTableFileParser::from_string(CAMPAIGN)).unwrap();
+		let graph = campaign.graph();
+
+		assert_eq!(graph.successors("m1.fs2"), vec!["m2.fs2", "M3", "loop.fs2"]);
+		assert_eq!(graph.branches[0].condition.operator(), Some("is-previous-event-true"));
+		assert!(graph.branches[2].is_loop);
+		assert_eq!(graph.branches[3].target, CampaignBranchTarget::EndOfCampaign);
+		assert_eq!(graph.branches[3].condition, Sexp::List(vec![Sexp::Atom("true".to_string())]));
+
+		assert_eq!(graph.unreachable_missions(), vec!["secret.fs2"]);
+		let undefined = graph.undefined_targets();
+		assert_eq!(undefined.len(), 1);
+		assert_eq!(undefined[0].target, CampaignBranchTarget::Mission("loop.fs2".to_string()));
+	}
+
+	#[test]
+	fn missing_missions() {
+		let campaign = Campaign::parse(FSOTableFileParser::from_string(CAMPAIGN)).unwrap();
+
+		let dir = crate::assets::tests::scratch_dir("missing_missions");
+		fs::create_dir_all(dir.join("data").join("missions")).unwrap();
+		for file in ["m1.fs2", "M2.FS2", "secret.fs2"] {
+			fs::write(dir.join("data").join("missions").join(file), b"#Mission Info").unwrap();
+		}
+
+		let mut index = AssetIndex::default();
+		index.add_mod_directory(&dir).unwrap();
+
+		let missing = index.find_missing(&campaign);
+		assert_eq!(missing.len(), 1);
+		assert_eq!(missing[0].filename, "m3.fs2");
+		assert_eq!(missing[0].referenced_by, "#Missions: m3.fs2");
+	}
+}
@@ -0,0 +1,12 @@
+use crate::assets::{AssetKind, AssetReference, ReferencesAssets};
+use crate::campaigns::Campaign;
+
+impl ReferencesAssets for Campaign {
+	fn referenced_assets(&self) -> Vec<AssetReference> {
+		self.missions.missions.iter().map(|mission| AssetReference {
+			filename: mission.filename.clone(),
+			kind: AssetKind::Mission,
+			referenced_by: format!("#Missions: {}", mission.filename)
+		}).collect()
+	}
+}
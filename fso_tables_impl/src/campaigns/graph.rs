@@ -0,0 +1,94 @@
+use fso_tables::Sexp;
+use crate::campaigns::Campaign;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CampaignBranchTarget {
+	Mission(String),
+	EndOfCampaign
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CampaignBranch {
+	pub from: String,
+	pub target: CampaignBranchTarget,
+	pub condition: Sexp,
+	//Whether the branch comes from the mission loop rather than the main formula
+	pub is_loop: bool
+}
+
+//The missions of a campaign in file order, with the branches between them. The first mission is where the campaign starts
+#[derive(Clone, Debug, PartialEq)]
+pub struct CampaignGraph {
+	pub missions: Vec<String>,
+	pub branches: Vec<CampaignBranch>
+}
+
+//The engine compares mission names case-insensitively and with or without extension
+fn same_mission(a: &str, b: &str) -> bool {
+	let strip = |name: &str| name.to_lowercase().trim_end_matches(".fs2").to_string();
+	strip(a) == strip(b)
+}
+
+impl CampaignGraph {
+	fn add_branches(&mut self, from: &str, formula: &Sexp, is_loop: bool) {
+		if formula.operator() != Some("cond") {
+			return;
+		}
+		for branch in formula.arguments() {
+			let elements = branch.elements();
+			let Some((condition, actions)) = elements.split_first() else { continue; };
+			for action in actions {
+				let target = match (action.operator(), action.arguments().first()) {
+					(Some("next-mission"), Some(Sexp::String(mission))) => { CampaignBranchTarget::Mission(mission.clone()) }
+					(Some("end-of-campaign"), _) => { CampaignBranchTarget::EndOfCampaign }
+					_ => { continue; }
+				};
+				self.branches.push(CampaignBranch { from: from.to_string(), target, condition: (*condition).clone(), is_loop });
+			}
+		}
+	}
+
+	pub fn successors(&self, mission: &str) -> Vec<&str> {
+		self.branches.iter().filter(|branch| same_mission(&branch.from, mission)).filter_map(|branch| match &branch.target {
+			CampaignBranchTarget::Mission(target) => { Some(target.as_str()) }
+			CampaignBranchTarget::EndOfCampaign => { None }
+		}).collect()
+	}
+
+	//Missions no chain of branches from the first mission leads to
+	pub fn unreachable_missions(&self) -> Vec<&str> {
+		let mut reached: Vec<&str> = Vec::new();
+		let mut to_visit: Vec<&str> = self.missions.first().map(String::as_str).into_iter().collect();
+		while let Some(mission) = to_visit.pop() {
+			if reached.iter().any(|other| same_mission(other, mission)) {
+				continue;
+			}
+			reached.push(mission);
+			to_visit.extend(self.successors(mission));
+		}
+		self.missions.iter().map(String::as_str).filter(|mission| !reached.iter().any(|other| same_mission(other, mission))).collect()
+	}
+
+	//Branches to missions that are not part of the campaign
+	pub fn undefined_targets(&self) -> Vec<&CampaignBranch> {
+		self.branches.iter().filter(|branch| match &branch.target {
+			CampaignBranchTarget::Mission(target) => { !self.missions.iter().any(|mission| same_mission(mission, target)) }
+			CampaignBranchTarget::EndOfCampaign => { false }
+		}).collect()
+	}
+}
+
+impl Campaign {
+	pub fn graph(&self) -> CampaignGraph {
+		let mut graph = CampaignGraph { missions: self.missions.missions.iter().map(|mission| mission.filename.clone()).collect(), branches: Vec::new() };
+		for mission in &self.missions.missions {
+			if let Some(formula) = &mission.formula {
+				graph.add_branches(&mission.filename, formula, false);
+			}
+			if let Some(mission_loop) = &mission.mission_loop {
+				graph.add_branches(&mission.filename, &mission_loop.formula, true);
+			}
+		}
+		graph
+	}
+}
@@ -0,0 +1,81 @@
+use fso_tables::{fso_table, Sexp};
+use crate::missions::{EntryKeys, MultiText, NameList, UnknownOption};
+
+//A .fc2 campaign file
+#[fso_table(table_end="#End", toplevel)]
+pub struct Campaign {
+	pub name: String,
+	#[fso_name="$Type:"]
+	pub campaign_type: CampaignType,
+	#[fso_name="+Description:"]
+	pub description: Option<MultiText>,
+	#[fso_name="+Num players:"]
+	pub num_players: Option<i32>,
+	pub flags: Option<i32>,
+	#[fso_name="+Campaign Intro Cutscene:"]
+	pub intro_cutscene: Option<String>,
+	#[fso_name="+Campaign End Cutscene:"]
+	pub end_cutscene: Option<String>,
+	#[fso_name="+Starting Ships:"]
+	pub starting_ships: Option<NameList>,
+	#[fso_name="+Starting Weapons:"]
+	pub starting_weapons: Option<NameList>,
+	#[unknown]
+	pub unknown_options: Vec<UnknownOption<Campaign>>,
+	#[unnamed]
+	pub missions: CampaignMissions
+}
+
+impl EntryKeys for Campaign {
+	const KEYS: &'static [&'static str] = &[];
+}
+
+#[fso_table]
+pub enum CampaignType {
+	#[fso_name="single"]
+	Single,
+	#[fso_name="multi coop"]
+	MultiCoop,
+	#[fso_name="multi teams"]
+	MultiTeams
+}
+
+#[fso_table(table_start="#Missions")]
+pub struct CampaignMissions {
+	#[unnamed]
+	pub missions: Vec<CampaignMission>
+}
+
+#[fso_table(prefix="+")]
+pub struct CampaignMission {
+	#[fso_name="$Mission:"]
+	pub filename: String,
+	pub flags: Option<i32>,
+	pub main_hall: Option<String>,
+	pub substitute_main_hall: Option<String>,
+	pub debriefing_persona_index: Option<i32>,
+	//A cond operator, whose branches each pick the next mission
+	pub formula: Option<Sexp>,
+	#[fso_name="+Mission Loop:"]
+	pub mission_loop: Option<CampaignMissionLoop>,
+	pub level: Option<i32>,
+	pub position: Option<i32>,
+	#[unknown]
+	pub unknown_options: Vec<UnknownOption<CampaignMission>>
+}
+
+impl EntryKeys for CampaignMission {
+	const KEYS: &'static [&'static str] = &["$Mission:"];
+}
+
+//An optional side branch the player is offered after the mission
+#[fso_table(prefix="+")]
+pub struct CampaignMissionLoop {
+	#[fso_name="+Mission Loop Text:"]
+	pub text: Option<MultiText>,
+	#[fso_name="+Mission Loop Brief Anim:"]
+	pub brief_anim: Option<String>,
+	#[fso_name="+Mission Loop Brief Sound:"]
+	pub brief_sound: Option<String>,
+	pub formula: Sexp
+}
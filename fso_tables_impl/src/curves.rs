@@ -1,20 +1,47 @@
 mod table;
 mod curve;
 mod builtins;
+mod calculus;
+mod bounds;
+mod baked;
+mod fit;
+mod plot;
 
 pub use table::*;
 #[allow(unused_imports)]
 pub use curve::*;
 pub use builtins::*;
+#[allow(unused_imports)]
+pub use calculus::*;
+pub use bounds::*;
+pub use baked::*;
+pub use fit::*;
+#[allow(unused_imports)]
+pub use plot::*;
 
 #[cfg(test)]
 mod tests {
+	use fso_tables::{fso_check_respew, fso_table, FSOFormatting, FSOParser, FSOSpewed, FSOStringBuilder, FSOTable, FSOTableBuilder, FSOTableFileParser};
+	use proptest::prelude::*;
 	use crate::curves::*;
+	use crate::{merge_table_files, TableKind};
+
+	#[fso_table]
+	struct Numbers {
+		#[fso_name="$Numbers:"]
+		numbers: Vec<i32>
+	}
+
+	fn spew(table: &impl FSOTable) -> String {
+		let mut builder = FSOTableBuilder::default();
+		table.spew(&mut builder);
+		builder.spew()
+	}
 
 	#[test]
 	fn builtin() {
 		let curve = BUILTIN_CURVES.iter().find(|curve| curve.name == "EaseInOutQuad");
-		let available_curves = BUILTIN_CURVES.iter().map(|c| c).collect::<Vec<&Curve>>();
+		let available_curves = BUILTIN_CURVES.iter().collect::<Vec<&Curve>>();
 
 		assert!(curve.is_some());
 
@@ -26,7 +53,7 @@ mod tests {
 		assert!((curve.calculate(0.75f32, &available_curves) - 0.875f32).abs() < 0.001);
 		assert!((curve.calculate(1f32, &available_curves) - 1f32).abs() < 0.001);
 
-		let (x_bounds, y_bounds) = curve.get_bounds();
+		let (x_bounds, y_bounds) = curve.get_bounds(&available_curves);
 
 		assert!((x_bounds.start - 0f32).abs() < 0.001);
 		assert!((x_bounds.end - 1f32).abs() < 0.001);
@@ -36,13 +63,13 @@ mod tests {
 
 	#[test]
 	fn subcurve() {
-		let available_curves = BUILTIN_CURVES.iter().map(|c| c).collect::<Vec<&Curve>>();
+		let available_curves = BUILTIN_CURVES.iter().collect::<Vec<&Curve>>();
 
 		let curve = Curve::new(
 			"".to_string(),
 			vec![
 				CurveKeyframe::new( (0f32, 0f32), CurveSegment::Subcurve { curve: "EaseInQuad".to_string() } ),
-				CurveKeyframe::new( (0.5f32, 0.5f32), CurveSegment::Polynomial { ease_in: Some(true), degree: 2f32 } ),
+				CurveKeyframe::new( (0.5f32, 0.5f32), CurveSegment::Polynomial { ease_in: Some(true), degree: 2f32.into() } ),
 				CurveKeyframe::new( (1f32, 1f32), CurveSegment::Constant )
 			]
 		);
@@ -53,4 +80,379 @@ mod tests {
 		assert!((curve.calculate(0.75f32, &available_curves) - 0.625f32).abs() < 0.001);
 		assert!((curve.calculate(1f32, &available_curves) - 1f32).abs() < 0.001);
 	}
+
+	#[test]
+	fn calculus() {
+		let available_curves = BUILTIN_CURVES.iter().collect::<Vec<&Curve>>();
+
+		let curve = Curve::new(
+			"".to_string(),
+			vec![
+				CurveKeyframe::new( (0f32, 0f32), CurveSegment::Polynomial { ease_in: Some(true), degree: 2f32.into() } ),
+				CurveKeyframe::new( (1f32, 1f32), CurveSegment::Circular { ease_in: Some(false) } ),
+				CurveKeyframe::new( (2f32, 3f32), CurveSegment::Subcurve { curve: "EaseInOutQuad".to_string() } ),
+				CurveKeyframe::new( (4f32, 4f32), CurveSegment::Constant )
+			]
+		);
+
+		//Check the analytic results against finite differences and sums
+		for x in [0.25f32, 0.5f32, 1.5f32, 1.9f32, 2.5f32, 3.5f32] {
+			let numeric = (curve.calculate(x + 0.001f32, &available_curves) - curve.calculate(x - 0.001f32, &available_curves)) / 0.002f32;
+			assert!((curve.derivative(x, &available_curves) - numeric).abs() < 0.01, "derivative at {x}");
+		}
+		assert_eq!(curve.derivative(5f32, &available_curves), 0f32);
+
+		let samples = 10000;
+		let numeric = (0..samples).map(|i| curve.calculate((i as f32 + 0.5f32) * 5f32 / samples as f32, &available_curves)).sum::<f32>() * 5f32 / samples as f32;
+		assert!((curve.integral(0f32..5f32, &available_curves) - numeric).abs() < 0.01);
+		assert!((curve.integral(0f32..1f32, &available_curves) - 1f32 / 3f32).abs() < 0.001);
+		assert!((curve.integral(1f32..0f32, &available_curves) + 1f32 / 3f32).abs() < 0.001);
+
+		for x in [0.25f32, 0.5f32, 1.5f32, 1.9f32, 2.5f32, 3.5f32, 4f32] {
+			let y = curve.calculate(x, &available_curves);
+			assert!((curve.inverse(y, &available_curves).unwrap() - x).abs() < 0.001, "inverse at {x}");
+		}
+		assert!(curve.inverse(5f32, &available_curves).is_none());
+	}
+
+	#[test]
+	fn baked() {
+		let available_curves = BUILTIN_CURVES.iter().collect::<Vec<&Curve>>();
+
+		let curve = Curve::new(
+			"".to_string(),
+			vec![
+				CurveKeyframe::new( (0f32, 0f32), CurveSegment::Subcurve { curve: "EaseInOutQuad".to_string() } ),
+				CurveKeyframe::new( (1f32, 1f32), CurveSegment::Constant ),
+				CurveKeyframe::new( (2f32, 2f32), CurveSegment::Circular { ease_in: Some(false) } ),
+				CurveKeyframe::new( (3f32, 0f32), CurveSegment::Constant )
+			]
+		);
+		let xs = (0..=400).map(|i| i as f32 / 100f32 - 0.5f32).collect::<Vec<f32>>();
+
+		let uniform = curve.bake(1024, &available_curves);
+		assert_eq!(uniform.points().len(), 1024);
+		//Evenly spaced points can't follow the jumps
+		assert!(uniform.max_error > 0.1);
+		assert!((uniform.evaluate(0.5f32) - 0.5f32).abs() < 0.001);
+		for x in &xs {
+			assert!((uniform.evaluate(*x) - curve.calculate(*x, &available_curves)).abs() <= uniform.max_error, "at {x}");
+		}
+
+		//The overshoot of a Bezier between two points is within the bound as well
+		let back = BUILTIN_CURVES.iter().find(|curve| curve.name == "EaseInOutBack").unwrap();
+		let coarse = back.bake(3, &available_curves);
+		let worst = xs.iter().map(|x| x / 4f32 + 0.125f32).map(|x| (coarse.evaluate(x) - back.calculate(x, &available_curves)).abs()).fold(0f32, f32::max);
+		assert!(worst > 0.1 && worst <= coarse.max_error, "{worst} {}", coarse.max_error);
+
+		let adaptive = curve.bake_adaptive(0.001, &available_curves);
+		//Points can't get closer than f32 allows to the vertical start of the circular segment, so the bound can't quite reach the tolerance there
+		assert!(adaptive.max_error <= 0.002);
+		assert!(adaptive.points().len() < 1024);
+		let baked = adaptive.evaluate_many(&xs);
+		for (x, y) in xs.iter().zip(baked) {
+			assert!((curve.calculate(*x, &available_curves) - y).abs() <= 0.001, "at {x}");
+		}
+	}
+
+	#[test]
+	fn segments() {
+		let available_curves = BUILTIN_CURVES.iter().collect::<Vec<&Curve>>();
+		let builtin = |name: &str| BUILTIN_CURVES.iter().find(|curve| curve.name == name).unwrap();
+
+		assert!((builtin("EaseInSine").calculate(0.5f32, &available_curves) - 0.2929f32).abs() < 0.001);
+		assert!((builtin("EaseOutExpo").calculate(0.5f32, &available_curves) - 0.9697f32).abs() < 0.001);
+		let back = builtin("EaseInOutBack");
+		assert!((back.calculate(0.5f32, &available_curves) - 0.5f32).abs() < 0.001);
+		assert!(back.calculate(0.1f32, &available_curves) < 0f32);
+		assert!(back.calculate(0.9f32, &available_curves) > 1f32);
+
+		let table = CurveTable::parse(FSOTableFileParser::from_string("#Curves
+$Name: Segments
+$KeyFrames:
+	(0, 0): Bezier, 0.25, 0.1, 0.25, 1
+	(1, 1): Sine, false
+	(2, 2): Step, 4
+	(3, 3): Exponential, 10, true
+	(4, 4): Constant
+#End
+")).unwrap();
+		let curve = &table.curves[0];
+		assert!(curve.keyframes[2].segment == CurveSegment::Step { steps: 4 });
+		assert!((curve.calculate(0.5f32, &available_curves) - 0.8024f32).abs() < 0.001);
+		assert!((curve.calculate(1.5f32, &available_curves) - 1.7071f32).abs() < 0.001);
+		assert!((curve.calculate(2.6f32, &available_curves) - 2.5f32).abs() < 0.001);
+		assert!((curve.calculate(3.5f32, &available_curves) - 3.0303f32).abs() < 0.001);
+
+		for x in [0.3f32, 1.5f32, 2.6f32, 3.5f32] {
+			let numeric = (curve.calculate(x + 0.001f32, &available_curves) - curve.calculate(x - 0.001f32, &available_curves)) / 0.002f32;
+			assert!((curve.derivative(x, &available_curves) - numeric).abs() < 0.01, "derivative at {x}");
+		}
+		let samples = 10000;
+		let numeric = (0..samples).map(|i| curve.calculate((i as f32 + 0.5f32) * 4f32 / samples as f32, &available_curves)).sum::<f32>() * 4f32 / samples as f32;
+		assert!((curve.integral(0f32..4f32, &available_curves) - numeric).abs() < 0.01);
+		assert!((curve.inverse(0.8024f32, &available_curves).unwrap() - 0.5f32).abs() < 0.001);
+		assert!((curve.inverse(3.0303f32, &available_curves).unwrap() - 3.5f32).abs() < 0.001);
+
+		let respewed = CurveTable::parse(FSOTableFileParser::from_string(table.spew())).unwrap();
+		assert!(respewed.curves[0].keyframes.iter().zip(&curve.keyframes).all(|(a, b)| a.pos == b.pos && a.segment == b.segment));
+	}
+
+	#[test]
+	fn fit() {
+		let available_curves = BUILTIN_CURVES.iter().collect::<Vec<&Curve>>();
+		let ease = BUILTIN_CURVES.iter().find(|curve| curve.name == "EaseInOutCubic").unwrap();
+
+		//An ease in and out over the first two seconds, holding afterwards
+		let csv = "time;value\n".to_string() + &(0..=60).map(|i| {
+			let x = i as f32 / 20f32;
+			format!("{}; {}\n", x, ease.calculate(x / 2f32, &available_curves) * 10f32)
+		}).collect::<String>();
+		let samples = parse_curve_samples(&csv).unwrap();
+		assert_eq!(samples.len(), 61);
+
+		let curve = Curve::fit("Fitted".to_string(), &samples, 0.01f32).unwrap();
+		assert_eq!(curve.keyframes.len(), 4);
+		assert!(curve.keyframes[0].segment == CurveSegment::Polynomial { degree: 3f32.into(), ease_in: Some(true) });
+		assert!(curve.keyframes[1].segment == CurveSegment::Polynomial { degree: 3f32.into(), ease_in: Some(false) });
+		for (x, y) in &samples {
+			assert!((curve.calculate(*x, &available_curves) - y).abs() <= 0.01f32, "at {x}");
+		}
+
+		let table = CurveTable::parse(FSOTableFileParser::from_string(CurveTable::new(vec![curve]).spew())).unwrap();
+		assert_eq!(table.curves[0].name, "Fitted");
+		assert_eq!(table.curves[0].keyframes.len(), 4);
+
+		//Reaching as far as possible with the first segment, the line up to 3, leaves a tail no single segment fits
+		let samples = [(0f32, 0f32), (1f32, 1f32), (2f32, 2f32), (3f32, 3f32), (4f32, 6f32), (5f32, 11f32), (6f32, 18f32), (7f32, 27f32)];
+		let curve = Curve::fit("Kink".to_string(), &samples, 0.01f32).unwrap();
+		assert_eq!(curve.keyframes.iter().map(|keyframe| *keyframe.pos.0).collect::<Vec<f32>>(), vec![0f32, 2f32, 7f32]);
+		assert!(curve.keyframes[1].segment == CurveSegment::Polynomial { degree: 2f32.into(), ease_in: Some(true) });
+
+		assert!(parse_curve_samples("0, 1\n1, one\n").is_err_and(|err| err.line == 2));
+		assert!(Curve::fit("".to_string(), &[(1f32, 0f32), (1f32, 1f32)], 0.01f32).is_none());
+	}
+
+	#[test]
+	fn plot() {
+		let available_curves = BUILTIN_CURVES.iter().collect::<Vec<&Curve>>();
+		let curve = BUILTIN_CURVES.iter().find(|curve| curve.name == "EaseInOutQuad").unwrap();
+
+		let svg = curve.to_svg(&available_curves);
+		assert!(svg.starts_with("<svg"));
+		assert_eq!(svg.matches("<polyline").count(), 2);
+		assert_eq!(svg.matches("<circle").count(), 3);
+		assert!(svg.contains("Polynomial 2 out"));
+
+		let ascii = curve.to_ascii(21, 11, &available_curves);
+		let lines = ascii.lines().collect::<Vec<&str>>();
+		assert_eq!(lines[0], "1 |                 ***o");
+		assert_eq!(lines[5], "  |          o");
+		assert_eq!(lines[10], "0 |o***");
+		assert_eq!(lines[13], "0 to 0.5: Polynomial 2 in");
+	}
+
+	#[test]
+	fn bounds() {
+		let available_curves = BUILTIN_CURVES.iter().collect::<Vec<&Curve>>();
+
+		let curve = Curve::new(
+			"".to_string(),
+			vec![
+				CurveKeyframe::new( (0f32, 0f32), CurveSegment::Subcurve { curve: "EaseInOutBack".to_string() } ),
+				CurveKeyframe::new( (1f32, 1f32), CurveSegment::Linear ),
+				CurveKeyframe::new( (2f32, 2f32), CurveSegment::Constant ),
+				CurveKeyframe::new( (3f32, 0.5f32), CurveSegment::Constant )
+			]
+		);
+
+		let samples = (0..=30000).map(|i| {
+			let x = i as f32 / 10000f32;
+			(x, curve.calculate(x, &available_curves))
+		}).collect::<Vec<(f32, f32)>>();
+		let lowest = samples.iter().fold(samples[0], |lowest, point| if point.1 < lowest.1 { *point } else { lowest });
+		let highest = samples.iter().fold(samples[0], |highest, point| if point.1 > highest.1 { *point } else { highest });
+
+		let extrema = curve.get_extrema(&available_curves);
+		assert!(lowest.1 < -0.04f32);
+		assert!((extrema.min.0 - lowest.0).abs() < 0.01 && (extrema.min.1 - lowest.1).abs() < 0.001);
+		assert!((extrema.max.0 - highest.0).abs() < 0.01 && (extrema.max.1 - highest.1).abs() < 0.001);
+
+		let (x_bounds, y_bounds) = curve.get_bounds(&available_curves);
+		assert_eq!(x_bounds, 0f32..3f32);
+		assert_eq!(y_bounds, extrema.min.1..extrema.max.1);
+	}
+
+	#[test]
+	fn round_trip() {
+		let table = "; Curves for the HUD\n#Curves\n\n$Name:  Fade   ;fades out\n$KeyFrames:\n   ( 0.0, 1.0 ) : Linear\n\t(1, 0.50):Constant\n\n$Name: Pop\n$KeyFrames:\n  (0, 0) : Polynomial, 2.0, NO\n  (1, 1) : Constant\n\n#End\n";
+		let mut curves = CurveTable::parse(FSOTableFileParser::from_string(table).with_round_trip()).unwrap();
+		assert_eq!(curves.spew_round_trip(), table);
+
+		//Only the changed keyframe is written anew
+		curves.curves[1].keyframes[1].pos.1 = 2f32.into();
+		let changed = curves.spew_round_trip();
+		let (before, after) = table.split_once("  (1, 1) : Constant").unwrap();
+		assert!(changed.starts_with(before) && changed.ends_with(after), "{:?}", changed);
+		//The changed keyframe is laid out anew as a whole, as spew would, behind the whitespace it had
+		assert!(changed.contains("\n  (1, 2): Constant\n"), "{:?}", changed);
+		let reparsed = CurveTable::parse(FSOTableFileParser::from_string(changed)).unwrap();
+		assert_eq!(reparsed.curves[1].keyframes[1].point(), (1f32, 2f32));
+		assert!(reparsed.curves[1].keyframes[0].segment == CurveSegment::Polynomial { degree: 2f32.into(), ease_in: Some(false) });
+	}
+
+	#[test]
+	fn merge() {
+		//Keyframes have no names, so their changes follow them to where the other side moved them
+		let base = "#Curves\n$Name: Fade\n$KeyFrames:\n(0, 0): Linear\n(1, 1): Constant\n#End\n";
+		let ours = "#Curves\n$Name: Fade\n$KeyFrames:\n(-1, 0): Linear\n(0, 0): Linear\n(1, 1): Constant\n#End\n";
+		let theirs = "#Curves\n$Name: Fade\n$KeyFrames:\n(0, 0): Circular\n(1, 1): Constant\n#End\n";
+		let (merged, conflicts) = merge_table_files(TableKind::Curves, base, ours, theirs).unwrap();
+		assert!(conflicts.is_empty(), "{:?}", conflicts);
+		let merged = CurveTable::parse(FSOTableFileParser::from_string(merged)).unwrap();
+		assert_eq!(merged.curves[0].keyframes.iter().map(|keyframe| keyframe.point()).collect::<Vec<(f32, f32)>>(), vec![(-1f32, 0f32), (0f32, 0f32), (1f32, 1f32)]);
+		assert!(merged.curves[0].keyframes[0].segment == CurveSegment::Linear);
+		assert!(merged.curves[0].keyframes[1].segment == CurveSegment::Circular { ease_in: None });
+
+		//As well as to where they went after ours removed some before them
+		let ours = "#Curves\n$Name: Fade\n$KeyFrames:\n(1, 1): Constant\n#End\n";
+		let theirs = "#Curves\n$Name: Fade\n$KeyFrames:\n(0, 0): Linear\n(1, 1): Sine\n#End\n";
+		let (merged, conflicts) = merge_table_files(TableKind::Curves, base, ours, theirs).unwrap();
+		assert!(conflicts.is_empty(), "{:?}", conflicts);
+		let merged = CurveTable::parse(FSOTableFileParser::from_string(merged)).unwrap();
+		assert_eq!(merged.curves[0].keyframes.len(), 1);
+		assert!(merged.curves[0].keyframes[0].segment == CurveSegment::Sine { ease_in: None });
+
+		//A keyframe both sides changed is a conflict, reported where it was in base
+		let theirs = "#Curves\n$Name: Fade\n$KeyFrames:\n(0, 0): Circular\n(1, 1): Constant\n#End\n";
+		let ours = "#Curves\n$Name: Fade\n$KeyFrames:\n(-1, 0): Linear\n(0, 0): Sine\n(1, 1): Constant\n#End\n";
+		let (merged, conflicts) = merge_table_files(TableKind::Curves, base, ours, theirs).unwrap();
+		assert_eq!(conflicts.iter().map(|conflict| conflict.path.to_string()).collect::<Vec<String>>(), vec!["[name=Fade].keyframes[0].segment"]);
+		assert!(CurveTable::parse(FSOTableFileParser::from_string(merged)).unwrap().curves[0].keyframes[1].segment == CurveSegment::Sine { ease_in: None });
+
+		//A keyframe both sides removed is removed once
+		let base = "#Curves\n$Name: Fade\n$KeyFrames:\n(0, 0): Linear\n(0.5, 0.5): Linear\n(1, 1): Constant\n#End\n";
+		let both = "#Curves\n$Name: Fade\n$KeyFrames:\n(0, 0): Linear\n(1, 1): Constant\n#End\n";
+		let (merged, conflicts) = merge_table_files(TableKind::Curves, base, both, both).unwrap();
+		assert!(conflicts.is_empty(), "{:?}", conflicts);
+		let merged = CurveTable::parse(FSOTableFileParser::from_string(merged)).unwrap();
+		assert_eq!(merged.curves[0].keyframes.iter().map(|keyframe| keyframe.point()).collect::<Vec<(f32, f32)>>(), vec![(0f32, 0f32), (1f32, 1f32)]);
+
+		//Keyframes they inserted go in front of the same keyframe in ours, unless ours inserted them already
+		let ours = "#Curves\n$Name: Fade\n$KeyFrames:\n(0, 0): Sine\n(0.5, 0.5): Linear\n(1, 1): Constant\n#End\n";
+		let theirs = "#Curves\n$Name: Fade\n$KeyFrames:\n(0, 0): Linear\n(0.25, 0): Linear\n(0.5, 0.5): Linear\n(0.75, 1): Linear\n(1, 1): Constant\n#End\n";
+		let (merged, conflicts) = merge_table_files(TableKind::Curves, base, ours, theirs).unwrap();
+		assert!(conflicts.is_empty(), "{:?}", conflicts);
+		let merged = CurveTable::parse(FSOTableFileParser::from_string(merged)).unwrap();
+		assert_eq!(merged.curves[0].keyframes.iter().map(|keyframe| keyframe.point()).collect::<Vec<(f32, f32)>>(), vec![(0f32, 0f32), (0.25f32, 0f32), (0.5f32, 0.5f32), (0.75f32, 1f32), (1f32, 1f32)]);
+		assert!(merged.curves[0].keyframes[0].segment == CurveSegment::Sine { ease_in: None });
+		let (merged, conflicts) = merge_table_files(TableKind::Curves, base, theirs, theirs).unwrap();
+		assert!(conflicts.is_empty(), "{:?}", conflicts);
+		assert_eq!(CurveTable::parse(FSOTableFileParser::from_string(merged)).unwrap().curves[0].keyframes.len(), 5);
+	}
+
+	#[test]
+	fn key_case() {
+		//FSO matches keys and segment names regardless of case, spewing gives them their declared casing again
+		let table = "#CURVES\n$name: Fade\n$KEYFRAMES:\n(0, 1): linear\n(1, 0): CONSTANT\n#end\n";
+		let curves = CurveTable::parse(FSOTableFileParser::from_string(table)).unwrap();
+		assert_eq!(curves.curves[0].name, "Fade");
+		assert!(curves.curves[0].keyframes[0].segment == CurveSegment::Linear);
+		assert!(curves.curves[0].keyframes[1].segment == CurveSegment::Constant);
+
+		let spewed = curves.spew();
+		assert_eq!(spewed.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<&str>>(), vec!["#Curves", "$Name: Fade", "$KeyFrames:", "(0, 1): Linear", "(1, 0): Constant", "#End"]);
+	}
+
+	#[test]
+	fn float_literals() {
+		let table = "#Curves\n$Name: Literal\n$KeyFrames:\n(0.50, 1.): Polynomial, 1e-3, YES\n(1., 0.50): Constant\n#End\n";
+		let mut curves = CurveTable::parse(FSOTableFileParser::from_string(table)).unwrap();
+		assert_eq!(curves.curves[0].keyframes[0].point(), (0.5f32, 1f32));
+		assert!(curves.curves[0].keyframes[0].segment == CurveSegment::Polynomial { degree: 0.001f32.into(), ease_in: Some(true) });
+
+		let spewed = curves.spew();
+		for line in ["(0.50, 1.): Polynomial, 1e-3, YES", "(1., 0.50): Constant"] {
+			assert!(spewed.lines().any(|spewed_line| spewed_line.trim() == line), "{} missing in {}", line, spewed);
+		}
+
+		//A changed value forgets its literal
+		curves.curves[0].keyframes[1].pos.1 = 0.25f32.into();
+		assert!(curves.spew().lines().any(|line| line.trim() == "(1., 0.25): Constant"));
+	}
+
+	#[test]
+	fn comments() {
+		let table = "#Curves ; all curves\n$Name: Fade ; after name\n$KeyFrames: ; after key\n  (0, 1) : Linear ; first\n  ; between\n  (1, 0) : Constant ; last\n; dangling\n#End\n";
+		let curves = CurveTable::parse(FSOTableFileParser::from_string(table)).unwrap();
+		assert_eq!(curves.curves[0].name, "Fade");
+
+		let spewed = curves.spew();
+		let lines = spewed.lines().map(str::trim_end).collect::<Vec<&str>>();
+		for line in ["#Curves ; all curves", "$Name: Fade ; after name", "$KeyFrames: ; after key", "(0, 1): Linear ; first", "; between", "(1, 0): Constant ; last", "; dangling", "#End"] {
+			assert!(lines.contains(&line), "{} missing in {}", line, spewed);
+		}
+		assert!(lines.iter().position(|line| *line == "; dangling") < lines.iter().position(|line| *line == "#End"));
+
+		let respewed = CurveTable::parse(FSOTableFileParser::from_string(spewed.as_str())).unwrap().spew();
+		assert_eq!(respewed, spewed);
+	}
+
+	#[test]
+	fn lists() {
+		//Keyframe lists end at the next $Name:, even without a blank line, and may be empty
+		let table = "#Curves\n$Name: Empty\n$KeyFrames:\n$Name: Ramp\n$KeyFrames:\n(0, 0) : Linear\n(0.5, 1) : Polynomial, 2, false\n(1, 1) : Ramp2\n$Name: Ramp2\n$KeyFrames: (0, 0) : Linear (1, 1) : Constant\n#End\n";
+		let curves = CurveTable::parse(FSOTableFileParser::from_string(table)).unwrap();
+		assert_eq!(curves.curves.iter().map(|curve| curve.keyframes.len()).collect::<Vec<usize>>(), vec![0, 3, 2]);
+		assert!(curves.curves[1].keyframes[1].segment == CurveSegment::Polynomial { degree: 2f32.into(), ease_in: Some(false) });
+		assert!(curves.curves[1].keyframes[2].segment == CurveSegment::Subcurve { curve: "Ramp2".to_string() });
+
+		let spewed = curves.spew();
+		let reparsed = CurveTable::parse(FSOTableFileParser::from_string(spewed.as_str())).unwrap();
+		assert_eq!(reparsed.curves.iter().map(|curve| curve.keyframes.len()).collect::<Vec<usize>>(), vec![0, 3, 2]);
+		assert_eq!(reparsed.spew(), spewed);
+
+		//Inline lists separate their entries, and the comment after the closing parenthesis is handed on
+		let parser = FSOTableFileParser::from_string("$Numbers: ( 1 2 3 ) ; all of them\n");
+		let (numbers, gobble) = Numbers::parse(&parser, None).unwrap();
+		assert_eq!(numbers.numbers, vec![1, 2, 3]);
+		assert!(parser.get().is_empty());
+		assert!(gobble.is_some_and(|gobble| gobble.comments.is_some_and(|comments| comments.contains("all of them"))));
+		let (respewed, _) = Numbers::parse(&FSOTableFileParser::from_string(spew(&numbers)), None).unwrap();
+		assert_eq!(respewed.numbers, vec![1, 2, 3]);
+	}
+
+	fn segment() -> impl Strategy<Value = FSOSpewed<CurveSegment>> {
+		let ease_in = proptest::option::of(any::<bool>());
+		prop_oneof![
+			Just(FSOSpewed(CurveSegment::Constant)),
+			Just(FSOSpewed(CurveSegment::Linear)),
+			(-8f32..8f32, ease_in.clone()).prop_map(|(degree, ease_in)| FSOSpewed(CurveSegment::Polynomial { degree: degree.into(), ease_in })),
+			ease_in.clone().prop_map(|ease_in| FSOSpewed(CurveSegment::Circular { ease_in })),
+			(0f32..1f32, -2f32..2f32, 0f32..1f32, -2f32..2f32).prop_map(|(x1, y1, x2, y2)| FSOSpewed(CurveSegment::Bezier { x1: x1.into(), y1: y1.into(), x2: x2.into(), y2: y2.into() })),
+			ease_in.clone().prop_map(|ease_in| FSOSpewed(CurveSegment::Sine { ease_in })),
+			(1u32..64).prop_map(|steps| FSOSpewed(CurveSegment::Step { steps })),
+			(-8f32..8f32, ease_in).prop_map(|(exponent, ease_in)| FSOSpewed(CurveSegment::Exponential { exponent: exponent.into(), ease_in })),
+			"[A-Z][a-z]{0,8}[0-9]".prop_map(|curve| FSOSpewed(CurveSegment::Subcurve { curve }))
+		]
+	}
+
+	fn curve_table() -> impl Strategy<Value = FSOSpewed<CurveTable>> {
+		let keyframe = ((-1e4f32..1e4f32, -1e4f32..1e4f32), segment()).prop_map(|(pos, segment)| FSOSpewed(CurveKeyframe::new(pos, segment.0)));
+		let curve = ("[A-Za-z][A-Za-z0-9 ]{0,12}[A-Za-z0-9]", prop::collection::vec(keyframe, 0..6)).prop_map(|(name, keyframes)| {
+			FSOSpewed(Curve::new(name, keyframes.into_iter().map(|keyframe| keyframe.0).collect()))
+		});
+		prop::collection::vec(curve, 0..5).prop_map(|curves| FSOSpewed(CurveTable::new(curves.into_iter().map(|curve| curve.0).collect())))
+	}
+
+	proptest! {
+		#[test]
+		fn respew(table in curve_table()) {
+			for formatting in [FSOFormatting::default(), FSOFormatting::canonical()] {
+				if let Err(problem) = fso_check_respew(&table.0, formatting) {
+					return Err(TestCaseError::fail(problem.to_string()));
+				}
+			}
+		}
+	}
 }
@@ -0,0 +1,130 @@
+use crate::curves::*;
+
+//How deep adaptive baking may split a segment, limiting it to 2^20 intervals per segment
+const BAKE_MAX_DEPTH: u32 = 20;
+//Pieces each interval is split into to bound the error of a baked curve. More pieces give a tighter bound
+const BAKE_ERROR_PIECES: usize = 8;
+
+//A curve sampled into line segments, with its subcurves resolved, so that evaluating it neither walks keyframes nor looks up subcurves
+#[derive(Clone, Debug)]
+pub struct BakedCurve {
+	//Sorted by x. Where the curve jumps, two points share an x, the second one being the value from there on
+	points: Vec<(f32, f32)>,
+	//Set if the points are evenly spaced, so the interval for an x can be computed instead of searched
+	step: Option<f32>,
+	//Bound on the difference to the original curve, from where the curve can be on each piece of every interval
+	pub max_error: f32
+}
+
+impl Curve {
+	//Samples the curve at resolution evenly spaced points. Jumps at keyframes get smoothed over, which shows in the error
+	pub fn bake(&self, resolution: usize, curves: &Vec<&Curve>) -> BakedCurve {
+		assert!(self.keyframes.len() >= 2);
+		let resolution = resolution.max(2);
+		let start = *self.keyframes[0].pos.0;
+		let step = (*self.keyframes[self.keyframes.len() - 1].pos.0 - start) / (resolution - 1) as f32;
+
+		let points = (0..resolution).map(|i| {
+			let x = if i == resolution - 1 { *self.keyframes[self.keyframes.len() - 1].pos.0 } else { start + i as f32 * step };
+			(x, self.calculate(x, curves))
+		}).collect();
+
+		let mut baked = BakedCurve { points, step: (step > 0f32).then_some(step), max_error: 0f32 };
+		baked.max_error = baked.error_bound(self, curves);
+		baked
+	}
+
+	//Samples the curve with as few points as needed to stay within tolerance, keeping jumps at keyframes.
+	//Near vertical parts of a segment may stop splitting at the depth limit, max_error tells whether the tolerance was kept
+	pub fn bake_adaptive(&self, tolerance: f32, curves: &Vec<&Curve>) -> BakedCurve {
+		assert!(self.keyframes.len() >= 2);
+		let mut points = Vec::new();
+
+		for (current, next) in self.keyframes.iter().zip(&self.keyframes[1..]) {
+			if next.pos.0 <= current.pos.0 {
+				continue;
+			}
+			//If the previous segment ended elsewhere, this keeps the jump
+			points.push(current.point());
+			let end = (*next.pos.0, current.segment.calculate(*next.pos.0, current, next, curves));
+			refine(current, next, curves, current.point(), end, tolerance, 0, &mut points);
+		}
+
+		let last = self.keyframes[self.keyframes.len() - 1].point();
+		if points.last() != Some(&last) {
+			points.push(last);
+		}
+		//Drop points not followed by a jump that repeat the previous one
+		points.dedup();
+
+		let mut baked = BakedCurve { points, step: None, max_error: 0f32 };
+		baked.max_error = baked.error_bound(self, curves);
+		baked
+	}
+}
+
+//Adds the points after start needed to follow the segment from current to next up to end within the tolerance
+#[allow(clippy::too_many_arguments)]
+fn refine(current: &CurveKeyframe, next: &CurveKeyframe, curves: &Vec<&Curve>, start: (f32, f32), end: (f32, f32), tolerance: f32, depth: u32, points: &mut Vec<(f32, f32)>) {
+	if error_bound(current, next, curves, start, end, start.0, end.0) <= tolerance || depth >= BAKE_MAX_DEPTH {
+		points.push(end);
+	}
+	else {
+		let middle = (start.0 + end.0) / 2f32;
+		let middle = (middle, current.segment.calculate(middle, current, next, curves));
+		refine(current, next, curves, start, middle, tolerance, depth + 1, points);
+		refine(current, next, curves, middle, end, tolerance, depth + 1, points);
+	}
+}
+
+//How far the line from start to end can be from the segment from current to next between from and to.
+//On each piece, the segment stays within its extrema there and the line between its values at the ends of the piece,
+//so the distance is at most the larger of the two gaps between those ranges
+fn error_bound(current: &CurveKeyframe, next: &CurveKeyframe, curves: &Vec<&Curve>, start: (f32, f32), end: (f32, f32), from: f32, to: f32) -> f32 {
+	let line = |x: f32| start.1 + (end.1 - start.1) * (x - start.0) / (end.0 - start.0);
+	(0..BAKE_ERROR_PIECES).map(|i| {
+		let piece_from = from + (to - from) * i as f32 / BAKE_ERROR_PIECES as f32;
+		let piece_to = if i == BAKE_ERROR_PIECES - 1 { to } else { from + (to - from) * (i + 1) as f32 / BAKE_ERROR_PIECES as f32 };
+		let extrema = current.segment_extrema(next, piece_from, piece_to, curves);
+		let (line_from, line_to) = (line(piece_from), line(piece_to));
+		(extrema.max.1 - line_from.min(line_to)).max(line_from.max(line_to) - extrema.min.1)
+	}).fold(0f32, f32::max)
+}
+
+impl BakedCurve {
+	pub fn points(&self) -> &[(f32, f32)] {
+		&self.points
+	}
+
+	pub fn evaluate(&self, x: f32) -> f32 {
+		let first = self.points[0];
+		let last = self.points[self.points.len() - 1];
+		if x < first.0 {
+			return first.1;
+		}
+		else if x >= last.0 {
+			return last.1;
+		}
+
+		let index = match self.step {
+			Some(step) => { (((x - first.0) / step) as usize).min(self.points.len() - 2) }
+			None => { self.points.partition_point(|point| point.0 <= x) - 1 }
+		};
+		let (start, end) = (self.points[index], self.points[index + 1]);
+		start.1 + (end.1 - start.1) * (x - start.0) / (end.0 - start.0)
+	}
+
+	pub fn evaluate_many(&self, xs: &[f32]) -> Vec<f32> {
+		xs.iter().map(|x| self.evaluate(*x)).collect()
+	}
+
+	//Intervals spanning keyframes are bounded on each segment they cover
+	fn error_bound(&self, curve: &Curve, curves: &Vec<&Curve>) -> f32 {
+		self.points.iter().zip(&self.points[1..]).filter(|(start, end)| end.0 > start.0).flat_map(|(start, end)| {
+			curve.keyframes.iter().zip(&curve.keyframes[1..]).filter_map(move |(current, next)| {
+				let (from, to) = (start.0.max(*current.pos.0), end.0.min(*next.pos.0));
+				(from < to).then(|| error_bound(current, next, curves, *start, *end, from, to))
+			})
+		}).fold(0f32, f32::max)
+	}
+}
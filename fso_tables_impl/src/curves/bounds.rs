@@ -0,0 +1,107 @@
+use std::ops::Range;
+
+use fso_tables::FSOFloat;
+
+use crate::curves::*;
+
+//Where a curve is lowest and highest, as (x, y) positions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CurveExtrema {
+	pub min: (f32, f32),
+	pub max: (f32, f32)
+}
+
+impl CurveExtrema {
+	fn including(self, point: (f32, f32)) -> Self {
+		CurveExtrema {
+			min: if point.1 < self.min.1 { point } else { self.min },
+			max: if point.1 > self.max.1 { point } else { self.max }
+		}
+	}
+}
+
+impl Curve {
+	//The x range between the first and last keyframe, and the y range the curve covers over it
+	pub fn get_bounds(&self, curves: &Vec<&Curve>) -> (Range<f32>, Range<f32>) {
+		assert!(self.keyframes.len() >= 2);
+		let first = self.keyframes.first().unwrap();
+		let last = self.keyframes.last().unwrap();
+		let x_bounds = *first.pos.0..*last.pos.0;
+
+		let extrema = self.get_extrema(curves);
+		let y_bounds = extrema.min.1..extrema.max.1;
+
+		(x_bounds, y_bounds)
+	}
+
+	//The lowest and highest points between the first and last keyframe, including where segments overshoot their keyframes.
+	//If the curve jumps at a keyframe, the value just before the jump counts as well
+	pub fn get_extrema(&self, curves: &Vec<&Curve>) -> CurveExtrema {
+		assert!(self.keyframes.len() >= 2);
+		self.extrema_between(*self.keyframes[0].pos.0, *self.keyframes[self.keyframes.len() - 1].pos.0, curves)
+	}
+
+	fn extrema_between(&self, start: f32, end: f32, curves: &Vec<&Curve>) -> CurveExtrema {
+		let point = |x: f32| (x, self.calculate(x, curves));
+		let mut extrema = CurveExtrema { min: point(start), max: point(start) }.including(point(end));
+
+		for (current, next) in self.keyframes.iter().zip(&self.keyframes[1..]) {
+			let (from, to) = (start.max(*current.pos.0), end.min(*next.pos.0));
+			if from >= to {
+				continue;
+			}
+			let segment = current.segment_extrema(next, from, to, curves);
+			extrema = extrema.including(segment.min).including(segment.max);
+		}
+		extrema
+	}
+}
+
+impl CurveKeyframe {
+	//The lowest and highest points of the segment from this keyframe to next between from and to, which have to lie within it.
+	//At next, this is the value the segment ends with, even if the curve jumps there
+	pub(crate) fn segment_extrema(&self, next: &CurveKeyframe, from: f32, to: f32, curves: &Vec<&Curve>) -> CurveExtrema {
+		let width = *next.pos.0 - *self.pos.0;
+		let height = *next.pos.1 - *self.pos.1;
+		let mut points = self.segment.extrema_from_delta((from - *self.pos.0) / width, (to - *self.pos.0) / width, curves).into_iter()
+			.map(|(t, value)| (*self.pos.0 + t * width, *self.pos.1 + value * height));
+		let first = points.next().unwrap();
+		points.fold(CurveExtrema { min: first, max: first }, CurveExtrema::including)
+	}
+}
+
+impl CurveSegment {
+	//The points, as (t, calculate_from_delta(t)), among which the segment's lowest and highest between start and end are
+	fn extrema_from_delta(&self, start: f32, end: f32, curves: &Vec<&Curve>) -> Vec<(f32, f32)> {
+		let mut points = vec![(start, self.calculate_from_delta(start, curves)), (end, self.calculate_from_delta(end, curves))];
+		match self {
+			&CurveSegment::Bezier { x1: FSOFloat { value: x1, .. }, y1: FSOFloat { value: y1, .. }, x2: FSOFloat { value: x2, .. }, y2: FSOFloat { value: y2, .. } } => {
+				//Where the derivative of y, a quadratic in the parameter, is zero
+				let (a, b, c) = (9f32 * (y1 - y2) + 3f32, 6f32 * y2 - 12f32 * y1, 3f32 * y1);
+				let roots = if a.abs() < 1e-6 {
+					if b.abs() < 1e-6 { vec![] } else { vec![-c / b] }
+				}
+				else {
+					let discriminant = b * b - 4f32 * a * c;
+					if discriminant < 0f32 { vec![] } else { vec![(-b - discriminant.sqrt()) / (2f32 * a), (-b + discriminant.sqrt()) / (2f32 * a)] }
+				};
+				for s in roots.into_iter().filter(|s| *s > 0f32 && *s < 1f32) {
+					let t = bezier(x1.clamp(0f32, 1f32), x2.clamp(0f32, 1f32), s);
+					if t > start && t < end {
+						points.push((t, bezier(y1, y2, s)));
+					}
+				}
+			}
+			CurveSegment::Subcurve { curve } => {
+				if let Some(curve) = curves.iter().find(|c| c.name.eq_ignore_ascii_case(curve)) {
+					let extrema = curve.extrema_between(start, end, curves);
+					points.push(extrema.min);
+					points.push(extrema.max);
+				}
+			}
+			//All other segments only ever rise or fall
+			_ => { }
+		}
+		points
+	}
+}
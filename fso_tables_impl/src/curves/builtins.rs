@@ -6,11 +6,35 @@ use crate::curves::*;
 pub static BUILTIN_CURVES: Lazy<Vec<Curve>> = Lazy::new(|| {
 	let mut builtins = Vec::new();
 
+	#[allow(clippy::upper_case_acronyms, clippy::enum_variant_names)]
 	#[derive(PartialEq, EnumIter, Display)]
 	enum EASE {EaseIn, EaseOut, EaseInOut}
 
+	#[allow(clippy::upper_case_acronyms)]
 	#[derive(Copy, Clone, EnumIter, Display)]
-	enum TYPE {Circ, Quad = 2, Cubic = 3, Quart = 4, Quint = 5}
+	enum TYPE {Circ, Quad = 2, Cubic = 3, Quart = 4, Quint = 5, Sine, Expo, Back}
+
+	let segment = |interptype: TYPE, ease_in: bool| match interptype {
+		TYPE::Circ => {
+			CurveSegment::Circular { ease_in: Some(ease_in) }
+		}
+		TYPE::Sine => {
+			CurveSegment::Sine { ease_in: Some(ease_in) }
+		}
+		TYPE::Expo => {
+			CurveSegment::Exponential { exponent: 10f32.into(), ease_in: Some(ease_in) }
+		}
+		//The usual back easing, overshooting by about 10%, as Bezier handles
+		TYPE::Back if ease_in => {
+			CurveSegment::Bezier { x1: 0.36f32.into(), y1: 0f32.into(), x2: 0.66f32.into(), y2: (-0.56f32).into() }
+		}
+		TYPE::Back => {
+			CurveSegment::Bezier { x1: 0.34f32.into(), y1: 1.56f32.into(), x2: 0.64f32.into(), y2: 1f32.into() }
+		}
+		interptype => {
+			CurveSegment::Polynomial { ease_in: Some(ease_in), degree: ((interptype as i32) as f32).into() }
+		}
+	};
 
 	for ease in EASE::iter() {
 		for reverse in [true, false] {
@@ -22,31 +46,17 @@ pub static BUILTIN_CURVES: Lazy<Vec<Curve>> = Lazy::new(|| {
 					name += "Rev";
 				}
 
-				let ease_in = Some(ease != EASE::EaseOut);
+				let ease_in = ease != EASE::EaseOut;
 
 				keyframes.push(CurveKeyframe::new(
 					(0f32, if reverse { 1f32 } else { 0f32 }),
-					match interptype {
-						TYPE::Circ => {
-							CurveSegment::Circular { ease_in }
-						}
-						interptype => {
-							CurveSegment::Polynomial { ease_in, degree: (interptype as i32) as f32 }
-						}
-					}
+					segment(interptype, ease_in)
 				));
 
 				if ease == EASE::EaseInOut {
 					keyframes.push(CurveKeyframe::new(
 						(0.5f32, 0.5f32),
-						match interptype {
-							TYPE::Circ => {
-								CurveSegment::Circular { ease_in: Some(!ease_in.unwrap()) }
-							}
-							interptype => {
-								CurveSegment::Polynomial { ease_in: Some(!ease_in.unwrap()), degree: (interptype as i32) as f32 }
-							}
-						}
+						segment(interptype, !ease_in)
 					));
 				}
 
@@ -0,0 +1,296 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, LN_2};
+use std::ops::Range;
+
+use fso_tables::FSOFloat;
+
+use crate::curves::*;
+
+//Samples used to find the part of a Bezier or subcurve containing a value before bisecting it
+const INVERSE_SAMPLES: usize = 64;
+const INVERSE_ITERATIONS: usize = 32;
+
+//The index of the keyframe starting the segment that contains x, if x is within the curve's keyframes
+fn segment_at(curve: &Curve, x: f32) -> Option<usize> {
+	assert!(curve.keyframes.len() >= 2);
+	if curve.keyframes[0].pos.0 > x || curve.keyframes[curve.keyframes.len() - 1].pos.0 <= x {
+		return None;
+	}
+	curve.keyframes[1..].iter().position(|kf| x < *kf.pos.0)
+}
+
+impl Curve {
+	//The slope at x. Outside of the keyframes, the curve is constant. At a keyframe, this is the slope of the segment it starts
+	pub fn derivative(&self, x: f32, curves: &Vec<&Curve>) -> f32 {
+		segment_at(self, x).map_or(0f32, |index| {
+			self.keyframes[index].segment.derivative(x, &self.keyframes[index], &self.keyframes[index + 1], curves)
+		})
+	}
+
+	//The area under the curve over the range, negative for ranges going backwards
+	pub fn integral(&self, range: Range<f32>, curves: &Vec<&Curve>) -> f32 {
+		self.antiderivative(range.end, curves) - self.antiderivative(range.start, curves)
+	}
+
+	//The area under the curve between the first keyframe and x
+	fn antiderivative(&self, x: f32, curves: &Vec<&Curve>) -> f32 {
+		assert!(self.keyframes.len() >= 2);
+		let first = &self.keyframes[0];
+		if x <= *first.pos.0 {
+			return (x - *first.pos.0) * *first.pos.1;
+		}
+
+		let mut area = 0f32;
+		for (current, next) in self.keyframes.iter().zip(&self.keyframes[1..]) {
+			if x < *next.pos.0 {
+				return area + current.segment.integral(x, current, next, curves);
+			}
+			area += current.segment.integral(*next.pos.0, current, next, curves);
+		}
+
+		let last = &self.keyframes[self.keyframes.len() - 1];
+		area + (x - *last.pos.0) * *last.pos.1
+	}
+
+	//The first x at which the curve reaches y, so for monotonic curves the only one.
+	//None if the curve never takes this value, i.e. because it jumps past it at a keyframe
+	pub fn inverse(&self, y: f32, curves: &Vec<&Curve>) -> Option<f32> {
+		assert!(self.keyframes.len() >= 2);
+		for (current, next) in self.keyframes.iter().zip(&self.keyframes[1..]) {
+			if let Some(x) = current.segment.inverse(y, current, next, curves) {
+				return Some(x);
+			}
+		}
+		let last = &self.keyframes[self.keyframes.len() - 1];
+		(last.pos.1 == y).then_some(*last.pos.0)
+	}
+}
+
+impl CurveSegment {
+	pub fn derivative(&self, x: f32, current: &CurveKeyframe, next: &CurveKeyframe, curves: &Vec<&Curve>) -> f32 {
+		let width = *next.pos.0 - *current.pos.0;
+		self.derivative_from_delta((x - *current.pos.0) / width, curves) * (*next.pos.1 - *current.pos.1) / width
+	}
+
+	//The area under the segment between the current keyframe and x
+	pub fn integral(&self, x: f32, current: &CurveKeyframe, next: &CurveKeyframe, curves: &Vec<&Curve>) -> f32 {
+		let width = *next.pos.0 - *current.pos.0;
+		let t = (x - *current.pos.0) / width;
+		width * (self.integral_from_delta(t, curves) * (*next.pos.1 - *current.pos.1) + t * *current.pos.1)
+	}
+
+	//The x between the keyframes at which the segment reaches y, if any
+	pub fn inverse(&self, y: f32, current: &CurveKeyframe, next: &CurveKeyframe, curves: &Vec<&Curve>) -> Option<f32> {
+		let height = *next.pos.1 - *current.pos.1;
+		if y == *current.pos.1 {
+			return Some(*current.pos.0);
+		}
+		if height == 0f32 {
+			return None;
+		}
+		self.inverse_from_delta((y - *current.pos.1) / height, curves).map(|t| *current.pos.0 + t * (*next.pos.0 - *current.pos.0))
+	}
+
+	fn derivative_from_delta(&self, t: f32, curves: &Vec<&Curve>) -> f32 {
+		match self {
+			CurveSegment::Constant => { 0f32 }
+			CurveSegment::Linear => { 1f32 }
+			&CurveSegment::Polynomial { ease_in, degree: FSOFloat { value: degree, .. } } => {
+				if ease_in.unwrap_or(true) {
+					degree * t.powf(degree - 1f32)
+				}
+				else {
+					degree * (1f32 - t).powf(degree - 1f32)
+				}
+			}
+			&CurveSegment::Circular { ease_in } => {
+				if ease_in.unwrap_or(true) {
+					t / (1f32 - t.powi(2)).sqrt()
+				}
+				else {
+					(1f32 - t) / (1f32 - (1f32 - t).powi(2)).sqrt()
+				}
+			}
+			&CurveSegment::Bezier { x1: FSOFloat { value: x1, .. }, y1: FSOFloat { value: y1, .. }, x2: FSOFloat { value: x2, .. }, y2: FSOFloat { value: y2, .. } } => {
+				let s = bezier_parameter(x1, x2, t);
+				bezier_derivative(y1, y2, s) / bezier_derivative(x1.clamp(0f32, 1f32), x2.clamp(0f32, 1f32), s)
+			}
+			&CurveSegment::Sine { ease_in } => {
+				if ease_in.unwrap_or(true) {
+					FRAC_PI_2 * (t * FRAC_PI_2).sin()
+				}
+				else {
+					FRAC_PI_2 * (t * FRAC_PI_2).cos()
+				}
+			}
+			CurveSegment::Step { .. } => { 0f32 }
+			&CurveSegment::Exponential { exponent: FSOFloat { value: exponent, .. }, ease_in } => {
+				if ease_in.unwrap_or(true) {
+					exponential_derivative(exponent, t)
+				}
+				else {
+					exponential_derivative(exponent, 1f32 - t)
+				}
+			}
+			CurveSegment::Subcurve { curve } => {
+				curves.iter().find(|c| c.name.eq_ignore_ascii_case(curve)).map_or(0f32, |c| c.derivative(t, curves))
+			}
+		}
+	}
+
+	//The area under calculate_from_delta between 0 and t
+	fn integral_from_delta(&self, t: f32, curves: &Vec<&Curve>) -> f32 {
+		//Area under the unit circle's upper half between 0 and v
+		let circle = |v: f32| (v * (1f32 - v * v).max(0f32).sqrt() + v.clamp(-1f32, 1f32).asin()) / 2f32;
+		match self {
+			CurveSegment::Constant => { 0f32 }
+			CurveSegment::Linear => { t * t / 2f32 }
+			&CurveSegment::Polynomial { ease_in, degree: FSOFloat { value: degree, .. } } => {
+				if ease_in.unwrap_or(true) {
+					t.powf(degree + 1f32) / (degree + 1f32)
+				}
+				else {
+					t - (1f32 - (1f32 - t).powf(degree + 1f32)) / (degree + 1f32)
+				}
+			}
+			&CurveSegment::Circular { ease_in } => {
+				if ease_in.unwrap_or(true) {
+					t - circle(t)
+				}
+				else {
+					FRAC_PI_4 - circle(1f32 - t)
+				}
+			}
+			&CurveSegment::Bezier { x1: FSOFloat { value: x1, .. }, y1: FSOFloat { value: y1, .. }, x2: FSOFloat { value: x2, .. }, y2: FSOFloat { value: y2, .. } } => {
+				//The area is the integral of y(s) * x'(s) over the parameter, a polynomial of degree 5, for which three point Gauss-Legendre quadrature is exact
+				let (x1, x2) = (x1.clamp(0f32, 1f32), x2.clamp(0f32, 1f32));
+				let end = bezier_parameter(x1, x2, t.clamp(0f32, 1f32));
+				let offset = end / 2f32 * (3f32 / 5f32).sqrt();
+				[(end / 2f32 - offset, 5f32 / 9f32), (end / 2f32, 8f32 / 9f32), (end / 2f32 + offset, 5f32 / 9f32)].iter()
+					.map(|(s, weight)| weight * bezier(y1, y2, *s) * bezier_derivative(x1, x2, *s)).sum::<f32>() * end / 2f32
+			}
+			&CurveSegment::Sine { ease_in } => {
+				if ease_in.unwrap_or(true) {
+					t - (t * FRAC_PI_2).sin() / FRAC_PI_2
+				}
+				else {
+					(1f32 - (t * FRAC_PI_2).cos()) / FRAC_PI_2
+				}
+			}
+			&CurveSegment::Step { steps } => {
+				let steps = steps.max(1) as f32;
+				let step = (t * steps).floor().min(steps);
+				step * (step - 1f32) / (2f32 * steps * steps) + (t - step / steps) * step / steps
+			}
+			&CurveSegment::Exponential { exponent: FSOFloat { value: exponent, .. }, ease_in } => {
+				if ease_in.unwrap_or(true) {
+					exponential_integral(exponent, t)
+				}
+				else {
+					t - exponential_integral(exponent, 1f32) + exponential_integral(exponent, 1f32 - t)
+				}
+			}
+			CurveSegment::Subcurve { curve } => {
+				curves.iter().find(|c| c.name.eq_ignore_ascii_case(curve)).map_or(0f32, |c| c.integral(0f32..t, curves))
+			}
+		}
+	}
+
+	//The t in 0 to 1 for which calculate_from_delta returns u, if any
+	fn inverse_from_delta(&self, u: f32, curves: &Vec<&Curve>) -> Option<f32> {
+		let overshoots = matches!(self, CurveSegment::Bezier { .. } | CurveSegment::Subcurve { .. });
+		if !overshoots && !(0f32..=1f32).contains(&u) {
+			return None;
+		}
+		match self {
+			CurveSegment::Constant => { (u == 0f32).then_some(0f32) }
+			CurveSegment::Linear => { Some(u) }
+			&CurveSegment::Polynomial { ease_in, degree: FSOFloat { value: degree, .. } } => {
+				if ease_in.unwrap_or(true) {
+					Some(u.powf(1f32 / degree))
+				}
+				else {
+					Some(1f32 - (1f32 - u).powf(1f32 / degree))
+				}
+			}
+			&CurveSegment::Circular { ease_in } => {
+				if ease_in.unwrap_or(true) {
+					Some((1f32 - (1f32 - u).powi(2)).sqrt())
+				}
+				else {
+					Some(1f32 - (1f32 - u * u).sqrt())
+				}
+			}
+			&CurveSegment::Sine { ease_in } => {
+				if ease_in.unwrap_or(true) {
+					Some((1f32 - u).acos() / FRAC_PI_2)
+				}
+				else {
+					Some(u.asin() / FRAC_PI_2)
+				}
+			}
+			&CurveSegment::Step { steps } => {
+				let steps = steps.max(1) as f32;
+				let step = (u * steps).round();
+				((u * steps - step).abs() < 1e-4).then_some(step / steps)
+			}
+			&CurveSegment::Exponential { exponent: FSOFloat { value: exponent, .. }, ease_in } => {
+				if ease_in.unwrap_or(true) {
+					Some(exponential_inverse(exponent, u))
+				}
+				else {
+					Some(1f32 - exponential_inverse(exponent, 1f32 - u))
+				}
+			}
+			//Bezier handles may overshoot and subcurves can be anything, so search numerically
+			CurveSegment::Bezier { .. } | CurveSegment::Subcurve { .. } => {
+				inverse_numerically(|t| self.calculate_from_delta(t, curves) - u)
+			}
+		}
+	}
+}
+
+//The first t in 0 to 1 at which f crosses 0
+fn inverse_numerically(f: impl Fn(f32) -> f32) -> Option<f32> {
+	let step = 1f32 / INVERSE_SAMPLES as f32;
+	(0..INVERSE_SAMPLES).map(|i| (i as f32 * step, (i + 1) as f32 * step)).find_map(|(mut low, mut high)| {
+		let (f_low, f_high) = (f(low), f(high));
+		if f_low == 0f32 {
+			return Some(low);
+		}
+		if f_low.signum() == f_high.signum() && f_high != 0f32 {
+			return None;
+		}
+		for _ in 0..INVERSE_ITERATIONS {
+			let mid = (low + high) / 2f32;
+			if f(mid).signum() == f_low.signum() { low = mid; } else { high = mid; }
+		}
+		Some((low + high) / 2f32)
+	})
+}
+
+fn exponential_derivative(exponent: f32, t: f32) -> f32 {
+	if exponent.abs() < 1e-4 {
+		1f32
+	}
+	else {
+		exponent * LN_2 * (exponent * t).exp2() / (exponent.exp2() - 1f32)
+	}
+}
+
+fn exponential_integral(exponent: f32, t: f32) -> f32 {
+	if exponent.abs() < 1e-4 {
+		t * t / 2f32
+	}
+	else {
+		(((exponent * t).exp2() - 1f32) / (exponent * LN_2) - t) / (exponent.exp2() - 1f32)
+	}
+}
+
+fn exponential_inverse(exponent: f32, u: f32) -> f32 {
+	if exponent.abs() < 1e-4 {
+		u
+	}
+	else {
+		(u * (exponent.exp2() - 1f32) + 1f32).log2() / exponent
+	}
+}
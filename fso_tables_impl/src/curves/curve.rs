@@ -1,5 +1,7 @@
-use std::ops::Range;
 use std::string::ToString;
+use std::f32::consts::FRAC_PI_2;
+
+use fso_tables::FSOFloat;
 
 use crate::curves::*;
 
@@ -8,13 +10,13 @@ impl Curve {
 		assert!(self.keyframes.len() >= 2);
 
 		if self.keyframes[0].pos.0 > x {
-			return self.keyframes[0].pos.1;
+			return *self.keyframes[0].pos.1;
 		}
 		else if self.keyframes[self.keyframes.len() - 1].pos.0 <= x {
-			return self.keyframes[self.keyframes.len() - 1].pos.1;
+			return *self.keyframes[self.keyframes.len() - 1].pos.1;
 		}
 
-		let result = self.keyframes[1..].iter().enumerate().find(|(_, kf)| x < kf.pos.0).map(|(prev_index, kf)| {
+		let result = self.keyframes[1..].iter().enumerate().find(|(_, kf)| x < *kf.pos.0).map(|(prev_index, kf)| {
 			let prev_kf = &self.keyframes[prev_index];
 			prev_kf.segment.calculate(x, prev_kf, kf, curves)
 		});
@@ -27,19 +29,18 @@ impl Curve {
 			unreachable!("Keyframe not found");
 		}
 	}
-	
-	pub fn get_bounds(&self) -> (Range<f32>, Range<f32>) {
-		assert!(self.keyframes.len() >= 2);
-		let first = self.keyframes.first().unwrap();
-		let last = self.keyframes.last().unwrap();
-		let x_bounds = first.pos.0..last.pos.0;
-
-		let (min_y, max_y) = self.keyframes.iter().fold((f32::INFINITY, -f32::INFINITY), |(min_y, max_y), kf| (f32::min(min_y, kf.pos.1), f32::max(max_y, kf.pos.1)) );
-		let y_bounds = min_y..max_y;
+}
+impl CurveKeyframe {
+	pub fn new((x, y): (f32, f32), segment: CurveSegment) -> Self {
+		CurveKeyframe::from_floats((x.into(), y.into()), segment)
+	}
 
-		(x_bounds, y_bounds)
+	//The position as plain numbers, without the literals the table spelled them with
+	pub fn point(&self) -> (f32, f32) {
+		(*self.pos.0, *self.pos.1)
 	}
 }
+
 impl Default for Curve {
 	fn default() -> Self { 
 		Curve::new("".to_string(), vec![
@@ -51,14 +52,14 @@ impl Default for Curve {
 
 impl CurveSegment {
 	pub fn calculate(&self, x: f32, current: &CurveKeyframe, next: &CurveKeyframe, curves: &Vec<&Curve>) -> f32 {
-		self.calculate_from_delta((x - current.pos.0) / (next.pos.0 - current.pos.0), curves) * (next.pos.1 - current.pos.1) + current.pos.1
+		self.calculate_from_delta((x - *current.pos.0) / (*next.pos.0 - *current.pos.0), curves) * (*next.pos.1 - *current.pos.1) + *current.pos.1
 	}
 	
-	fn calculate_from_delta(&self, t: f32, curves: &Vec<&Curve>) -> f32 {
+	pub(crate) fn calculate_from_delta(&self, t: f32, curves: &Vec<&Curve>) -> f32 {
 		match self{
 			CurveSegment::Constant => { 0f32 }
 			CurveSegment::Linear => { t }
-			&CurveSegment::Polynomial { ease_in, degree } => {
+			&CurveSegment::Polynomial { ease_in, degree: FSOFloat { value: degree, .. } } => {
 				if ease_in.unwrap_or(true) {
 					t.powf(degree)
 				}
@@ -74,9 +75,73 @@ impl CurveSegment {
 					(1f32 - (1f32 - t).powi(2)).sqrt()
 				}
 			}
+			&CurveSegment::Bezier { x1: FSOFloat { value: x1, .. }, y1: FSOFloat { value: y1, .. }, x2: FSOFloat { value: x2, .. }, y2: FSOFloat { value: y2, .. } } => {
+				bezier(y1, y2, bezier_parameter(x1, x2, t))
+			}
+			&CurveSegment::Sine { ease_in } => {
+				if ease_in.unwrap_or(true) {
+					1f32 - (t * FRAC_PI_2).cos()
+				}
+				else {
+					(t * FRAC_PI_2).sin()
+				}
+			}
+			&CurveSegment::Step { steps } => {
+				let steps = steps.max(1) as f32;
+				(t * steps).floor() / steps
+			}
+			&CurveSegment::Exponential { exponent: FSOFloat { value: exponent, .. }, ease_in } => {
+				if ease_in.unwrap_or(true) {
+					exponential(exponent, t)
+				}
+				else {
+					1f32 - exponential(exponent, 1f32 - t)
+				}
+			}
 			CurveSegment::Subcurve { curve } => { 
 				curves.iter().find(|c| c.name.eq_ignore_ascii_case(curve)).map_or(0f32, |c| c.calculate(t, curves))
 			}
 		}
 	}
+}
+
+//One coordinate of a cubic Bezier from 0 to 1 with the given handle coordinates, at parameter s
+pub(crate) fn bezier(p1: f32, p2: f32, s: f32) -> f32 {
+	let r = 1f32 - s;
+	3f32 * r * r * s * p1 + 3f32 * r * s * s * p2 + s * s * s
+}
+
+pub(crate) fn bezier_derivative(p1: f32, p2: f32, s: f32) -> f32 {
+	let r = 1f32 - s;
+	3f32 * r * r * p1 + 6f32 * r * s * (p2 - p1) + 3f32 * s * s * (1f32 - p2)
+}
+
+//The parameter at which the Bezier's x reaches t. With the handles' x within 0 to 1, x only ever grows, so this is unique
+pub(crate) fn bezier_parameter(x1: f32, x2: f32, t: f32) -> f32 {
+	let (x1, x2) = (x1.clamp(0f32, 1f32), x2.clamp(0f32, 1f32));
+	let t = t.clamp(0f32, 1f32);
+	let (mut low, mut high) = (0f32, 1f32);
+	let mut s = t;
+	for _ in 0..32 {
+		let x = bezier(x1, x2, s) - t;
+		if x.abs() < 1e-6 {
+			break;
+		}
+		if x > 0f32 { high = s; } else { low = s; }
+		//Newton steps where the slope allows it, bisection otherwise
+		let slope = bezier_derivative(x1, x2, s);
+		let next = s - x / slope;
+		s = if slope.abs() > 1e-6 && next > low && next < high { next } else { (low + high) / 2f32 };
+	}
+	s
+}
+
+//Exponential ease in, scaled to go from 0 to 1. Exponents close to 0 are linear
+pub(crate) fn exponential(exponent: f32, t: f32) -> f32 {
+	if exponent.abs() < 1e-4 {
+		t
+	}
+	else {
+		((exponent * t).exp2() - 1f32) / (exponent.exp2() - 1f32)
+	}
 }
\ No newline at end of file
@@ -0,0 +1,89 @@
+use fso_tables::FSOParsingError;
+use crate::curves::*;
+
+//Reads x and y columns separated by commas, semicolons or tabs. Empty lines, lines starting with # and a header line are skipped
+pub fn parse_curve_samples(csv: &str) -> Result<Vec<(f32, f32)>, FSOParsingError> {
+	let mut samples = Vec::new();
+	for (line_number, line) in csv.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+
+		let mut columns = line.split([',', ';', '\t']).map(str::trim);
+		let values = (columns.next().map(str::parse::<f32>), columns.next().map(str::parse::<f32>));
+		match values {
+			(Some(Ok(x)), Some(Ok(y))) => { samples.push((x, y)); }
+			_ if samples.is_empty() && line.chars().any(char::is_alphabetic) => { }
+			_ => {
+				return Err(FSOParsingError { line: line_number + 1, reason: format!("Expected an x and a y value, got \"{}\".", line), comments: None, version_string: None });
+			}
+		}
+	}
+	Ok(samples)
+}
+
+//The segments tried when fitting, simplest first, so that those are preferred when several fit
+fn fit_candidates() -> Vec<CurveSegment> {
+	let mut candidates = vec![CurveSegment::Linear, CurveSegment::Constant];
+	for ease_in in [true, false] {
+		candidates.push(CurveSegment::Sine { ease_in: Some(ease_in) });
+		candidates.push(CurveSegment::Circular { ease_in: Some(ease_in) });
+		candidates.extend((3..=12).map(|degree| CurveSegment::Polynomial { degree: (degree as f32 / 2f32).into(), ease_in: Some(ease_in) }));
+		candidates.extend((1..=12).map(|exponent| CurveSegment::Exponential { exponent: (exponent as f32).into(), ease_in: Some(ease_in) }));
+	}
+	candidates
+}
+
+//Whether the segment, going from the first to the last sample, passes all samples in between within the tolerance
+fn segment_fits(segment: &CurveSegment, samples: &[(f32, f32)], tolerance: f32) -> bool {
+	let current = CurveKeyframe::new(samples[0], segment.clone());
+	let next = CurveKeyframe::new(samples[samples.len() - 1], CurveSegment::Constant);
+	samples[1..samples.len() - 1].iter().all(|(x, y)| (segment.calculate(*x, &current, &next, &Vec::new()) - y).abs() <= tolerance)
+}
+
+impl Curve {
+	//Fits a curve through the samples, with keyframes on samples, within the tolerance at every sample, using as few keyframes as possible.
+	//Among fits with the fewest keyframes, earlier segments reach as far as they can.
+	//None if there aren't at least two samples with different x
+	pub fn fit(name: String, samples: &[(f32, f32)], tolerance: f32) -> Option<Curve> {
+		let mut samples = samples.to_vec();
+		samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+		samples.dedup_by(|a, b| a.0 == b.0);
+		if samples.len() < 2 {
+			return None;
+		}
+
+		//For each sample, the fewest segments from it to the last sample, with the first of them and where it ends.
+		//A segment fitting a range doesn't need to fit the ranges it starts, so all ranges are tried
+		let candidates = fit_candidates();
+		let last = samples.len() - 1;
+		let mut best: Vec<Option<(usize, usize, CurveSegment)>> = vec![None; samples.len()];
+		best[last] = Some((0, last, CurveSegment::Constant));
+		for start in (0..last).rev() {
+			for end in (start + 1..=last).rev() {
+				let Some((segments, _, _)) = &best[end] else { continue; };
+				let segments = segments + 1;
+				if best[start].as_ref().is_some_and(|(fewest, _, _)| *fewest <= segments) {
+					continue;
+				}
+				//Two samples are always fit by a line
+				let segment = if end == start + 1 { Some(CurveSegment::Linear) } else { candidates.iter().find(|candidate| segment_fits(candidate, &samples[start..=end], tolerance)).cloned() };
+				if let Some(segment) = segment {
+					best[start] = Some((segments, end, segment));
+				}
+			}
+		}
+
+		let mut keyframes = Vec::new();
+		let mut start = 0;
+		while start < last {
+			let (_, end, segment) = best[start].clone().unwrap();
+			keyframes.push(CurveKeyframe::new(samples[start], segment));
+			start = end;
+		}
+		keyframes.push(CurveKeyframe::new(samples[last], CurveSegment::Constant));
+
+		Some(Curve::new(name, keyframes))
+	}
+}
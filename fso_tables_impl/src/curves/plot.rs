@@ -0,0 +1,115 @@
+use std::fmt::Write;
+use std::ops::Range;
+use crate::curves::*;
+
+const SVG_WIDTH: f32 = 640f32;
+const SVG_HEIGHT: f32 = 400f32;
+const SVG_MARGIN: f32 = 40f32;
+//Points per segment in the SVG's polyline
+const SVG_SAMPLES: usize = 64;
+
+impl CurveSegment {
+	//Short name of the segment kind and its parameters, as used in plot labels
+	pub fn label(&self) -> String {
+		let ease = |ease_in: Option<bool>| if ease_in.unwrap_or(true) { "in" } else { "out" };
+		match self {
+			CurveSegment::Constant => { "Constant".to_string() }
+			CurveSegment::Linear => { "Linear".to_string() }
+			CurveSegment::Polynomial { degree, ease_in } => { format!("Polynomial {} {}", degree, ease(*ease_in)) }
+			CurveSegment::Circular { ease_in } => { format!("Circular {}", ease(*ease_in)) }
+			CurveSegment::Bezier { x1, y1, x2, y2 } => { format!("Bezier ({}, {}) ({}, {})", x1, y1, x2, y2) }
+			CurveSegment::Sine { ease_in } => { format!("Sine {}", ease(*ease_in)) }
+			CurveSegment::Step { steps } => { format!("Step {}", steps) }
+			CurveSegment::Exponential { exponent, ease_in } => { format!("Exponential {} {}", exponent, ease(*ease_in)) }
+			CurveSegment::Subcurve { curve } => { format!("Subcurve {}", curve) }
+		}
+	}
+}
+
+//The y range to plot, widened where it would be empty
+fn plot_bounds(curve: &Curve, curves: &Vec<&Curve>) -> (Range<f32>, Range<f32>) {
+	let (x_bounds, y_bounds) = curve.get_bounds(curves);
+	let y_bounds = if y_bounds.end - y_bounds.start > f32::EPSILON { y_bounds } else { y_bounds.start - 1f32..y_bounds.end + 1f32 };
+	(x_bounds, y_bounds)
+}
+
+impl Curve {
+	//Plots the curve over its bounds, with the keyframes marked and each segment labelled with its kind
+	pub fn to_svg(&self, curves: &Vec<&Curve>) -> String {
+		let (x_bounds, y_bounds) = plot_bounds(self, curves);
+		let x_scale = if x_bounds.end > x_bounds.start { (SVG_WIDTH - 2f32 * SVG_MARGIN) / (x_bounds.end - x_bounds.start) } else { 0f32 };
+		let y_scale = (SVG_HEIGHT - 2f32 * SVG_MARGIN) / (y_bounds.end - y_bounds.start);
+		let to_svg = |(x, y): (f32, f32)| (SVG_MARGIN + (x - x_bounds.start) * x_scale, SVG_HEIGHT - SVG_MARGIN - (y - y_bounds.start) * y_scale);
+
+		let mut svg = String::new();
+		writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">", SVG_WIDTH, SVG_HEIGHT, SVG_WIDTH, SVG_HEIGHT).unwrap();
+		writeln!(svg, "<title>{}</title>", escape(&self.name)).unwrap();
+		writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#ccc\"/>", SVG_MARGIN, SVG_MARGIN, SVG_WIDTH - 2f32 * SVG_MARGIN, SVG_HEIGHT - 2f32 * SVG_MARGIN).unwrap();
+		for (value, (x, y), anchor) in [
+			(x_bounds.start, to_svg((x_bounds.start, y_bounds.start)), "start"),
+			(x_bounds.end, to_svg((x_bounds.end, y_bounds.start)), "end")
+		] {
+			writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"10\" text-anchor=\"{}\">{}</text>", x, y + 14f32, anchor, value).unwrap();
+		}
+		for (value, (x, y)) in [(y_bounds.start, to_svg((x_bounds.start, y_bounds.start))), (y_bounds.end, to_svg((x_bounds.start, y_bounds.end)))] {
+			writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"10\" text-anchor=\"end\">{}</text>", x - 4f32, y + 4f32, value).unwrap();
+		}
+
+		for (current, next) in self.keyframes.iter().zip(&self.keyframes[1..]) {
+			//The segment ends just before the next keyframe, which may be a jump
+			let points = (0..=SVG_SAMPLES).map(|i| {
+				let t = i as f32 / SVG_SAMPLES as f32;
+				let x = *current.pos.0 + (*next.pos.0 - *current.pos.0) * t;
+				let (x, y) = to_svg((x, current.segment.calculate(x, current, next, curves)));
+				format!("{:.2},{:.2}", x, y)
+			}).collect::<Vec<String>>().join(" ");
+			writeln!(svg, "<polyline points=\"{}\" fill=\"none\" stroke=\"#1f77b4\" stroke-width=\"2\"><title>{}</title></polyline>", points, escape(&current.segment.label())).unwrap();
+
+			let (x, _) = to_svg(((*current.pos.0 + *next.pos.0) / 2f32, 0f32));
+			writeln!(svg, "<text x=\"{:.2}\" y=\"{}\" font-size=\"10\" text-anchor=\"middle\" fill=\"#555\">{}</text>", x, SVG_MARGIN - 6f32, escape(&current.segment.label())).unwrap();
+		}
+
+		for keyframe in &self.keyframes {
+			let (x, y) = to_svg(keyframe.point());
+			writeln!(svg, "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"4\" fill=\"#d62728\"><title>({}, {})</title></circle>", x, y, keyframe.pos.0, keyframe.pos.1).unwrap();
+		}
+
+		svg.push_str("</svg>\n");
+		svg
+	}
+
+	//Plots the curve over its bounds as text, with keyframes as 'o' and a legend of the segments below
+	pub fn to_ascii(&self, width: usize, height: usize, curves: &Vec<&Curve>) -> String {
+		let (width, height) = (width.max(2), height.max(2));
+		let (x_bounds, y_bounds) = plot_bounds(self, curves);
+		let column = |x: f32| (((x - x_bounds.start) / (x_bounds.end - x_bounds.start).max(f32::EPSILON) * (width - 1) as f32).round() as usize).min(width - 1);
+		let row = |y: f32| height - 1 - (((y - y_bounds.start) / (y_bounds.end - y_bounds.start) * (height - 1) as f32).round().clamp(0f32, (height - 1) as f32) as usize);
+
+		let mut grid = vec![vec![' '; width]; height];
+		for x in (0..width).map(|i| x_bounds.start + (x_bounds.end - x_bounds.start) * i as f32 / (width - 1) as f32) {
+			grid[row(self.calculate(x, curves))][column(x)] = '*';
+		}
+		for keyframe in &self.keyframes {
+			grid[row(*keyframe.pos.1)][column(*keyframe.pos.0)] = 'o';
+		}
+
+		let label_width = format!("{}", y_bounds.end).len().max(format!("{}", y_bounds.start).len());
+		let mut plot = String::new();
+		for (i, line) in grid.iter().enumerate() {
+			let label = if i == 0 { format!("{}", y_bounds.end) } else if i == height - 1 { format!("{}", y_bounds.start) } else { String::new() };
+			writeln!(plot, "{:>label_width$} |{}", label, line.iter().collect::<String>().trim_end()).unwrap();
+		}
+		writeln!(plot, "{:>label_width$} +{}", "", "-".repeat(width)).unwrap();
+		let end = format!("{}", x_bounds.end);
+		writeln!(plot, "{:>label_width$}  {}{:>pad$}", "", x_bounds.start, end, pad = width.saturating_sub(format!("{}", x_bounds.start).len())).unwrap();
+
+		for (current, next) in self.keyframes.iter().zip(&self.keyframes[1..]) {
+			writeln!(plot, "{} to {}: {}", current.pos.0, next.pos.0, current.segment.label()).unwrap();
+		}
+		plot
+	}
+}
+
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
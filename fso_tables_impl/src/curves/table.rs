@@ -1,4 +1,4 @@
-use fso_tables::fso_table;
+use fso_tables::{fso_table, FSOFloat};
 
 #[fso_table(table_start="#Curves", table_end="#End", toplevel)]
 pub struct CurveTable {
@@ -14,11 +14,12 @@ pub struct Curve {
 	pub keyframes: Vec<CurveKeyframe>
 }
 
-#[fso_table]
+//new takes plain f32s, from_floats keeps the literals of FSOFloats
+#[fso_table(constructor="from_floats")]
 pub struct CurveKeyframe {
 	#[unnamed]
 	#[gobble=":"]
-	pub pos: (f32, f32),
+	pub pos: (FSOFloat, FSOFloat),
 	#[unnamed]
 	pub segment: CurveSegment
 }
@@ -28,8 +29,14 @@ pub struct CurveKeyframe {
 pub enum CurveSegment{
 	Constant,
 	Linear,
-	Polynomial { degree: f32, ease_in: Option<bool> },
+	Polynomial { degree: FSOFloat, ease_in: Option<bool> },
 	Circular { ease_in: Option<bool> },
+	//Cubic Bezier from (0, 0) to (1, 1) with two handles, as in CSS easing. Handle x values outside of 0 to 1 are clamped
+	Bezier { x1: FSOFloat, y1: FSOFloat, x2: FSOFloat, y2: FSOFloat },
+	Sine { ease_in: Option<bool> },
+	//Holds each of the given number of steps for an equal part of the segment
+	Step { steps: u32 },
+	Exponential { exponent: FSOFloat, ease_in: Option<bool> },
 	#[use_as_default_string]
 	Subcurve { curve: String }
 }
\ No newline at end of file
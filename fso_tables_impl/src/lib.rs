@@ -1,11 +1,25 @@
 pub mod animations;
+pub mod assets;
+pub mod campaigns;
 pub mod curves;
+pub mod missions;
+pub mod models;
+pub mod music;
+pub mod sounds;
+
+mod math;
+mod table_file;
+mod xstr;
 
 use fso_tables::fso_table;
 
+pub use table_file::*;
+pub use xstr::*;
+
 //Reexport the properties that you need to use this crate. Only force people to include the original fso_tables crate if they want to manually add tables or types or anything.
 pub use fso_tables::FSOParsingError;
 pub use fso_tables::FSOTableFileParser;
+pub use fso_tables::FSOResolution;
 
 #[fso_table(inline)]
 pub struct Vec3D {
@@ -26,3 +40,13 @@ pub struct Angles {
     #[unnamed]
     pub bank: f32,
 }
+
+#[fso_table]
+pub struct Matrix {
+    #[unnamed]
+    pub rvec: Vec3D,
+    #[unnamed]
+    pub uvec: Vec3D,
+    #[unnamed]
+    pub fvec: Vec3D
+}
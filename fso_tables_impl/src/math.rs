@@ -0,0 +1,99 @@
+use crate::{Angles, Matrix, Vec3D};
+
+//Vector and matrix math following the engine's conventions: matrices are orthonormal bases, with rvec, uvec and fvec being the
+//local x, y and z axes expressed in the parent's frame, and angles are given in degrees, as in the tables
+
+impl Vec3D {
+	pub fn zero() -> Self {
+		Vec3D::new(0f32, 0f32, 0f32)
+	}
+
+	pub fn add(&self, other: &Vec3D) -> Vec3D {
+		Vec3D::new(self.x + other.x, self.y + other.y, self.z + other.z)
+	}
+
+	pub fn sub(&self, other: &Vec3D) -> Vec3D {
+		Vec3D::new(self.x - other.x, self.y - other.y, self.z - other.z)
+	}
+
+	pub fn scale(&self, factor: f32) -> Vec3D {
+		Vec3D::new(self.x * factor, self.y * factor, self.z * factor)
+	}
+
+	pub fn dot(&self, other: &Vec3D) -> f32 {
+		self.x * other.x + self.y * other.y + self.z * other.z
+	}
+
+	pub fn cross(&self, other: &Vec3D) -> Vec3D {
+		Vec3D::new(self.y * other.z - self.z * other.y, self.z * other.x - self.x * other.z, self.x * other.y - self.y * other.x)
+	}
+
+	pub fn length(&self) -> f32 {
+		self.dot(self).sqrt()
+	}
+
+	//Returns the zero vector unchanged
+	pub fn normalized(&self) -> Vec3D {
+		let length = self.length();
+		if length > f32::EPSILON { self.scale(1f32 / length) } else { self.clone() }
+	}
+}
+
+impl Angles {
+	pub fn zero() -> Self {
+		Angles::new(0f32, 0f32, 0f32)
+	}
+}
+
+impl Matrix {
+	pub fn identity() -> Self {
+		Matrix::new(Vec3D::new(1f32, 0f32, 0f32), Vec3D::new(0f32, 1f32, 0f32), Vec3D::new(0f32, 0f32, 1f32))
+	}
+
+	pub fn from_angles(angles: &Angles) -> Self {
+		let (sinp, cosp) = angles.pitch.to_radians().sin_cos();
+		let (sinb, cosb) = angles.bank.to_radians().sin_cos();
+		let (sinh, cosh) = angles.heading.to_radians().sin_cos();
+		Matrix::new(
+			Vec3D::new(cosb * cosh + sinp * sinb * sinh, sinb * cosp, sinp * sinb * cosh - cosb * sinh),
+			Vec3D::new(sinp * cosb * sinh - sinb * cosh, cosb * cosp, sinb * sinh + sinp * cosb * cosh),
+			Vec3D::new(sinh * cosp, -sinp, cosh * cosp)
+		)
+	}
+
+	pub fn to_angles(&self) -> Angles {
+		let heading = self.fvec.x.atan2(self.fvec.z);
+		let (sinh, cosh) = heading.sin_cos();
+		let cosp = if sinh.abs() > cosh.abs() { self.fvec.x / sinh } else { self.fvec.z / cosh };
+		let pitch = (-self.fvec.y).atan2(cosp);
+		let bank = self.rvec.y.atan2(self.uvec.y);
+		Angles::new(pitch.to_degrees(), heading.to_degrees(), bank.to_degrees())
+	}
+
+	//Rotation by angle degrees around the axis, counterclockwise when looking down the axis
+	pub fn from_axis_angle(axis: &Vec3D, angle: f32) -> Self {
+		let axis = axis.normalized();
+		let (sin, cos) = angle.to_radians().sin_cos();
+		let rotate = |v: Vec3D| v.scale(cos).add(&axis.cross(&v).scale(sin)).add(&axis.scale(axis.dot(&v) * (1f32 - cos)));
+		Matrix::new(rotate(Vec3D::new(1f32, 0f32, 0f32)), rotate(Vec3D::new(0f32, 1f32, 0f32)), rotate(Vec3D::new(0f32, 0f32, 1f32)))
+	}
+
+	//Expresses a vector given in this matrix' local frame in the parent frame
+	pub fn unrotate(&self, local: &Vec3D) -> Vec3D {
+		self.rvec.scale(local.x).add(&self.uvec.scale(local.y)).add(&self.fvec.scale(local.z))
+	}
+
+	//Expresses a vector given in the parent frame in this matrix' local frame
+	pub fn rotate(&self, parent: &Vec3D) -> Vec3D {
+		Vec3D::new(self.rvec.dot(parent), self.uvec.dot(parent), self.fvec.dot(parent))
+	}
+
+	//Applies a rotation given in this matrix' local frame
+	pub fn rotated_by(&self, local: &Matrix) -> Matrix {
+		Matrix::new(self.unrotate(&local.rvec), self.unrotate(&local.uvec), self.unrotate(&local.fvec))
+	}
+
+	pub fn transposed(&self) -> Matrix {
+		Matrix::new(Vec3D::new(self.rvec.x, self.uvec.x, self.fvec.x), Vec3D::new(self.rvec.y, self.uvec.y, self.fvec.y), Vec3D::new(self.rvec.z, self.uvec.z, self.fvec.z))
+	}
+}
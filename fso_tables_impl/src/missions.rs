@@ -0,0 +1,329 @@
+mod check;
+mod operators;
+mod table;
+mod text;
+mod unknown;
+
+pub use check::*;
+pub use operators::*;
+pub use table::*;
+pub use text::*;
+pub use unknown::*;
+
+#[cfg(test)]
+mod tests {
+	use fso_tables::{FSOFormatting, FSOLineEnding, FSOStreamBuilder, FSOTable, FSOTableFileParser, Sexp};
+	use crate::missions::*;
+
+	pub(crate) const MISSION: &str = "#Mission Info
+
+$Version: 0.10
+$Name: XSTR(\"Test Mission\", -1)
+$Author: Volition
+$Created: 03/27/98 at 10:12:23
+$Modified: 03/27/98 at 10:12:23
+$Notes:
+This is a FRED2_OPEN created mission.
+$End Notes:
+$Mission Desc:
+XSTR(\"Put mission description here\", -1)
+$end_multi_text
++Game Type Flags: 1
++Flags: 0
++NumPlayers: 1
++NumRespawns: 3
++Disallow Support: 0
++Hull Repair Ceiling: 0.000000
++Subsystem Repair Ceiling: 100.000000
+$Squadron Reassign Name:
+$Squadron Reassign Logo:
+
+#Command Briefing
+
+$Stage Text:
+XSTR(\"Hello\", -1)
+$end_multi_text
+$Ani Filename: <default>
+
+#Players	;! 1 total
+
+$Starting Shipname: Alpha 1
+$Ship Choices: (
+	\"GTF Ulysses\"	5
+)
+
+#Objects	;! 2 total
+
+$Name: Alpha 1		;! Object #0
+$Class: GTF Ulysses
+$Team: Friendly
+$Location: 0.000000, 0.000000, 0.000000
+$Orientation:
+	1.000000, 0.000000, 0.000000,
+	0.000000, 1.000000, 0.000000,
+	0.000000, 0.000000, 1.000000
+$AI Behavior: None
++AI Class: Captain
+$Cargo 1: XSTR(\"Nothing\", -1)
+$Initial Velocity: 33
+$Initial Hull: 100
+$Initial Shields: 100
++Subsystem: Pilot
++Primary Banks: ( \"ML-16 Laser\" \"ML-16 Laser\" )
++Pbank Ammo: ( 100 100 )
+$Arrival Location: Hyperspace
+$Arrival Cue: ( true )
+$Departure Location: Hyperspace
+$Departure Cue: ( false )
+$Determination: 10
++Flags: ( \"cargo-known\" \"player-start\" )
++Respawn priority: 0
++Score: 8
++Kamikaze Damage: 500
+
+$Name: GTC Fenris 1		;! Object #1
+$Class: GTC Fenris
+$Team: Hostile
+$Location: 100.000000, 0.000000, 2000.000000
+$Orientation:
+	1.000000, 0.000000, 0.000000,
+	0.000000, 1.000000, 0.000000,
+	0.000000, 0.000000, 1.000000
+$Arrival Location: Hyperspace
+$Arrival Cue: ( true )
+$Departure Location: Hyperspace
+$Departure Cue: ( false )
+
+#Wings	;! 1 total
+
+$Name: Alpha
+$Waves: 1
+$Wave Threshold: 0
+$Special Ship: 0
+$Arrival Location: Hyperspace
+$Arrival Cue: ( true )
+$Departure Location: Hyperspace
+$Departure Cue: ( false )
+$Ships: (	;! 1 total
+	\"Alpha 1\"
+)
++Flags: ( \"reinforcement\" )
+
+#Events	;! 1 total
+$Formula: ( when
+   ( true )
+   ( send-message
+      \"#Command\"
+      \"High\"
+      \"Msg1\"
+   )
+)
++Name: Greeting
++Repeat Count: 1
++Interval: 1
+
+#Goals	;! 1 total
+$Type: Primary
++Name: Kill Fenris
+$MessageNew:  XSTR(\"Destroy the Fenris\", -1)
+$end_multi_text
+$Formula: ( is-destroyed-delay 0 \"GTC Fenris 1\" )
++Invalid
++Team: 0
+
+#Waypoints	;! 1 lists total
+
+$Jump Node: 0.000000, 0.000000, 5000.000000
+$Jump Node Name: Jump Node 1
+
+$Name: Waypoint path 1
+$List: ( ;! 2 points
+	( -100.000000, 0.000000, 200.000000 )
+	( 300.000000, 10.000000, 200.000000 )
+)
+
+#Messages	;! 1 total
+$Name: Msg1
+$Team: -1
+$MessageNew:  XSTR(\"Hello, Alpha 1.\", -1)
+$end_multi_text
++Persona: Command
++Avi Name: Head-CM1
+
+#Reinforcements	;! 0 total
+
+#Background bitmaps	;! 0 total
+$Num stars: 500
+
+#Music
+$Event Music: 1: Genesis
+
+#End
+";
+
+	#[test]
+	fn parse() {
+		let mission = Mission::parse(FSOTableFileParser::from_string(MISSION)).unwrap();
+
+		assert_eq!(mission.info.name.text, "Test Mission");
+		assert_eq!(mission.info.notes.as_ref().unwrap().0, "This is a FRED2_OPEN created mission.");
+		assert_eq!(mission.info.mission_desc.as_ref().unwrap().0.text, "Put mission description here");
+		assert_eq!(mission.info.num_respawns, Some(3));
+		assert_eq!(mission.info.unknown_options.len(), 2);
+		assert_eq!(mission.info.unknown_options[0].name, "$Squadron Reassign Name:");
+
+		let headers = mission.sections_before_objects.iter().map(|section| section.header.as_str()).collect::<Vec<&str>>();
+		assert_eq!(headers, vec!["#Command Briefing", "#Players"]);
+
+		let ships = &mission.objects.ships;
+		assert_eq!(ships.len(), 2);
+		assert_eq!(ships[0].name, "Alpha 1");
+		assert_eq!(ships[0].orientation.uvec.y, 1f32);
+		assert_eq!(ships[0].cargo.as_ref().unwrap().text, "Nothing");
+		assert_eq!(ships[0].subsystems[0].primary_banks.as_ref().unwrap().0, vec!["ML-16 Laser", "ML-16 Laser"]);
+		assert_eq!(ships[0].subsystems[0].pbank_ammo, Some(vec![100, 100]));
+		assert_eq!(ships[0].arrival_cue, Some(Sexp::List(vec![Sexp::Atom("true".to_string())])));
+		assert_eq!(ships[0].flags.as_ref().unwrap().0, vec!["cargo-known", "player-start"]);
+		assert_eq!(ships[0].score, Some(8));
+		assert_eq!(ships[0].unknown_options[0].value, "500");
+		assert_eq!(ships[1].team, "Hostile");
+
+		assert_eq!(mission.wings.wings[0].ships.0, vec!["Alpha 1"]);
+
+		let event = &mission.events.events[0];
+		assert_eq!(event.name.as_deref(), Some("Greeting"));
+		assert_eq!(event.formula.operator(), Some("when"));
+		assert_eq!(event.formula.arguments()[1].arguments()[2], &Sexp::String("Msg1".to_string()));
+
+		let goal = &mission.goals.goals[0];
+		assert_eq!(goal.goal_type, "Primary");
+		assert!(goal.invalid);
+		assert_eq!(goal.formula.arguments()[0], &Sexp::Number(0));
+
+		assert_eq!(mission.waypoints.jump_nodes[0].name.as_deref(), Some("Jump Node 1"));
+		assert_eq!(mission.waypoints.paths[0].points.len(), 2);
+		assert_eq!(mission.waypoints.paths[0].points[1].position, (300f32.into(), 10f32.into(), 200f32.into()));
+
+		assert_eq!(mission.messages.messages[0].message.0.text, "Hello, Alpha 1.");
+		assert_eq!(mission.messages.messages[0].avi_name.as_deref(), Some("Head-CM1"));
+
+		let headers = mission.sections_after_messages.iter().map(|section| section.header.as_str()).collect::<Vec<&str>>();
+		assert_eq!(headers, vec!["#Reinforcements", "#Background bitmaps", "#Music"]);
+	}
+
+	#[test]
+	fn respew() {
+		let mission = Mission::parse(FSOTableFileParser::from_string(MISSION)).unwrap();
+		let respewed = Mission::parse(FSOTableFileParser::from_string(mission.spew())).unwrap();
+
+		assert_eq!(respewed.objects.ships.len(), 2);
+		assert_eq!(respewed.objects.ships[0].subsystems[0].primary_banks, mission.objects.ships[0].subsystems[0].primary_banks);
+		assert_eq!(respewed.events.events[0].formula, mission.events.events[0].formula);
+		assert_eq!(respewed.messages.messages[0].message, mission.messages.messages[0].message);
+		assert_eq!(respewed.sections_after_messages, mission.sections_after_messages);
+	}
+
+	#[test]
+	fn unknown_options() {
+		//Options that aren't modeled may come before ones that are, as with the arrival paths of fighterbay arrivals
+		let text = MISSION.replacen("$Arrival Cue: ( true )", "+Arrival Paths: ( \"bay01\" )\n$Arrival Cue: ( true )", 1);
+		let mission = Mission::parse(FSOTableFileParser::from_string(text.as_str())).unwrap();
+		let ship = &mission.objects.ships[0];
+		assert_eq!(ship.unknown_options.iter().map(|option| option.name.as_str()).collect::<Vec<&str>>(), vec!["+Arrival Paths:", "+Kamikaze Damage:"]);
+		assert_eq!(ship.unknown_options[0].value, "( \"bay01\" )");
+		assert_eq!(ship.arrival_cue, Some(Sexp::List(vec![Sexp::Atom("true".to_string())])));
+		assert!(ship.departure_cue.is_some());
+		assert_eq!(ship.flags.as_ref().unwrap().0, vec!["cargo-known", "player-start"]);
+		assert_eq!(ship.score, Some(8));
+		assert_eq!(mission.objects.ships[1].name, "GTC Fenris 1");
+
+		//They are spewed where they were found
+		let spewed = mission.spew();
+		let lines = spewed.lines().map(str::trim).collect::<Vec<&str>>();
+		assert!(lines.windows(2).any(|pair| pair == ["+Arrival Paths: ( \"bay01\" )", "$Arrival Cue: ( true )"]), "{}", spewed);
+		assert!(lines.windows(2).any(|pair| pair == ["+Score: 8", "+Kamikaze Damage: 500"]), "{}", spewed);
+		let respewed = Mission::parse(FSOTableFileParser::from_string(spewed.as_str())).unwrap();
+		assert_eq!(respewed.spew(), spewed);
+
+		let mut mission = Mission::parse(FSOTableFileParser::from_string(text.as_str()).with_round_trip()).unwrap();
+		assert_eq!(mission.spew_round_trip(), text);
+		mission.objects.ships[0].arrival_cue = Some(Sexp::List(vec![Sexp::Atom("false".to_string())]));
+		assert!(mission.spew_round_trip().contains("+Arrival Paths: ( \"bay01\" )\n$Arrival Cue: ( false )\n"));
+	}
+
+	#[test]
+	fn check_sexps() {
+		let mission = Mission::parse(FSOTableFileParser::from_string(MISSION)).unwrap();
+		assert_eq!(mission.check_sexps(), vec![]);
+
+		let formula = "( when
+			( is-destroyed-delay \"GTC Fenris 1\" )
+			( send-message \"#Command\" \"High\" \"Msg2\" )
+			( ai-warp-out @Priority[89] )
+			( self-destruct-all \"Alpha 1\" )
+		)";
+		let (formula, _) = Sexp::parse(&FSOTableFileParser::from_string(formula), None).unwrap();
+		let problems = SexpChecker::new(&mission).check(&formula, SexpArgumentKind::Action);
+		assert_eq!(problems, vec![
+			SexpProblem::WrongArity { operator: "is-destroyed-delay".to_string(), min: 2, max: None, found: 1 },
+			SexpProblem::WrongArgumentKind { operator: "is-destroyed-delay".to_string(), argument: 0, expected: SexpArgumentKind::Number },
+			SexpProblem::UnresolvedEntity { operator: "send-message".to_string(), argument: 2, kind: SexpArgumentKind::Message, name: "Msg2".to_string() },
+			SexpProblem::WrongArgumentKind { operator: "when".to_string(), argument: 2, expected: SexpArgumentKind::Action },
+			SexpProblem::UncataloguedOperator { operator: "self-destruct-all".to_string() },
+		]);
+		assert_eq!(problems[2].to_string(), "Argument 3 of send-message refers to unknown Message \"Msg2\"");
+		assert_eq!(problems.iter().map(SexpProblem::severity).filter(|severity| *severity == SexpSeverity::Warning).count(), 1);
+
+		//Common operators of retail missions are known, others are only warned about
+		let formula = "( when ( and ( has-docked \"Alpha 1\" \"GTC Fenris 1\" 1 ) ( is-ship-type \"cruiser\" \"GTC Fenris 1\" ) ( key-pressed \"Space\" ) ) ( ship-guardian \"Alpha 1\" ) ( set-training-context-speed 0 100 ) )";
+		let (formula, _) = Sexp::parse(&FSOTableFileParser::from_string(formula), None).unwrap();
+		assert_eq!(SexpChecker::new(&mission).check(&formula, SexpArgumentKind::Action), vec![SexpProblem::UncataloguedOperator { operator: "set-training-context-speed".to_string() }]);
+
+		//Like the engine, operators are looked up regardless of case
+		let formula = "( When ( Is-Destroyed-Delay 0 \"GTC Fenris 1\" ) ( SEND-MESSAGE \"#Command\" \"High\" \"Msg1\" ) )";
+		let (formula, _) = Sexp::parse(&FSOTableFileParser::from_string(formula), None).unwrap();
+		assert_eq!(SexpChecker::new(&mission).check(&formula, SexpArgumentKind::Action), vec![]);
+	}
+
+	//Accepts a limited number of bytes, then fails
+	#[derive(Debug)]
+	struct FullWriter(usize);
+
+	impl std::io::Write for FullWriter {
+		fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+			if buf.len() > self.0 {
+				return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "full"));
+			}
+			self.0 -= buf.len();
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> std::io::Result<()> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn spew_into() {
+		let mission = Mission::parse(FSOTableFileParser::from_string(MISSION)).unwrap();
+		for formatting in [FSOFormatting::default(), FSOFormatting::canonical(), FSOFormatting { line_ending: FSOLineEnding::CrLf, ..FSOFormatting::default() }] {
+			let written = mission.spew_into(Vec::new(), formatting.clone()).unwrap();
+			assert_eq!(String::from_utf8(written).unwrap(), mission.spew_formatted(formatting));
+		}
+
+		let error = mission.spew_into(FullWriter(100), FSOFormatting::default()).unwrap_err();
+		assert_eq!(error.kind(), std::io::ErrorKind::WriteZero);
+	}
+
+	#[test]
+	fn spew_into_builder() {
+		let mission = Mission::parse(FSOTableFileParser::from_string(MISSION)).unwrap();
+		let mut builder = FSOStreamBuilder::new(Vec::new(), FSOFormatting::default());
+		FSOTable::spew(&mission, &mut builder);
+		assert_eq!(String::from_utf8(builder.finish().unwrap()).unwrap(), mission.spew());
+
+		let mut builder = FSOStreamBuilder::new(FullWriter(100), FSOFormatting::default());
+		FSOTable::spew(&mission, &mut builder);
+		assert_eq!(builder.finish().unwrap_err().kind(), std::io::ErrorKind::WriteZero);
+	}
+}
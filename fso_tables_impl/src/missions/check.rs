@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use fso_tables::Sexp;
+use crate::missions::{find_operator, Mission, SexpArgumentKind, SexpOperator, SexpReturnKind};
+
+//Uncatalogued operators may well be valid, so they are only warned about
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SexpSeverity {
+	Error,
+	Warning
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SexpProblem {
+	//Not in the operator catalog, so neither its arguments nor what it evaluates to are checked
+	UncataloguedOperator { operator: String },
+	//A list that does not start with an operator, or a lone value where an operator was expected
+	NotAnOperator { found: String },
+	WrongArity { operator: String, min: usize, max: Option<usize>, found: usize },
+	//Argument indices start at 0 for the first argument after the operator
+	WrongArgumentKind { operator: String, argument: usize, expected: SexpArgumentKind },
+	//The whole S-expression does not evaluate to what its place in the mission needs
+	WrongReturnKind { operator: String, expected: SexpArgumentKind },
+	UnresolvedEntity { operator: String, argument: usize, kind: SexpArgumentKind, name: String }
+}
+
+impl Display for SexpProblem {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SexpProblem::UncataloguedOperator { operator } => { write!(f, "Operator {} is not in the catalog, its arguments are not checked", operator) }
+			SexpProblem::NotAnOperator { found } => { write!(f, "Expected an operator, got {}", found) }
+			SexpProblem::WrongArity { operator, min, max: Some(max), found } if min == max => { write!(f, "{} takes {} arguments, got {}", operator, min, found) }
+			SexpProblem::WrongArity { operator, min, max: Some(max), found } => { write!(f, "{} takes {} to {} arguments, got {}", operator, min, max, found) }
+			SexpProblem::WrongArity { operator, min, max: None, found } => { write!(f, "{} takes at least {} arguments, got {}", operator, min, found) }
+			SexpProblem::WrongArgumentKind { operator, argument, expected } => { write!(f, "Argument {} of {} should be {}", argument + 1, operator, expected) }
+			SexpProblem::WrongReturnKind { operator, expected } => { write!(f, "{} does not evaluate to {}", operator, expected) }
+			SexpProblem::UnresolvedEntity { operator, argument, kind, name } => { write!(f, "Argument {} of {} refers to unknown {} \"{}\"", argument + 1, operator, kind, name) }
+		}
+	}
+}
+
+impl SexpProblem {
+	pub fn severity(&self) -> SexpSeverity {
+		match self {
+			SexpProblem::UncataloguedOperator { .. } => { SexpSeverity::Warning }
+			_ => { SexpSeverity::Error }
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SexpDiagnostic {
+	//Human readable description of where in the mission the S-expression is
+	pub location: String,
+	pub problem: SexpProblem
+}
+
+impl Display for SexpDiagnostic {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: {}", self.location, self.problem)
+	}
+}
+
+//Names of everything in a mission that S-expressions can refer to. Lookups are case-insensitive, as they are in the engine
+#[derive(Clone, Debug, Default)]
+pub struct SexpChecker {
+	ships: HashSet<String>,
+	wings: HashSet<String>,
+	messages: HashSet<String>,
+	events: HashSet<String>,
+	goals: HashSet<String>,
+	waypoint_paths: HashSet<String>,
+	jump_nodes: HashSet<String>
+}
+
+impl SexpChecker {
+	pub fn new(mission: &Mission) -> Self {
+		let names = |names: Vec<&str>| names.into_iter().map(|name| name.to_lowercase()).collect::<HashSet<String>>();
+		SexpChecker {
+			ships: names(mission.objects.ships.iter().map(|ship| ship.name.as_str()).collect()),
+			wings: names(mission.wings.wings.iter().map(|wing| wing.name.as_str()).collect()),
+			messages: names(mission.messages.messages.iter().map(|message| message.name.as_str()).collect()),
+			events: names(mission.events.events.iter().filter_map(|event| event.name.as_deref()).collect()),
+			goals: names(mission.goals.goals.iter().map(|goal| goal.name.as_str()).collect()),
+			waypoint_paths: names(mission.waypoints.paths.iter().map(|path| path.name.as_str()).collect()),
+			jump_nodes: names(mission.waypoints.jump_nodes.iter().filter_map(|node| node.name.as_deref()).collect())
+		}
+	}
+
+	//Checks an S-expression used where something of the expected kind is needed, i.e. Boolean for arrival cues
+	pub fn check(&self, sexp: &Sexp, expected: SexpArgumentKind) -> Vec<SexpProblem> {
+		let mut problems = Vec::new();
+		match sexp {
+			Sexp::List(_) => { self.check_operator(sexp, &mut problems); }
+			other => { problems.push(SexpProblem::NotAnOperator { found: other.to_string() }); }
+		}
+		if let Some(operator) = sexp.operator().and_then(find_operator) {
+			if !Self::returns_kind(operator, expected) {
+				problems.push(SexpProblem::WrongReturnKind { operator: operator.name.to_string(), expected });
+			}
+		}
+		problems
+	}
+
+	fn returns_kind(operator: &SexpOperator, expected: SexpArgumentKind) -> bool {
+		matches!((expected, operator.returns),
+			(SexpArgumentKind::Boolean, SexpReturnKind::Boolean) |
+			(SexpArgumentKind::Number, SexpReturnKind::Number) |
+			(SexpArgumentKind::Action, SexpReturnKind::Action | SexpReturnKind::Boolean) |
+			(SexpArgumentKind::AiGoal, SexpReturnKind::AiGoal))
+	}
+
+	fn check_operator(&self, sexp: &Sexp, problems: &mut Vec<SexpProblem>) {
+		let Some(name) = sexp.operator() else {
+			problems.push(SexpProblem::NotAnOperator { found: sexp.to_string() });
+			return;
+		};
+		let Some(operator) = find_operator(name) else {
+			problems.push(SexpProblem::UncataloguedOperator { operator: name.to_string() });
+			return;
+		};
+
+		let arguments = sexp.arguments();
+		if arguments.len() < operator.min_arguments || operator.max_arguments.is_some_and(|max| arguments.len() > max) {
+			problems.push(SexpProblem::WrongArity { operator: name.to_string(), min: operator.min_arguments, max: operator.max_arguments, found: arguments.len() });
+		}
+
+		for (index, argument) in arguments.into_iter().enumerate() {
+			let Some(kind) = operator.argument_kind(index) else { break; };
+			self.check_argument(operator, index, kind, argument, problems);
+		}
+	}
+
+	fn check_argument(&self, operator: &SexpOperator, index: usize, kind: SexpArgumentKind, argument: &Sexp, problems: &mut Vec<SexpProblem>) {
+		let wrong_kind = || SexpProblem::WrongArgumentKind { operator: operator.name.to_string(), argument: index, expected: kind };
+
+		match argument {
+			Sexp::List(_) => {
+				self.check_operator(argument, problems);
+				if let Some(inner) = argument.operator().and_then(find_operator) {
+					if !Self::returns_kind(inner, kind) {
+						problems.push(wrong_kind());
+					}
+				}
+			}
+			Sexp::Number(_) => {
+				if kind != SexpArgumentKind::Number {
+					problems.push(wrong_kind());
+				}
+			}
+			//Variables can't be resolved before the mission runs, but number variables can't go where strings are expected and vice versa
+			Sexp::Variable { quoted, .. } => {
+				let accepts_variable = match kind {
+					SexpArgumentKind::Variable => { true }
+					SexpArgumentKind::Number => { !quoted }
+					SexpArgumentKind::Boolean | SexpArgumentKind::Action | SexpArgumentKind::AiGoal => { false }
+					_ => { *quoted }
+				};
+				if !accepts_variable {
+					problems.push(wrong_kind());
+				}
+			}
+			Sexp::String(name) | Sexp::Atom(name) => {
+				match kind {
+					SexpArgumentKind::Boolean | SexpArgumentKind::Number | SexpArgumentKind::Action | SexpArgumentKind::AiGoal | SexpArgumentKind::Variable => {
+						problems.push(wrong_kind());
+					}
+					kind if !self.resolves(kind, name) => {
+						problems.push(SexpProblem::UnresolvedEntity { operator: operator.name.to_string(), argument: index, kind, name: name.clone() });
+					}
+					_ => { }
+				}
+			}
+			Sexp::Comment(_) => { }
+		}
+	}
+
+	fn resolves(&self, kind: SexpArgumentKind, name: &str) -> bool {
+		let lowercase = name.to_lowercase();
+		let name = lowercase.as_str();
+		match kind {
+			SexpArgumentKind::Ship => { self.ships.contains(name) }
+			SexpArgumentKind::Wing => { self.wings.contains(name) }
+			SexpArgumentKind::ShipOrWing => { self.ships.contains(name) || self.wings.contains(name) }
+			SexpArgumentKind::Object => {
+				self.ships.contains(name) || self.wings.contains(name) || name.rsplit_once(':').is_some_and(|(path, point)| self.waypoint_paths.contains(path) && point.parse::<usize>().is_ok())
+			}
+			SexpArgumentKind::Message => { self.messages.contains(name) }
+			SexpArgumentKind::MessageSender => { name.starts_with('#') || self.ships.contains(name) }
+			SexpArgumentKind::Event => { self.events.contains(name) }
+			SexpArgumentKind::Goal => { self.goals.contains(name) }
+			SexpArgumentKind::WaypointPath => { self.waypoint_paths.contains(name) }
+			SexpArgumentKind::JumpNode => { self.jump_nodes.contains(name) }
+			SexpArgumentKind::Priority => { matches!(name, "high" | "normal" | "low") }
+			_ => { true }
+		}
+	}
+}
+
+impl Mission {
+	//Checks all formulas and cues of the mission against the operator catalog and the entities defined in the mission
+	pub fn check_sexps(&self) -> Vec<SexpDiagnostic> {
+		let checker = SexpChecker::new(self);
+		let mut to_check: Vec<(String, &Sexp, SexpArgumentKind)> = Vec::new();
+
+		for ship in &self.objects.ships {
+			if let Some(cue) = &ship.arrival_cue {
+				to_check.push((format!("#Objects: {} $Arrival Cue:", ship.name), cue, SexpArgumentKind::Boolean));
+			}
+			if let Some(cue) = &ship.departure_cue {
+				to_check.push((format!("#Objects: {} $Departure Cue:", ship.name), cue, SexpArgumentKind::Boolean));
+			}
+		}
+		for wing in &self.wings.wings {
+			if let Some(cue) = &wing.arrival_cue {
+				to_check.push((format!("#Wings: {} $Arrival Cue:", wing.name), cue, SexpArgumentKind::Boolean));
+			}
+			if let Some(cue) = &wing.departure_cue {
+				to_check.push((format!("#Wings: {} $Departure Cue:", wing.name), cue, SexpArgumentKind::Boolean));
+			}
+		}
+		for (index, event) in self.events.events.iter().enumerate() {
+			let name = event.name.clone().unwrap_or_else(|| format!("Event {}", index));
+			to_check.push((format!("#Events: {}", name), &event.formula, SexpArgumentKind::Action));
+		}
+		for goal in &self.goals.goals {
+			to_check.push((format!("#Goals: {}", goal.name), &goal.formula, SexpArgumentKind::Boolean));
+		}
+
+		to_check.into_iter().flat_map(|(location, sexp, expected)| {
+			checker.check(sexp, expected).into_iter().map(move |problem| SexpDiagnostic { location: location.clone(), problem })
+		}).collect()
+	}
+}
@@ -0,0 +1,259 @@
+use once_cell::sync::Lazy;
+use strum_macros::Display;
+
+//What an argument of an operator has to be. Entity kinds must name something defined in the mission
+#[derive(Clone, Copy, Debug, PartialEq, Display)]
+pub enum SexpArgumentKind {
+	Boolean,
+	Number,
+	//Operators that do something, or booleans, as the engine evaluates those as well
+	Action,
+	AiGoal,
+	String,
+	Team,
+	Priority,
+	//Subsystems live in the ship's model, so they cannot be resolved from the mission alone
+	Subsystem,
+	Ship,
+	Wing,
+	ShipOrWing,
+	//Ships, wings or waypoints, i.e. "Waypoint path 1:2"
+	Object,
+	Message,
+	//Either a ship or a special sender such as "#Command"
+	MessageSender,
+	Event,
+	Goal,
+	WaypointPath,
+	JumpNode,
+	Variable
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Display)]
+pub enum SexpReturnKind {
+	Boolean,
+	Number,
+	Action,
+	AiGoal
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SexpOperator {
+	pub name: &'static str,
+	pub min_arguments: usize,
+	//None for operators taking any number of arguments
+	pub max_arguments: Option<usize>,
+	//Kinds of the arguments in order. Arguments past the end have the kind of the last one
+	pub arguments: &'static [SexpArgumentKind],
+	pub returns: SexpReturnKind
+}
+
+impl SexpOperator {
+	pub fn argument_kind(&self, index: usize) -> Option<SexpArgumentKind> {
+		self.arguments.get(index).or(self.arguments.last()).copied()
+	}
+}
+
+pub fn find_operator(name: &str) -> Option<&'static SexpOperator> {
+	SEXP_OPERATORS.iter().find(|operator| operator.name.eq_ignore_ascii_case(name))
+}
+
+//The common operators of the engine. It has many more, which the checker only warns about
+pub static SEXP_OPERATORS: Lazy<Vec<SexpOperator>> = Lazy::new(|| {
+	use SexpArgumentKind::*;
+
+	let operator = |name, min_arguments, max_arguments, arguments, returns| SexpOperator { name, min_arguments, max_arguments, arguments, returns };
+	let boolean = |name, min_arguments, max_arguments, arguments| operator(name, min_arguments, max_arguments, arguments, SexpReturnKind::Boolean);
+	let number = |name, min_arguments, max_arguments, arguments| operator(name, min_arguments, max_arguments, arguments, SexpReturnKind::Number);
+	let action = |name, min_arguments, max_arguments, arguments| operator(name, min_arguments, max_arguments, arguments, SexpReturnKind::Action);
+	let ai_goal = |name, min_arguments, max_arguments, arguments| operator(name, min_arguments, max_arguments, arguments, SexpReturnKind::AiGoal);
+
+	vec![
+		//Logical
+		boolean("true", 0, Some(0), &[]),
+		boolean("false", 0, Some(0), &[]),
+		boolean("and", 1, None, &[Boolean]),
+		boolean("and-in-sequence", 1, None, &[Boolean]),
+		boolean("or", 1, None, &[Boolean]),
+		boolean("not", 1, Some(1), &[Boolean]),
+		boolean("xor", 2, None, &[Boolean]),
+		boolean("=", 2, None, &[Number]),
+		boolean("!=", 2, None, &[Number]),
+		boolean("<", 2, None, &[Number]),
+		boolean(">", 2, None, &[Number]),
+		boolean("<=", 2, None, &[Number]),
+		boolean(">=", 2, None, &[Number]),
+		boolean("string-equals", 2, None, &[String]),
+		boolean("has-time-elapsed", 1, Some(1), &[Number]),
+
+		//Arithmetic
+		number("+", 1, None, &[Number]),
+		number("-", 1, None, &[Number]),
+		number("*", 1, None, &[Number]),
+		number("/", 1, None, &[Number]),
+		number("mod", 2, None, &[Number]),
+		number("rand", 2, Some(3), &[Number]),
+		number("abs", 1, Some(1), &[Number]),
+		number("min", 1, None, &[Number]),
+		number("max", 1, None, &[Number]),
+		number("mission-time", 0, Some(0), &[]),
+
+		//Status
+		boolean("is-destroyed", 1, None, &[ShipOrWing]),
+		boolean("is-destroyed-delay", 2, None, &[Number, ShipOrWing]),
+		boolean("is-subsystem-destroyed", 2, Some(2), &[Ship, Subsystem]),
+		boolean("is-subsystem-destroyed-delay", 3, Some(3), &[Ship, Subsystem, Number]),
+		boolean("is-disabled", 1, None, &[Ship]),
+		boolean("is-disabled-delay", 2, None, &[Number, Ship]),
+		boolean("is-disarmed", 1, None, &[Ship]),
+		boolean("is-disarmed-delay", 2, None, &[Number, Ship]),
+		boolean("has-arrived", 1, None, &[ShipOrWing]),
+		boolean("has-arrived-delay", 2, None, &[Number, ShipOrWing]),
+		boolean("has-departed", 1, None, &[ShipOrWing]),
+		boolean("has-departed-delay", 2, None, &[Number, ShipOrWing]),
+		boolean("depart-node-delay", 3, None, &[Number, JumpNode, Ship]),
+		boolean("is-event-true", 1, Some(1), &[Event]),
+		boolean("is-event-true-delay", 2, Some(3), &[Event, Number, Boolean]),
+		boolean("is-event-false", 1, Some(1), &[Event]),
+		boolean("is-event-false-delay", 2, Some(3), &[Event, Number, Boolean]),
+		boolean("is-goal-true-delay", 2, Some(3), &[Goal, Number, Boolean]),
+		boolean("is-goal-false-delay", 2, Some(3), &[Goal, Number, Boolean]),
+		boolean("is-goal-incomplete", 1, Some(1), &[Goal]),
+		boolean("is-iff", 2, None, &[Team, ShipOrWing]),
+		boolean("is-ship-visible", 1, Some(1), &[Ship]),
+		boolean("percent-ships-destroyed", 2, None, &[Number, ShipOrWing]),
+		boolean("percent-ships-departed", 2, None, &[Number, ShipOrWing]),
+		number("distance", 2, Some(2), &[Object]),
+		number("hits-left", 1, Some(1), &[Ship]),
+		number("hits-left-subsystem", 2, Some(3), &[Ship, Subsystem, Boolean]),
+		number("shields-left", 1, Some(1), &[Ship]),
+		number("num-ships-in-battle", 0, None, &[ShipOrWing]),
+		number("time-ship-arrived", 1, Some(1), &[Ship]),
+		number("time-ship-destroyed", 1, Some(1), &[Ship]),
+		number("time-ship-departed", 1, Some(1), &[Ship]),
+		number("time-wing-arrived", 1, Some(1), &[Wing]),
+		number("time-wing-destroyed", 1, Some(1), &[Wing]),
+		number("time-wing-departed", 1, Some(1), &[Wing]),
+		boolean("has-docked", 3, Some(3), &[Ship, Ship, Number]),
+		boolean("has-undocked", 3, Some(3), &[Ship, Ship, Number]),
+		boolean("has-docked-delay", 4, Some(4), &[Ship, Ship, Number, Number]),
+		boolean("has-undocked-delay", 4, Some(4), &[Ship, Ship, Number, Number]),
+		boolean("is-cargo-known", 1, None, &[Ship]),
+		boolean("is-cargo-known-delay", 2, None, &[Number, Ship]),
+		boolean("cargo-known-delay", 2, None, &[Number, Ship]),
+		boolean("has-been-tagged-delay", 2, None, &[Number, Ship]),
+		boolean("is-tagged", 1, Some(1), &[Ship]),
+		boolean("is-ship-type", 2, None, &[String, Ship]),
+		boolean("is-ship-class", 2, None, &[String, Ship]),
+		boolean("ship-type-destroyed", 2, Some(2), &[Number, String]),
+		boolean("are-waypoints-done", 2, Some(2), &[Ship, WaypointPath]),
+		boolean("are-waypoints-done-delay", 3, Some(3), &[Ship, WaypointPath, Number]),
+		boolean("primaries-depleted", 1, Some(1), &[Ship]),
+		boolean("secondaries-depleted", 1, Some(1), &[Ship]),
+		boolean("targeted", 1, Some(3), &[Ship, Number, Subsystem]),
+		boolean("key-pressed", 1, Some(2), &[String, Number]),
+		boolean("skill-level-at-least", 1, Some(1), &[String]),
+		boolean("special-check", 1, Some(1), &[Number]),
+		boolean("is-previous-event-true", 2, Some(3), &[String, String, Boolean]),
+		boolean("is-previous-event-false", 2, Some(3), &[String, String, Boolean]),
+		boolean("is-previous-goal-true", 2, Some(3), &[String, String, Boolean]),
+		boolean("is-previous-goal-false", 2, Some(3), &[String, String, Boolean]),
+		number("num-kills", 1, Some(1), &[Ship]),
+		number("num-type-kills", 2, Some(2), &[Ship, String]),
+		number("num-class-kills", 2, Some(2), &[Ship, String]),
+		number("num-players", 0, Some(0), &[]),
+		number("team-score", 1, Some(1), &[Number]),
+		number("get-object-x", 1, Some(5), &[Object, Subsystem, Number]),
+		number("get-object-y", 1, Some(5), &[Object, Subsystem, Number]),
+		number("get-object-z", 1, Some(5), &[Object, Subsystem, Number]),
+
+		//Conditionals
+		action("when", 2, None, &[Boolean, Action]),
+		action("every-time", 2, None, &[Boolean, Action]),
+		action("cond", 1, None, &[Action]),
+
+		//Actions
+		action("do-nothing", 0, Some(0), &[]),
+		action("send-message", 3, Some(3), &[MessageSender, Priority, Message]),
+		action("send-message-list", 4, None, &[MessageSender, Priority, Message, Number]),
+		action("send-random-message", 3, None, &[MessageSender, Priority, Message]),
+		action("add-goal", 2, Some(2), &[ShipOrWing, AiGoal]),
+		action("remove-goal", 2, Some(2), &[ShipOrWing, AiGoal]),
+		action("clear-goals", 1, None, &[ShipOrWing]),
+		action("add-ship-goal", 2, Some(2), &[Ship, AiGoal]),
+		action("add-wing-goal", 2, Some(2), &[Wing, AiGoal]),
+		action("change-iff", 2, None, &[Team, ShipOrWing]),
+		action("protect-ship", 1, None, &[Ship]),
+		action("unprotect-ship", 1, None, &[Ship]),
+		action("ship-invulnerable", 1, None, &[Ship]),
+		action("ship-vulnerable", 1, None, &[Ship]),
+		action("ship-invisible", 1, None, &[Ship]),
+		action("ship-visible", 1, None, &[Ship]),
+		action("self-destruct", 1, None, &[Ship]),
+		action("sabotage-subsystem", 3, Some(3), &[Ship, Subsystem, Number]),
+		action("repair-subsystem", 3, Some(4), &[Ship, Subsystem, Number, Boolean]),
+		action("set-subsystem-strength", 3, Some(4), &[Ship, Subsystem, Number, Boolean]),
+		action("invalidate-goal", 1, None, &[Goal]),
+		action("validate-goal", 1, None, &[Goal]),
+		action("modify-variable", 2, Some(2), &[Variable, Number]),
+		action("end-mission", 0, Some(3), &[Boolean]),
+		action("end-campaign", 0, Some(1), &[Boolean]),
+		action("player-use-ai", 0, Some(0), &[]),
+		action("player-not-use-ai", 0, Some(0), &[]),
+		action("ship-guardian", 1, None, &[Ship]),
+		action("ship-no-guardian", 1, None, &[Ship]),
+		action("ship-vanish", 1, None, &[Ship]),
+		action("destroy-instantly", 1, None, &[Ship]),
+		action("kamikaze", 2, None, &[Number, ShipOrWing]),
+		action("fire-beam", 3, None, &[Ship, Subsystem, Ship, Subsystem, Boolean]),
+		action("beam-free", 2, None, &[Ship, Subsystem]),
+		action("beam-lock", 2, None, &[Ship, Subsystem]),
+		action("beam-free-all", 1, None, &[Ship]),
+		action("beam-lock-all", 1, None, &[Ship]),
+		action("turret-free", 2, None, &[Ship, Subsystem]),
+		action("turret-lock", 2, None, &[Ship, Subsystem]),
+		action("turret-free-all", 1, None, &[Ship]),
+		action("turret-lock-all", 1, None, &[Ship]),
+		action("change-ship-class", 2, None, &[String, Ship]),
+		action("set-arrival-info", 2, Some(7), &[ShipOrWing, String, String, Number, Number, Number, Boolean]),
+		action("set-departure-info", 2, Some(6), &[ShipOrWing, String, String, Number, Number, Boolean]),
+		action("set-cargo", 2, Some(3), &[String, Ship, Subsystem]),
+		action("transfer-cargo", 2, Some(2), &[Ship, Ship]),
+		action("exchange-cargo", 2, Some(2), &[Ship, Ship]),
+		action("key-reset", 1, None, &[String]),
+		action("good-rearm-time", 2, Some(2), &[Team, Number]),
+		action("grant-promotion", 0, Some(0), &[]),
+		action("grant-medal", 1, Some(1), &[String]),
+		action("allow-ship", 1, Some(1), &[String]),
+		action("allow-weapon", 1, Some(1), &[String]),
+		action("tech-add-ships", 1, None, &[String]),
+		action("tech-add-weapons", 1, None, &[String]),
+		action("set-object-position", 4, Some(4), &[Object, Number]),
+		action("fade-in", 0, Some(4), &[Number]),
+		action("fade-out", 0, Some(4), &[Number]),
+
+		//AI goals
+		ai_goal("ai-chase", 2, Some(3), &[ShipOrWing, Number, Boolean]),
+		ai_goal("ai-chase-any", 1, Some(1), &[Number]),
+		ai_goal("ai-guard", 2, Some(2), &[ShipOrWing, Number]),
+		ai_goal("ai-destroy-subsystem", 3, Some(4), &[Ship, Subsystem, Number, Boolean]),
+		ai_goal("ai-dock", 4, Some(4), &[Ship, String, String, Number]),
+		ai_goal("ai-undock", 1, Some(2), &[Number, Ship]),
+		ai_goal("ai-waypoints", 2, Some(2), &[WaypointPath, Number]),
+		ai_goal("ai-waypoints-once", 2, Some(2), &[WaypointPath, Number]),
+		ai_goal("ai-warp-out", 1, Some(1), &[Number]),
+		ai_goal("ai-stay-still", 2, Some(2), &[Object, Number]),
+		ai_goal("ai-play-dead", 1, Some(1), &[Number]),
+		ai_goal("ai-chase-wing", 2, Some(2), &[Wing, Number]),
+		ai_goal("ai-guard-wing", 2, Some(2), &[Wing, Number]),
+		ai_goal("ai-disable-ship", 2, Some(3), &[Ship, Number, Boolean]),
+		ai_goal("ai-disarm-ship", 2, Some(3), &[Ship, Number, Boolean]),
+		ai_goal("ai-evade-ship", 2, Some(2), &[Ship, Number]),
+		ai_goal("ai-ignore", 2, Some(2), &[ShipOrWing, Number]),
+		ai_goal("ai-ignore-new", 2, Some(2), &[ShipOrWing, Number]),
+		ai_goal("ai-stay-near-ship", 2, Some(3), &[Ship, Number, Number]),
+		ai_goal("ai-keep-safe-distance", 1, Some(1), &[Number]),
+		ai_goal("ai-rearm-repair", 2, Some(2), &[Ship, Number]),
+		ai_goal("ai-form-on-wing", 1, Some(1), &[Ship]),
+	]
+});
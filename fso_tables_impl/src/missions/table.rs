@@ -0,0 +1,299 @@
+use fso_tables::{fso_table, FSOFloat, Sexp};
+use crate::{Matrix, Vec3D, XString};
+use crate::missions::{EntryKeys, MissionNotes, MultiText, NameList, UnknownOption, UnparsedSection};
+
+//A .fs2 mission file. Sections that are not modeled (briefings, players, reinforcements, ...) are kept verbatim
+#[fso_table(table_end="#End", toplevel)]
+pub struct Mission {
+	#[unnamed]
+	pub info: MissionInfo,
+	#[unnamed]
+	pub sections_before_objects: Vec<UnparsedSection>,
+	#[unnamed]
+	pub objects: MissionObjects,
+	#[unnamed]
+	pub wings: MissionWings,
+	#[unnamed]
+	pub events: MissionEvents,
+	#[unnamed]
+	pub goals: MissionGoals,
+	#[unnamed]
+	pub waypoints: MissionWaypoints,
+	#[unnamed]
+	pub messages: MissionMessages,
+	#[unnamed]
+	pub sections_after_messages: Vec<UnparsedSection>
+}
+
+#[fso_table(table_start="#Mission Info")]
+pub struct MissionInfo {
+	pub version: FSOFloat,
+	pub name: XString,
+	pub author: Option<String>,
+	pub created: Option<String>,
+	pub modified: Option<String>,
+	pub notes: Option<MissionNotes>,
+	pub mission_desc: Option<MultiText>,
+	#[fso_name="+Game Type Flags:"]
+	pub game_type_flags: Option<i32>,
+	#[fso_name="+Flags:"]
+	pub flags: Option<i64>,
+	#[fso_name="+NumPlayers:"]
+	pub num_players: Option<i32>,
+	#[fso_name="+NumRespawns:"]
+	pub num_respawns: Option<i32>,
+	#[fso_name="+Max Respawn Time:"]
+	pub max_respawn_time: Option<i32>,
+	#[fso_name="+Red Alert:"]
+	pub red_alert: Option<i32>,
+	#[fso_name="+Scramble:"]
+	pub scramble: Option<i32>,
+	#[fso_name="+Disallow Support:"]
+	pub disallow_support: Option<i32>,
+	#[fso_name="+Hull Repair Ceiling:"]
+	pub hull_repair_ceiling: Option<FSOFloat>,
+	#[fso_name="+Subsystem Repair Ceiling:"]
+	pub subsystem_repair_ceiling: Option<FSOFloat>,
+	#[unknown]
+	pub unknown_options: Vec<UnknownOption<MissionInfo>>
+}
+
+impl EntryKeys for MissionInfo {
+	const KEYS: &'static [&'static str] = &[];
+}
+
+#[fso_table(table_start="#Objects")]
+pub struct MissionObjects {
+	#[unnamed]
+	pub ships: Vec<MissionShip>
+}
+
+#[fso_table]
+pub struct MissionShip {
+	pub name: String,
+	pub class: String,
+	pub alt: Option<String>,
+	pub callsign: Option<String>,
+	pub team: String,
+	pub location: Vec3D,
+	pub orientation: Matrix,
+	#[fso_name="$AI Behavior:"]
+	pub ai_behavior: Option<String>,
+	#[fso_name="+AI Class:"]
+	pub ai_class: Option<String>,
+	#[fso_name="$Cargo 1:"]
+	pub cargo: Option<XString>,
+	pub initial_velocity: Option<i32>,
+	pub initial_hull: Option<i32>,
+	pub initial_shields: Option<i32>,
+	#[unnamed]
+	pub subsystems: Vec<MissionSubsystem>,
+	pub arrival_location: Option<String>,
+	#[fso_name="+Arrival Distance:"]
+	pub arrival_distance: Option<i32>,
+	pub arrival_anchor: Option<String>,
+	#[fso_name="+Arrival Delay:"]
+	pub arrival_delay: Option<i32>,
+	pub arrival_cue: Option<Sexp>,
+	pub departure_location: Option<String>,
+	pub departure_anchor: Option<String>,
+	#[fso_name="+Departure Delay:"]
+	pub departure_delay: Option<i32>,
+	pub departure_cue: Option<Sexp>,
+	pub determination: Option<i32>,
+	#[fso_name="+Flags:"]
+	pub flags: Option<NameList>,
+	#[fso_name="+Flags2:"]
+	pub flags2: Option<NameList>,
+	#[fso_name="+Respawn priority:"]
+	pub respawn_priority: Option<i32>,
+	#[fso_name="+Escort priority:"]
+	pub escort_priority: Option<i32>,
+	#[fso_name="+Hotkey:"]
+	pub hotkey: Option<i32>,
+	#[fso_name="+Group:"]
+	pub group: Option<i32>,
+	#[fso_name="+Score:"]
+	pub score: Option<i32>,
+	#[unknown]
+	pub unknown_options: Vec<UnknownOption<MissionShip>>
+}
+
+impl EntryKeys for MissionShip {
+	const KEYS: &'static [&'static str] = &["$Name:", "+Subsystem:"];
+}
+
+#[fso_table(prefix="+")]
+pub struct MissionSubsystem {
+	#[fso_name="+Subsystem:"]
+	pub name: String,
+	#[fso_name="$Damage:"]
+	pub damage: Option<FSOFloat>,
+	pub cargo_name: Option<XString>,
+	#[fso_name="+AI Class:"]
+	pub ai_class: Option<String>,
+	pub primary_banks: Option<NameList>,
+	pub pbank_ammo: Option<Vec<i32>>,
+	pub secondary_banks: Option<NameList>,
+	pub sbank_ammo: Option<Vec<i32>>
+}
+
+#[fso_table(table_start="#Wings")]
+pub struct MissionWings {
+	#[unnamed]
+	pub wings: Vec<MissionWing>
+}
+
+#[fso_table]
+pub struct MissionWing {
+	pub name: String,
+	pub waves: i32,
+	pub wave_threshold: i32,
+	pub special_ship: i32,
+	pub arrival_location: Option<String>,
+	#[fso_name="+Arrival Distance:"]
+	pub arrival_distance: Option<i32>,
+	pub arrival_anchor: Option<String>,
+	#[fso_name="+Arrival delay:"]
+	pub arrival_delay: Option<i32>,
+	pub arrival_cue: Option<Sexp>,
+	pub departure_location: Option<String>,
+	pub departure_anchor: Option<String>,
+	#[fso_name="+Departure delay:"]
+	pub departure_delay: Option<i32>,
+	pub departure_cue: Option<Sexp>,
+	pub ships: NameList,
+	#[fso_name="+Flags:"]
+	pub flags: Option<NameList>,
+	#[fso_name="+Wave Delay Min:"]
+	pub wave_delay_min: Option<i32>,
+	#[fso_name="+Wave Delay Max:"]
+	pub wave_delay_max: Option<i32>,
+	#[unknown]
+	pub unknown_options: Vec<UnknownOption<MissionWing>>
+}
+
+impl EntryKeys for MissionWing {
+	const KEYS: &'static [&'static str] = &["$Name:"];
+}
+
+#[fso_table(table_start="#Events")]
+pub struct MissionEvents {
+	#[unnamed]
+	pub events: Vec<MissionEvent>
+}
+
+#[fso_table(prefix="+")]
+pub struct MissionEvent {
+	#[fso_name="$Formula:"]
+	pub formula: Sexp,
+	pub name: Option<String>,
+	pub repeat_count: Option<i32>,
+	pub trigger_count: Option<i32>,
+	pub interval: Option<i32>,
+	pub score: Option<i32>,
+	pub chained: Option<i32>,
+	pub objective: Option<XString>,
+	#[fso_name="+Objective key:"]
+	pub objective_key: Option<XString>,
+	pub team: Option<i32>,
+	#[unknown]
+	pub unknown_options: Vec<UnknownOption<MissionEvent>>
+}
+
+impl EntryKeys for MissionEvent {
+	const KEYS: &'static [&'static str] = &["$Formula:"];
+}
+
+#[fso_table(table_start="#Goals")]
+pub struct MissionGoals {
+	#[unnamed]
+	pub goals: Vec<MissionGoal>
+}
+
+#[fso_table(prefix="+")]
+pub struct MissionGoal {
+	#[fso_name="$Type:"]
+	pub goal_type: String,
+	pub name: String,
+	#[fso_name="$MessageNew:"]
+	pub message: Option<MultiText>,
+	#[fso_name="$Formula:"]
+	pub formula: Sexp,
+	#[existence]
+	pub invalid: bool,
+	#[existence]
+	#[fso_name="+No music"]
+	pub no_music: bool,
+	pub score: Option<i32>,
+	pub team: Option<i32>,
+	#[unknown]
+	pub unknown_options: Vec<UnknownOption<MissionGoal>>
+}
+
+impl EntryKeys for MissionGoal {
+	const KEYS: &'static [&'static str] = &["$Type:"];
+}
+
+#[fso_table(table_start="#Waypoints")]
+pub struct MissionWaypoints {
+	#[unnamed]
+	pub jump_nodes: Vec<JumpNode>,
+	#[unnamed]
+	pub paths: Vec<WaypointPath>
+}
+
+#[fso_table]
+pub struct JumpNode {
+	#[fso_name="$Jump Node:"]
+	pub position: Vec3D,
+	#[fso_name="$Jump Node Name:"]
+	pub name: Option<String>,
+	#[unknown]
+	pub unknown_options: Vec<UnknownOption<JumpNode>>
+}
+
+impl EntryKeys for JumpNode {
+	const KEYS: &'static [&'static str] = &["$Jump Node:", "$Name:"];
+}
+
+#[fso_table]
+pub struct WaypointPath {
+	pub name: String,
+	#[fso_name="$List:"]
+	pub points: Vec<WaypointPoint>
+}
+
+#[fso_table]
+pub struct WaypointPoint {
+	#[unnamed]
+	pub position: (FSOFloat, FSOFloat, FSOFloat)
+}
+
+#[fso_table(table_start="#Messages")]
+pub struct MissionMessages {
+	pub command_sender: Option<String>,
+	pub command_persona: Option<String>,
+	#[unnamed]
+	pub messages: Vec<MissionMessage>
+}
+
+#[fso_table]
+pub struct MissionMessage {
+	pub name: String,
+	pub team: Option<i32>,
+	#[fso_name="$MessageNew:"]
+	pub message: MultiText,
+	#[fso_name="+Persona:"]
+	pub persona: Option<String>,
+	#[fso_name="+Avi Name:"]
+	pub avi_name: Option<String>,
+	#[fso_name="+Wave Name:"]
+	pub wave_name: Option<String>,
+	#[unknown]
+	pub unknown_options: Vec<UnknownOption<MissionMessage>>
+}
+
+impl EntryKeys for MissionMessage {
+	const KEYS: &'static [&'static str] = &["$Name:"];
+}
@@ -0,0 +1,77 @@
+use fso_tables::{FSOBuilder, FSOParser, FSOParsingError, FSOParsingHangingGobble, FSOTable, FSOTableFileParser, Sexp};
+use crate::XString;
+
+//Free text spanning multiple lines, terminated by $end_multi_text
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MultiText(pub XString);
+
+impl FSOTable for MultiText {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, _hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		let line = state.line();
+		if !state.get().contains("$end_multi_text") {
+			return Err(FSOParsingError { line, reason: "Expected $end_multi_text.".to_string(), comments: None, version_string: None });
+		}
+
+		let text = state.read_until_target("$end_multi_text", true).trim();
+		if text.starts_with("XSTR(") {
+			let (text, _) = XString::parse(&FSOTableFileParser::from_string(text), None).map_err(|mut err| {
+				err.line += line;
+				err
+			})?;
+			Ok((MultiText(text), None))
+		}
+		else {
+			Ok((MultiText(XString::new(text.to_string())), None))
+		}
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		self.0.spew(state);
+		state.append("\n$end_multi_text");
+	}
+}
+
+//The $Notes: block of a mission, terminated by $End Notes:
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MissionNotes(pub String);
+
+impl FSOTable for MissionNotes {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, _hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		if !state.get().contains("$End Notes:") {
+			return Err(FSOParsingError { line: state.line(), reason: "Expected $End Notes:.".to_string(), comments: None, version_string: None });
+		}
+		Ok((MissionNotes(state.read_until_target("$End Notes:", true).trim().to_string()), None))
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		state.append("\n");
+		state.append(self.0.as_str());
+		state.append("\n$End Notes:");
+	}
+}
+
+//A parenthesized list of (usually quoted) names, such as the ships of a wing or a list of flags
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NameList(pub Vec<String>);
+
+impl FSOTable for NameList {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, _hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		let line = state.line();
+		let (list, _) = Sexp::parse(state, None)?;
+		let Sexp::List(elements) = list else { unreachable!("Sexp::parse only accepts lists") };
+		let names = elements.into_iter().filter(|name| !matches!(name, Sexp::Comment(_))).map(|name| match name {
+			Sexp::String(name) | Sexp::Atom(name) => { Ok(name) }
+			Sexp::List(_) => { Err(FSOParsingError { line, reason: "Expected a list of names, got a nested list.".to_string(), comments: None, version_string: None }) }
+			other => { Ok(other.to_string()) }
+		}).collect::<Result<Vec<String>, FSOParsingError>>()?;
+		Ok((NameList(names), None))
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		state.append("(");
+		for name in &self.0 {
+			state.append(format!(" \"{}\"", name).as_str());
+		}
+		state.append(" )");
+	}
+}
@@ -0,0 +1,122 @@
+use std::marker::PhantomData;
+use fso_tables::{FSOBuilder, FSOParser, FSOParsingError, FSOParsingHangingGobble, FSOTable};
+
+//The options that start a new entry of this kind or an unnamed part of it. An UnknownOption never consumes one of these, so that it cannot swallow them
+pub trait EntryKeys {
+	const KEYS: &'static [&'static str];
+}
+
+//An option this crate does not model, kept verbatim (including continuation lines) so that nothing is lost when parsing a mission
+pub struct UnknownOption<Entry: EntryKeys> {
+	pub name: String,
+	pub value: String,
+	entry: PhantomData<fn() -> Entry>
+}
+
+impl<Entry: EntryKeys> UnknownOption<Entry> {
+	pub fn new(name: String, value: String) -> Self {
+		UnknownOption { name, value, entry: PhantomData }
+	}
+}
+
+impl<Entry: EntryKeys> Clone for UnknownOption<Entry> {
+	fn clone(&self) -> Self {
+		UnknownOption::new(self.name.clone(), self.value.clone())
+	}
+}
+
+impl<Entry: EntryKeys> std::fmt::Debug for UnknownOption<Entry> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("UnknownOption").field("name", &self.name).field("value", &self.value).finish()
+	}
+}
+
+fn starts_new_option(line: &str) -> bool {
+	line.trim_start().starts_with(['$', '+', '#'])
+}
+
+impl<Entry: EntryKeys> FSOTable for UnknownOption<Entry> {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		let (comments, version_string) = if let Some(hanging_gobble) = hanging_gobble {
+			(hanging_gobble.comments, hanging_gobble.version_string)
+		}
+		else {
+			state.consume_whitespace(false)
+		};
+
+		let current = state.get();
+		if !current.starts_with(['$', '+']) || Entry::KEYS.iter().any(|key| current.starts_with(key)) {
+			return Err(FSOParsingError { line: state.line(), reason: "Expected an option.".to_string(), comments, version_string });
+		}
+
+		let first_line_end = current.find('\n').unwrap_or(current.len());
+		let name_end = current[..first_line_end].find(':').map_or_else(|| current[..first_line_end].find(char::is_whitespace).unwrap_or(first_line_end), |colon| colon + 1);
+
+		let mut value_end = first_line_end;
+		while current[value_end..].starts_with('\n') {
+			let next_line = &current[value_end + 1..];
+			let next_line = &next_line[..next_line.find('\n').unwrap_or(next_line.len())];
+			if next_line.trim().is_empty() || starts_new_option(next_line) {
+				break;
+			}
+			value_end += 1 + next_line.len();
+		}
+
+		let option = UnknownOption::new(current[..name_end].to_string(), current[name_end..value_end].trim().to_string());
+		state.consume(value_end);
+		Ok((option, None))
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		state.append(self.name.as_str());
+		if !self.value.is_empty() {
+			state.append(" ");
+			state.append(self.value.as_str());
+		}
+	}
+}
+
+//Section headers that Mission parses itself and that must therefore never end up in an UnparsedSection
+const MODELED_SECTIONS: &[&str] = &["#Mission Info", "#Objects", "#Wings", "#Events", "#Goals", "#Waypoints", "#Messages", "#End"];
+
+//A section of a mission this crate does not model, kept verbatim up until the next section
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnparsedSection {
+	pub header: String,
+	pub content: String
+}
+
+impl FSOTable for UnparsedSection {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		let (comments, version_string) = if let Some(hanging_gobble) = hanging_gobble {
+			(hanging_gobble.comments, hanging_gobble.version_string)
+		}
+		else {
+			state.consume_whitespace(false)
+		};
+
+		let current = state.get();
+		let header_end = current.find(['\n', ';']).unwrap_or(current.len());
+		let header = current[..header_end].trim_end();
+		if !header.starts_with('#') || MODELED_SECTIONS.iter().any(|section| section.eq_ignore_ascii_case(header)) {
+			return Err(FSOParsingError { line: state.line(), reason: format!("Expected an unknown section, got {}.", header), comments, version_string });
+		}
+
+		let header = header.to_string();
+		state.consume(header_end);
+		state.consume_whitespace_inline(&[]);
+		let current = state.get();
+		let content_end = current.match_indices('\n').map(|(newline, _)| newline).find(|newline| current[newline + 1..].trim_start_matches([' ', '\t']).starts_with('#')).unwrap_or(current.len());
+		let content = current[..content_end].trim().to_string();
+		state.consume(content_end);
+
+		Ok((UnparsedSection { header, content }, None))
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		state.append(self.header.as_str());
+		state.append("\n");
+		state.append(self.content.as_str());
+		state.append("\n");
+	}
+}
@@ -0,0 +1,140 @@
+mod pof;
+
+pub use pof::*;
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use crate::models::*;
+
+	fn chunk(id: &[u8; 4], content: Vec<u8>) -> Vec<u8> {
+		let mut out = id.to_vec();
+		out.extend_from_slice(&(content.len() as i32).to_le_bytes());
+		out.extend(content);
+		out
+	}
+
+	fn int(out: &mut Vec<u8>, value: i32) {
+		out.extend_from_slice(&value.to_le_bytes());
+	}
+
+	fn floats(out: &mut Vec<u8>, values: &[f32]) {
+		for value in values {
+			out.extend_from_slice(&value.to_le_bytes());
+		}
+	}
+
+	fn string(out: &mut Vec<u8>, value: &str) {
+		int(out, value.len() as i32 + 1);
+		out.extend_from_slice(value.as_bytes());
+		out.push(0);
+	}
+
+	//A hull with a door, a turret (base and arm), one gun bank, two missile banks, one named docking point and one fighterbay
+	pub(crate) fn test_pof() -> Vec<u8> {
+		let mut out = b"PSPO".to_vec();
+		int(&mut out, 2117);
+
+		let mut header = Vec::new();
+		floats(&mut header, &[50f32]);
+		int(&mut header, 0);
+		int(&mut header, 4);
+		floats(&mut header, &[-10f32, -10f32, -20f32, 10f32, 10f32, 20f32]);
+		int(&mut header, 1);
+		int(&mut header, 0);
+		int(&mut header, 0);
+		out.extend(chunk(b"HDR2", header));
+
+		//Deliberately out of order and with gaps in the numbers, to make sure parents and turrets are resolved by number
+		for (number, parent, offset, name, properties) in [(0, -1, [0f32; 3], "detail0", ""), (5, 2, [0f32, 1f32, 2f32], "turret01a", ""), (2, 0, [0f32, 5f32, 0f32], "turret01", "$special=subsystem"), (7, 0, [0f32; 3], "door01", "")] {
+			let mut submodel = Vec::new();
+			int(&mut submodel, number);
+			floats(&mut submodel, &[5f32]);
+			int(&mut submodel, parent);
+			floats(&mut submodel, &offset);
+			floats(&mut submodel, &[0f32; 9]);
+			string(&mut submodel, name);
+			string(&mut submodel, properties);
+			int(&mut submodel, -1);
+			int(&mut submodel, -1);
+			int(&mut submodel, 0);
+			out.extend(chunk(b"OBJ2", submodel));
+		}
+
+		for (id, banks) in [(b"GPNT", 1), (b"MPNT", 2)] {
+			let mut points = Vec::new();
+			int(&mut points, banks);
+			for _ in 0..banks {
+				int(&mut points, 1);
+				floats(&mut points, &[1f32, 0f32, 10f32, 0f32, 0f32, 1f32]);
+			}
+			out.extend(chunk(id, points));
+		}
+
+		let mut turrets = Vec::new();
+		int(&mut turrets, 1);
+		int(&mut turrets, 2);
+		int(&mut turrets, 5);
+		floats(&mut turrets, &[0f32, 1f32, 0f32]);
+		int(&mut turrets, 1);
+		floats(&mut turrets, &[0f32, 2f32, 1f32]);
+		out.extend(chunk(b"TGUN", turrets));
+
+		let mut docks = Vec::new();
+		int(&mut docks, 1);
+		string(&mut docks, "$name=Cargo Port");
+		int(&mut docks, 0);
+		int(&mut docks, 2);
+		floats(&mut docks, &[0f32; 12]);
+		out.extend(chunk(b"DOCK", docks));
+
+		let mut special = Vec::new();
+		int(&mut special, 1);
+		string(&mut special, "$engine01");
+		string(&mut special, "$special=subsystem");
+		floats(&mut special, &[0f32, 0f32, -20f32, 3f32]);
+		out.extend(chunk(b"SPCL", special));
+
+		let mut paths = Vec::new();
+		int(&mut paths, 1);
+		string(&mut paths, "$bay01");
+		string(&mut paths, "detail0");
+		int(&mut paths, 1);
+		floats(&mut paths, &[0f32, 0f32, 30f32, 2f32]);
+		int(&mut paths, 0);
+		out.extend(chunk(b"PATH", paths));
+
+		out.extend(chunk(b"TXTR", vec![0, 0, 0, 0]));
+		out
+	}
+
+	#[test]
+	fn read_pof() {
+		let model = PofModel::read(&test_pof()).unwrap();
+
+		assert_eq!(model.version, 2117);
+		assert_eq!(model.header.num_submodels, 4);
+		assert_eq!(model.header.bounding_max.z, 20f32);
+		let names = model.submodels.iter().map(|submodel| submodel.name.as_str()).collect::<Vec<&str>>();
+		assert_eq!(names, vec!["detail0", "turret01", "turret01a", "door01"]);
+		assert_eq!(model.submodel("Turret01A").unwrap().parent, Some(1));
+		assert_eq!(model.children(0).count(), 2);
+
+		assert_eq!(model.gun_banks.len(), 1);
+		assert_eq!(model.missile_banks.len(), 2);
+		let turret = model.turret("turret01").unwrap();
+		assert_eq!((turret.base, turret.arm), (1, 2));
+		assert_eq!(turret.firing_points[0].y, 2f32);
+		assert_eq!(model.subsystems(), vec!["turret01", "engine01"]);
+		assert_eq!(model.docking_point("cargo port").unwrap().points.len(), 2);
+		assert!(model.docking_point("1").is_none());
+		assert_eq!(model.fighterbays().len(), 1);
+
+		assert!(PofModel::read(&test_pof()[..200]).is_err());
+
+		//A turret on a submodel number no submodel has
+		let mut unknown_turret = test_pof();
+		let base = unknown_turret.windows(4).position(|id| id == b"TGUN").unwrap() + 12;
+		unknown_turret[base..base + 4].copy_from_slice(&9i32.to_le_bytes());
+		assert!(PofModel::read(&unknown_turret).is_err());
+	}
+}
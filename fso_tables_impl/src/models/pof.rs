@@ -0,0 +1,338 @@
+use std::fs;
+use std::path::Path;
+use fso_tables::FSOParsingError;
+use crate::Vec3D;
+
+#[derive(Clone)]
+pub struct PofHeader {
+	pub max_radius: f32,
+	pub flags: i32,
+	pub num_submodels: i32,
+	pub bounding_min: Vec3D,
+	pub bounding_max: Vec3D,
+	//Submodel numbers of the detail levels and debris pieces
+	pub detail_levels: Vec<i32>,
+	pub debris: Vec<i32>
+}
+
+#[derive(Clone)]
+pub struct PofSubmodel {
+	pub number: i32,
+	pub name: String,
+	//Free text set in the modeling tool, i.e. "$special=subsystem"
+	pub properties: String,
+	//Index into PofModel::submodels, None for top-level submodels such as detail levels
+	pub parent: Option<usize>,
+	pub offset: Vec3D,
+	pub radius: f32,
+	pub movement_type: i32,
+	pub movement_axis: i32
+}
+
+#[derive(Clone)]
+pub struct PofSpecialPoint {
+	pub name: String,
+	pub properties: String,
+	pub point: Vec3D,
+	pub radius: f32
+}
+
+#[derive(Clone)]
+pub struct PofDockingPoint {
+	pub properties: String,
+	pub paths: Vec<i32>,
+	//Position and normal of each point
+	pub points: Vec<(Vec3D, Vec3D)>
+}
+
+impl PofDockingPoint {
+	//Set through "$name=" in the properties, otherwise the engine refers to it by number only
+	pub fn name(&self) -> Option<&str> {
+		self.properties.lines().find_map(|line| line.trim().strip_prefix("$name=")).map(str::trim)
+	}
+}
+
+#[derive(Clone)]
+pub struct PofWeaponSlot {
+	pub point: Vec3D,
+	pub normal: Vec3D,
+	//Only present from POF version 22.01 onwards
+	pub angle_offset: f32
+}
+
+#[derive(Clone)]
+pub struct PofWeaponBank {
+	pub slots: Vec<PofWeaponSlot>
+}
+
+#[derive(Clone)]
+pub struct PofTurret {
+	//Indices into PofModel::submodels. The base rotates around the turret normal, the arm carries the barrels
+	pub base: usize,
+	pub arm: usize,
+	pub normal: Vec3D,
+	pub firing_points: Vec<Vec3D>
+}
+
+#[derive(Clone)]
+pub struct PofPath {
+	pub name: String,
+	pub parent: String,
+	pub vertices: Vec<(Vec3D, f32)>
+}
+
+//A binary .pof model. Only the chunks relevant for tables are read, geometry and textures are skipped
+#[derive(Clone)]
+pub struct PofModel {
+	pub version: i32,
+	pub header: PofHeader,
+	pub submodels: Vec<PofSubmodel>,
+	pub special_points: Vec<PofSpecialPoint>,
+	pub docking_points: Vec<PofDockingPoint>,
+	pub gun_banks: Vec<PofWeaponBank>,
+	pub missile_banks: Vec<PofWeaponBank>,
+	pub gun_turrets: Vec<PofTurret>,
+	pub missile_turrets: Vec<PofTurret>,
+	pub paths: Vec<PofPath>
+}
+
+struct PofReader<'a> {
+	data: &'a [u8],
+	position: usize
+}
+
+impl<'a> PofReader<'a> {
+	fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+		if self.data.len() - self.position < count {
+			return Err("Unexpected end of data".to_string());
+		}
+		let bytes = &self.data[self.position..self.position + count];
+		self.position += count;
+		Ok(bytes)
+	}
+
+	fn i32(&mut self) -> Result<i32, String> {
+		let bytes = self.bytes(4)?;
+		Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	fn f32(&mut self) -> Result<f32, String> {
+		let bytes = self.bytes(4)?;
+		Ok(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	fn count(&mut self) -> Result<usize, String> {
+		self.count_of_size(4)
+	}
+
+	//Counts larger than what the remaining data could hold are corrupt, and would only make us allocate
+	fn count_of_size(&mut self, element_size: usize) -> Result<usize, String> {
+		let count = self.i32()?;
+		if count < 0 || count as usize > (self.data.len() - self.position) / element_size {
+			return Err(format!("Invalid count {}", count));
+		}
+		Ok(count as usize)
+	}
+
+	fn vec3d(&mut self) -> Result<Vec3D, String> {
+		Ok(Vec3D::new(self.f32()?, self.f32()?, self.f32()?))
+	}
+
+	fn string(&mut self) -> Result<String, String> {
+		let length = self.count_of_size(1)?;
+		let bytes = self.bytes(length)?;
+		let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+		Ok(String::from_utf8_lossy(&bytes[..end]).to_string())
+	}
+
+	//Reads a submodel number and finds the index of the submodel with it
+	fn submodel_index(&mut self, numbers: &[i32]) -> Result<usize, String> {
+		let number = self.i32()?;
+		numbers.iter().position(|known| *known == number).ok_or_else(|| format!("Invalid submodel number {}", number))
+	}
+}
+
+impl PofModel {
+	pub fn open(path: &Path) -> Result<Self, FSOParsingError> {
+		let data = fs::read(path).map_err(|err| FSOParsingError { line: 0, reason: format!("Could not read POF {}! Reason: {}.", path.to_string_lossy(), err), comments: None, version_string: None })?;
+		Self::read(&data)
+	}
+
+	pub fn read(data: &[u8]) -> Result<Self, FSOParsingError> {
+		Self::read_chunks(data).map_err(|reason| FSOParsingError { line: 0, reason: format!("Could not read POF! Reason: {}.", reason), comments: None, version_string: None })
+	}
+
+	fn read_chunks(data: &[u8]) -> Result<Self, String> {
+		let mut reader = PofReader { data, position: 0 };
+		if reader.bytes(4)? != b"PSPO" {
+			return Err("Missing PSPO signature".to_string());
+		}
+		let version = reader.i32()?;
+
+		let mut header = None;
+		//Submodels, with their parents still as submodel numbers, which may refer to submodels that come later in the file
+		let mut submodels: Vec<(PofSubmodel, i32)> = Vec::new();
+		let mut model = PofModel { version, header: PofHeader { max_radius: 0f32, flags: 0, num_submodels: 0, bounding_min: Vec3D::new(0f32, 0f32, 0f32), bounding_max: Vec3D::new(0f32, 0f32, 0f32), detail_levels: Vec::new(), debris: Vec::new() }, submodels: Vec::new(), special_points: Vec::new(), docking_points: Vec::new(), gun_banks: Vec::new(), missile_banks: Vec::new(), gun_turrets: Vec::new(), missile_turrets: Vec::new(), paths: Vec::new() };
+		//Turrets refer to submodels by number, so they can only be resolved once all submodels are known
+		let mut turret_chunks: Vec<(bool, &[u8])> = Vec::new();
+
+		while reader.position < data.len() {
+			let id = reader.bytes(4)?;
+			let length = reader.count_of_size(1)?;
+			let chunk = reader.bytes(length)?;
+			let mut chunk_reader = PofReader { data: chunk, position: 0 };
+			let chunk_reader = &mut chunk_reader;
+
+			match id {
+				b"HDR2" => {
+					header = Some(PofHeader {
+						max_radius: chunk_reader.f32()?,
+						flags: chunk_reader.i32()?,
+						num_submodels: chunk_reader.i32()?,
+						bounding_min: chunk_reader.vec3d()?,
+						bounding_max: chunk_reader.vec3d()?,
+						detail_levels: (0..chunk_reader.count()?).map(|_| chunk_reader.i32()).collect::<Result<_, _>>()?,
+						debris: (0..chunk_reader.count()?).map(|_| chunk_reader.i32()).collect::<Result<_, _>>()?
+					});
+				}
+				b"OBJ2" => {
+					let number = chunk_reader.i32()?;
+					//The radius moved in front of the parent in version 21.16
+					let mut radius = if version >= 2116 { chunk_reader.f32()? } else { 0f32 };
+					let parent = chunk_reader.i32()?;
+					let offset = chunk_reader.vec3d()?;
+					if version < 2116 {
+						radius = chunk_reader.f32()?;
+					}
+					//Geometric center and bounding box
+					chunk_reader.bytes(36)?;
+					let name = chunk_reader.string()?;
+					let properties = chunk_reader.string()?;
+					let movement_type = chunk_reader.i32()?;
+					let movement_axis = chunk_reader.i32()?;
+					submodels.push((PofSubmodel { number, name, properties, parent: None, offset, radius, movement_type, movement_axis }, parent));
+				}
+				b"SPCL" => {
+					for _ in 0..chunk_reader.count()? {
+						model.special_points.push(PofSpecialPoint { name: chunk_reader.string()?, properties: chunk_reader.string()?, point: chunk_reader.vec3d()?, radius: chunk_reader.f32()? });
+					}
+				}
+				b"GPNT" | b"MPNT" => {
+					let mut banks = Vec::new();
+					for _ in 0..chunk_reader.count()? {
+						let mut slots = Vec::new();
+						for _ in 0..chunk_reader.count()? {
+							let point = chunk_reader.vec3d()?;
+							let normal = chunk_reader.vec3d()?;
+							let angle_offset = if version >= 2201 { chunk_reader.f32()? } else { 0f32 };
+							slots.push(PofWeaponSlot { point, normal, angle_offset });
+						}
+						banks.push(PofWeaponBank { slots });
+					}
+					if id == b"GPNT" {
+						model.gun_banks = banks;
+					}
+					else {
+						model.missile_banks = banks;
+					}
+				}
+				b"TGUN" | b"TMIS" => { turret_chunks.push((id == b"TGUN", chunk)); }
+				b"DOCK" => {
+					for _ in 0..chunk_reader.count()? {
+						let properties = chunk_reader.string()?;
+						let paths = (0..chunk_reader.count()?).map(|_| chunk_reader.i32()).collect::<Result<_, _>>()?;
+						let points = (0..chunk_reader.count()?).map(|_| Ok((chunk_reader.vec3d()?, chunk_reader.vec3d()?))).collect::<Result<_, String>>()?;
+						model.docking_points.push(PofDockingPoint { properties, paths, points });
+					}
+				}
+				b"PATH" => {
+					for _ in 0..chunk_reader.count()? {
+						let name = chunk_reader.string()?;
+						let parent = chunk_reader.string()?;
+						let mut vertices = Vec::new();
+						for _ in 0..chunk_reader.count()? {
+							let position = chunk_reader.vec3d()?;
+							let radius = chunk_reader.f32()?;
+							//Turrets that can see this vertex
+							let turrets = chunk_reader.count()?;
+							chunk_reader.bytes(turrets * 4)?;
+							vertices.push((position, radius));
+						}
+						model.paths.push(PofPath { name, parent, vertices });
+					}
+				}
+				_ => { }
+			}
+		}
+
+		model.header = header.ok_or_else(|| "Missing HDR2 chunk".to_string())?;
+
+		//Submodels are referred to by their number, which usually, but not necessarily, matches the order in the file
+		submodels.sort_by_key(|(submodel, _)| submodel.number);
+		let numbers: Vec<i32> = submodels.iter().map(|(submodel, _)| submodel.number).collect();
+		for (mut submodel, parent) in submodels {
+			submodel.parent = numbers.iter().position(|number| *number == parent);
+			model.submodels.push(submodel);
+		}
+
+		for (is_gun, chunk) in turret_chunks {
+			let mut chunk_reader = PofReader { data: chunk, position: 0 };
+			let mut turrets = Vec::new();
+			for _ in 0..chunk_reader.count()? {
+				let base = chunk_reader.submodel_index(&numbers)?;
+				let arm = chunk_reader.submodel_index(&numbers)?;
+				let normal = chunk_reader.vec3d()?;
+				let firing_points = (0..chunk_reader.count()?).map(|_| chunk_reader.vec3d()).collect::<Result<_, _>>()?;
+				turrets.push(PofTurret { base, arm, normal, firing_points });
+			}
+			if is_gun {
+				model.gun_turrets = turrets;
+			}
+			else {
+				model.missile_turrets = turrets;
+			}
+		}
+
+		Ok(model)
+	}
+
+	//Lookups by name are case-insensitive, as they are in the engine
+	pub fn submodel(&self, name: &str) -> Option<&PofSubmodel> {
+		self.submodels.iter().find(|submodel| submodel.name.eq_ignore_ascii_case(name))
+	}
+
+	pub fn children(&self, submodel: usize) -> impl Iterator<Item = &PofSubmodel> {
+		self.submodels.iter().filter(move |child| child.parent == Some(submodel))
+	}
+
+	pub fn turrets(&self) -> impl Iterator<Item = &PofTurret> {
+		self.gun_turrets.iter().chain(self.missile_turrets.iter())
+	}
+
+	//Turrets are named after their base submodel
+	pub fn turret(&self, name: &str) -> Option<&PofTurret> {
+		self.turrets().find(|turret| self.submodels[turret.base].name.eq_ignore_ascii_case(name))
+	}
+
+	//Names of everything ships.tbl can attach a subsystem to: turrets, submodels marked as subsystems, and special points
+	pub fn subsystems(&self) -> Vec<&str> {
+		let mut subsystems: Vec<&str> = self.turrets().map(|turret| self.submodels[turret.base].name.as_str()).collect();
+		subsystems.extend(self.submodels.iter().filter(|submodel| submodel.properties.contains("$special=subsystem")).map(|submodel| submodel.name.as_str()));
+		subsystems.extend(self.special_points.iter().map(|point| point.name.trim_start_matches('$')));
+		subsystems.dedup();
+		subsystems
+	}
+
+	//Docking points can be referred to by name or by their index
+	pub fn docking_point(&self, name_or_number: &str) -> Option<&PofDockingPoint> {
+		match name_or_number.trim().parse::<usize>() {
+			Ok(number) => { self.docking_points.get(number) }
+			Err(_) => { self.docking_points.iter().find(|dock| dock.name().is_some_and(|name| name.eq_ignore_ascii_case(name_or_number.trim()))) }
+		}
+	}
+
+	//Fighterbays are the paths named $bay01, $bay02, ...
+	pub fn fighterbays(&self) -> Vec<&PofPath> {
+		self.paths.iter().filter(|path| path.name.to_lowercase().starts_with("$bay")).collect()
+	}
+}
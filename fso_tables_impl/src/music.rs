@@ -0,0 +1,65 @@
+mod table;
+mod assets;
+
+pub use table::*;
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use fso_tables::FSOTableFileParser;
+	use crate::assets::*;
+	use crate::music::*;
+
+	const MUSIC: &str = "#Soundtrack Start
+$SoundTrack Name: 1: Genesis
+$Name: 1_NRMLa.wav, 9.6
+$Name: 1_NRMLb.wav
+$Name: none.wav
+
+$SoundTrack Name: 2: Ancient Evil
+$Name: 2_NRMLa.ogg
+#Soundtrack End
+
+#Menu Music Start
+$Name: Brief1
+$Filename: Brief1.wav
+$Name: Debrief
+$Filename: Debrief_Success.wav
+#Menu Music End
+";
+
+	#[test]
+	fn parse() {
+		let table = MusicTable::parse(FSOTableFileParser::from_string(MUSIC)).unwrap();
+		let soundtracks = &table.soundtracks.as_ref().unwrap().soundtracks;
+		assert_eq!(soundtracks.len(), 2);
+		assert_eq!(soundtracks[0].name, "1: Genesis");
+		assert_eq!(soundtracks[0].patterns.len(), 3);
+		assert_eq!(soundtracks[0].patterns[0].num_measures, Some(9.6.into()));
+		assert_eq!(soundtracks[0].patterns[1].num_measures, None);
+		assert_eq!(soundtracks[1].patterns[0].filename.as_str(), "2_NRMLa.ogg");
+
+		let menu_music = &table.menu_music.as_ref().unwrap().music;
+		assert_eq!(menu_music.len(), 2);
+		assert_eq!(menu_music[1].filename.as_str(), "Debrief_Success.wav");
+	}
+
+	#[test]
+	fn missing_assets() {
+		let table = MusicTable::parse(FSOTableFileParser::from_string(MUSIC)).unwrap();
+
+		let dir = crate::assets::tests::scratch_dir("missing_music");
+		fs::create_dir_all(dir.join("data").join("music")).unwrap();
+		for file in ["1_nrmla.ogg", "1_NRMLb.wav", "Brief1.ogg", "Debrief_Success.wav"] {
+			fs::write(dir.join("data").join("music").join(file), b"OggS").unwrap();
+		}
+
+		let mut index = AssetIndex::default();
+		index.add_mod_directory(&dir).unwrap();
+
+		let missing = index.find_missing(&table);
+		assert_eq!(missing.len(), 1);
+		assert_eq!(missing[0].filename, "2_NRMLa.ogg");
+		assert_eq!(missing[0].referenced_by, "#Soundtrack: 2: Ancient Evil");
+	}
+}
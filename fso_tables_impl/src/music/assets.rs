@@ -0,0 +1,25 @@
+use crate::assets::{AssetKind, AssetReference, ReferencesAssets};
+use crate::music::MusicTable;
+
+impl ReferencesAssets for MusicTable {
+	fn referenced_assets(&self) -> Vec<AssetReference> {
+		let mut references = Vec::new();
+		if let Some(soundtracks) = &self.soundtracks {
+			for soundtrack in &soundtracks.soundtracks {
+				references.extend(soundtrack.patterns.iter().filter(|pattern| !pattern.filename.is_none()).map(|pattern| AssetReference {
+					filename: pattern.filename.0.clone(),
+					kind: AssetKind::Music,
+					referenced_by: format!("#Soundtrack: {}", soundtrack.name)
+				}));
+			}
+		}
+		if let Some(menu_music) = &self.menu_music {
+			references.extend(menu_music.music.iter().filter(|music| !music.filename.is_none()).map(|music| AssetReference {
+				filename: music.filename.0.clone(),
+				kind: AssetKind::Music,
+				referenced_by: format!("#Menu Music: {}", music.name)
+			}));
+		}
+		references
+	}
+}
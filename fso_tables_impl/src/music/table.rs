@@ -0,0 +1,45 @@
+use fso_tables::{fso_table, FSOFloat};
+use crate::assets::Filename;
+
+#[fso_table(toplevel)]
+pub struct MusicTable {
+	#[unnamed]
+	pub soundtracks: Option<SoundtrackSubtable>,
+	#[unnamed]
+	pub menu_music: Option<MenuMusicSubtable>
+}
+
+#[fso_table(table_start="#Soundtrack Start", table_end="#Soundtrack End")]
+pub struct SoundtrackSubtable {
+	#[unnamed]
+	pub soundtracks: Vec<Soundtrack>
+}
+
+#[fso_table]
+pub struct Soundtrack {
+	#[fso_name="$SoundTrack Name:"]
+	pub name: String,
+	#[unnamed]
+	pub patterns: Vec<MusicPattern>
+}
+
+//Patterns are matched to their role (normal, arrival, battle, ...) by their order in the soundtrack
+#[fso_table]
+pub struct MusicPattern {
+	#[fso_name="$Name:"]
+	pub filename: Filename,
+	#[unnamed]
+	pub num_measures: Option<FSOFloat>
+}
+
+#[fso_table(table_start="#Menu Music Start", table_end="#Menu Music End")]
+pub struct MenuMusicSubtable {
+	#[unnamed]
+	pub music: Vec<MenuMusic>
+}
+
+#[fso_table]
+pub struct MenuMusic {
+	pub name: String,
+	pub filename: Filename
+}
@@ -0,0 +1,102 @@
+mod table;
+mod legacy;
+mod assets;
+
+pub use table::*;
+pub use legacy::*;
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use fso_tables::{FSOFormatting, FSOLineEnding, FSOTableFileParser};
+	use crate::assets::*;
+	use crate::sounds::*;
+
+	const SOUNDS: &str = "#Game Sounds Start
+$Name: 0 snd_missile_tracking.wav, 0, 0.40, 0
+$Name: 1 snd_missile_lock.wav, 1, 0.40, 1, 100, 400
+$Name: SND_ENGINE
+	+Filename: engine_loop.wav
+	+Preload: YES
+	+Volume: 0.75
+	+3D Sound:
+		+Attenuation Start: 200
+		+Attenuation End: 800
+	+Priority: SINGLE_INSTANCE
+$Name: SND_RANDOM_BEEP
+	+Entry: beep_1.wav
+	+Entry: beep_2.wav
+	+Cycle type: random
+	+Volume: 0.5
+#Game Sounds End
+
+#Interface Sounds Start
+$Name: 0 user_c.wav, 0, 0.70, 0
+#Interface Sounds End
+
+#Flyby Sounds Start
+$Terran: 0 T_flyby1.wav, 0, 0.90, 1, 500, 1000
+#Flyby Sounds End
+";
+
+	#[test]
+	fn parse() {
+		let table = SoundTable::parse(FSOTableFileParser::from_string(SOUNDS)).unwrap();
+		let game_sounds = &table.game_sounds.as_ref().unwrap().sounds;
+		assert_eq!(game_sounds.len(), 4);
+
+		assert_eq!(game_sounds[0].name, SoundId::Index(0));
+		let legacy = game_sounds[1].legacy.as_ref().unwrap();
+		assert_eq!(legacy.filename.as_str(), "snd_missile_lock.wav");
+		assert_eq!(legacy.attenuation, Some((100, 400)));
+
+		assert_eq!(game_sounds[2].name, SoundId::Name("SND_ENGINE".to_string()));
+		assert_eq!(game_sounds[2].preload, Some(true));
+		assert_eq!(game_sounds[2].three_d.as_ref().map(|d| (d.attenuation_start, d.attenuation_end)), Some((200, 800)));
+		assert!(matches!(game_sounds[2].priority, Some(SoundPriority::SingleInstance)));
+
+		assert_eq!(game_sounds[3].entries.len(), 2);
+		assert!(matches!(game_sounds[3].cycle_type, Some(SoundCycleType::Random)));
+
+		assert_eq!(table.interface_sounds.as_ref().unwrap().sounds.len(), 1);
+		let flyby = &table.flyby_sounds.as_ref().unwrap().sounds[0];
+		assert_eq!(flyby.species, "Terran");
+		assert_eq!(flyby.definition.filename.as_str(), "T_flyby1.wav");
+	}
+
+	#[test]
+	fn missing_assets() {
+		let table = SoundTable::parse(FSOTableFileParser::from_string(SOUNDS)).unwrap();
+
+		let dir = crate::assets::tests::scratch_dir("missing_sounds");
+		fs::create_dir_all(dir.join("data").join("sounds")).unwrap();
+		for file in ["snd_missile_tracking.wav", "SND_MISSILE_LOCK.ogg", "engine_loop.wav", "beep_1.wav", "user_c.wav"] {
+			fs::write(dir.join("data").join("sounds").join(file), b"RIFF").unwrap();
+		}
+		crate::assets::tests::write_vp(&dir.join("flyby.vp"), &[("t_flyby1.wav", b"RIFF")]);
+
+		let mut index = AssetIndex::default();
+		index.add_mod_directory(&dir).unwrap();
+
+		let missing = index.find_missing(&table);
+		assert_eq!(missing.len(), 1);
+		assert_eq!(missing[0].filename, "beep_2.wav");
+		assert_eq!(missing[0].referenced_by, "#Game Sounds: SND_RANDOM_BEEP");
+	}
+
+	#[test]
+	fn spew_formatted() {
+		let table = SoundTable::parse(FSOTableFileParser::from_string(SOUNDS)).unwrap();
+		let formatting = FSOFormatting { indentation: "\t".to_string(), line_ending: FSOLineEnding::CrLf, float_precision: Some(2), trim_trailing_whitespace: true, ..FSOFormatting::default() };
+		let spewed = table.spew_formatted(formatting);
+
+		assert!(spewed.contains("$Name: SND_ENGINE\r\n\t+Filename: engine_loop.wav\r\n"));
+		assert!(spewed.contains("\t+3D Sound:\r\n\t\t+Attenuation Start: 200\r\n\t\t+Attenuation End: 800\r\n\t+Priority: SINGLE_INSTANCE"));
+		assert!(spewed.contains("\t+Volume: 0.50\r\n"));
+		assert!(!spewed.replace("\r\n", "").contains('\n'));
+		assert!(spewed.lines().all(|line| line == line.trim_end()));
+
+		let reparsed = SoundTable::parse(FSOTableFileParser::from_string(spewed)).unwrap();
+		assert_eq!(reparsed.game_sounds.unwrap().sounds.len(), 4);
+	}
+}
@@ -0,0 +1,38 @@
+use crate::assets::{AssetKind, AssetReference, Filename, ReferencesAssets};
+use crate::sounds::{Sound, SoundTable};
+
+fn reference(filename: &Filename, referenced_by: String) -> Option<AssetReference> {
+	if filename.is_none() {
+		None
+	}
+	else {
+		Some(AssetReference { filename: filename.0.clone(), kind: AssetKind::Sound, referenced_by })
+	}
+}
+
+fn sound_references(section: &str, sounds: &[Sound]) -> Vec<AssetReference> {
+	sounds.iter().flat_map(|sound| {
+		let referenced_by = format!("{}: {}", section, sound.name);
+		sound.legacy.iter().map(|legacy| &legacy.filename)
+			.chain(sound.filename.iter())
+			.chain(sound.entries.iter().map(|entry| &entry.filename))
+			.filter_map(|filename| reference(filename, referenced_by.clone()))
+			.collect::<Vec<_>>()
+	}).collect()
+}
+
+impl ReferencesAssets for SoundTable {
+	fn referenced_assets(&self) -> Vec<AssetReference> {
+		let mut references = Vec::new();
+		if let Some(game_sounds) = &self.game_sounds {
+			references.extend(sound_references("#Game Sounds", &game_sounds.sounds));
+		}
+		if let Some(interface_sounds) = &self.interface_sounds {
+			references.extend(sound_references("#Interface Sounds", &interface_sounds.sounds));
+		}
+		if let Some(flyby_sounds) = &self.flyby_sounds {
+			references.extend(flyby_sounds.sounds.iter().filter_map(|flyby| reference(&flyby.definition.filename, format!("#Flyby Sounds: {} {}", flyby.species, flyby.index))));
+		}
+		references
+	}
+}
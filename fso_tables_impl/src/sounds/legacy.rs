@@ -0,0 +1,117 @@
+use fso_tables::{FSOBuilder, FSOFloat, FSOParser, FSOParsingError, FSOParsingHangingGobble, FSOTable};
+use crate::assets::Filename;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SoundId {
+	Index(u32),
+	Name(String)
+}
+
+impl FSOTable for SoundId {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, _hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		state.consume_whitespace_inline(&[]);
+		let current = state.get();
+		let digits = current.chars().take_while(|c| c.is_ascii_digit()).count();
+
+		//A retail style index is followed by the rest of the definition on the same line, a name takes up the entire line
+		if digits > 0 && current[digits..].chars().next().is_none_or(|c| c.is_whitespace()) {
+			let index = current[..digits].parse::<u32>().map_err(|_| FSOParsingError { line: state.line(), reason: format!("Invalid sound index {}.", &current[..digits]), comments: None, version_string: None })?;
+			state.consume(digits);
+			Ok((SoundId::Index(index), None))
+		}
+		else {
+			let name = state.read_until_last_whitespace_of_line_or_stop(&[]);
+			if name.is_empty() {
+				return Err(FSOParsingError { line: state.line(), reason: "Expected sound name or index.".to_string(), comments: None, version_string: None });
+			}
+			Ok((SoundId::Name(name.to_string()), None))
+		}
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		match self {
+			SoundId::Index(index) => { state.append(index.to_string().as_str()); }
+			SoundId::Name(name) => { state.append(name.as_str()); }
+		}
+	}
+}
+
+impl std::fmt::Display for SoundId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SoundId::Index(index) => { write!(f, "{}", index) }
+			SoundId::Name(name) => { write!(f, "{}", name) }
+		}
+	}
+}
+
+//The retail single line format: <filename>, <preload>, <volume>, <3d>[, <min>, <max>]
+//The commas after the file name are mandatory for the engine, so this cannot be an inline fso_table
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegacySoundDefinition {
+	pub filename: Filename,
+	pub preload: bool,
+	pub volume: FSOFloat,
+	//0 for a 2D sound, 1 for a 3D sound
+	pub sound_type: i32,
+	pub attenuation: Option<(i32, i32)>
+}
+
+impl FSOTable for LegacySoundDefinition {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, _hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		let (filename, _) = Filename::parse(state, None)?;
+		let (preload, _) = i32::parse(state, None)?;
+		let (volume, _) = FSOFloat::parse(state, None)?;
+		let (sound_type, _) = i32::parse(state, None)?;
+		let attenuation = match i32::parse(state, None) {
+			Ok((min, _)) => { Some((min, i32::parse(state, None)?.0)) }
+			Err(_) => { None }
+		};
+
+		Ok((LegacySoundDefinition { filename, preload: preload != 0, volume, sound_type, attenuation }, None))
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		self.filename.spew(state);
+		state.append(format!(", {}, {}, {}", if self.preload { 1 } else { 0 }, self.volume, self.sound_type).as_str());
+		if let Some((min, max)) = self.attenuation {
+			state.append(format!(", {}, {}", min, max).as_str());
+		}
+	}
+}
+
+//$<Species>: <index> <legacy definition>
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlybySound {
+	pub species: String,
+	pub index: u32,
+	pub definition: LegacySoundDefinition
+}
+
+impl FSOTable for FlybySound {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		let (comments, version_string) = if let Some(hanging_gobble) = hanging_gobble {
+			(hanging_gobble.comments, hanging_gobble.version_string)
+		}
+		else {
+			state.consume_whitespace(false)
+		};
+
+		let current = state.get();
+		let species_end = current.find(':').filter(|end| current.starts_with('$') && !current[..*end].contains('\n'));
+		let Some(species_end) = species_end else {
+			return Err(FSOParsingError { line: state.line(), reason: "Expected $<Species>:.".to_string(), comments, version_string });
+		};
+		let species = current[1..species_end].to_string();
+		state.consume(species_end + 1);
+
+		let (index, _) = u32::parse(state, None)?;
+		let (definition, _) = LegacySoundDefinition::parse(state, None)?;
+		Ok((FlybySound { species, index, definition }, None))
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		state.append(format!("${}: {} ", self.species, self.index).as_str());
+		self.definition.spew(state);
+	}
+}
@@ -0,0 +1,84 @@
+use fso_tables::{fso_table, FSOFloat};
+use crate::assets::Filename;
+use crate::sounds::{FlybySound, LegacySoundDefinition, SoundId};
+
+#[fso_table(toplevel)]
+pub struct SoundTable {
+	#[unnamed]
+	pub game_sounds: Option<GameSoundsSubtable>,
+	#[unnamed]
+	pub interface_sounds: Option<InterfaceSoundsSubtable>,
+	#[unnamed]
+	pub flyby_sounds: Option<FlybySoundsSubtable>
+}
+
+#[fso_table(table_start="#Game Sounds Start", table_end="#Game Sounds End")]
+pub struct GameSoundsSubtable {
+	#[unnamed]
+	pub sounds: Vec<Sound>
+}
+
+#[fso_table(table_start="#Interface Sounds Start", table_end="#Interface Sounds End")]
+pub struct InterfaceSoundsSubtable {
+	#[unnamed]
+	pub sounds: Vec<Sound>
+}
+
+#[fso_table(table_start="#Flyby Sounds Start", table_end="#Flyby Sounds End")]
+pub struct FlybySoundsSubtable {
+	#[unnamed]
+	pub sounds: Vec<FlybySound>
+}
+
+//Supports both the retail one-line format ($Name: <index> <filename>, <preload>, <volume>, <3d>[, <min>, <max>]) and the newer multi-line format
+#[fso_table(prefix="+")]
+pub struct Sound {
+	#[fso_name="$Name:"]
+	pub name: SoundId,
+	#[unnamed]
+	pub legacy: Option<LegacySoundDefinition>,
+	pub filename: Option<Filename>,
+	#[unnamed]
+	pub entries: Vec<SoundEntry>,
+	#[fso_name="+Cycle type:"]
+	pub cycle_type: Option<SoundCycleType>,
+	pub preload: Option<bool>,
+	pub volume: Option<FSOFloat>,
+	pub pitch: Option<FSOFloat>,
+	#[fso_name="+3D Sound:"]
+	pub three_d: Option<Sound3D>,
+	pub priority: Option<SoundPriority>,
+	pub limit: Option<u32>
+}
+
+#[fso_table(prefix="+")]
+pub struct SoundEntry {
+	#[fso_name="+Entry:"]
+	pub filename: Filename
+}
+
+#[fso_table]
+pub enum SoundCycleType {
+	#[fso_name="random"]
+	Random,
+	#[fso_name="sequential"]
+	Sequential
+}
+
+#[fso_table(prefix="+")]
+pub struct Sound3D {
+	pub attenuation_start: i32,
+	pub attenuation_end: i32
+}
+
+#[fso_table]
+pub enum SoundPriority {
+	#[fso_name="MUST_PLAY"]
+	MustPlay,
+	#[fso_name="SINGLE_INSTANCE"]
+	SingleInstance,
+	#[fso_name="DOUBLE_INSTANCE"]
+	DoubleInstance,
+	#[fso_name="TRIPLE_INSTANCE"]
+	TripleInstance
+}
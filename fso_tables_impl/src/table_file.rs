@@ -0,0 +1,135 @@
+use std::io::Write;
+use std::path::Path;
+use fso_tables::{fso_merge_resolving, FSOConflict, FSOFormatting, FSOPathed, FSOResolution};
+use crate::animations::AnimationTable;
+use crate::campaigns::Campaign;
+use crate::curves::CurveTable;
+use crate::missions::Mission;
+use crate::music::MusicTable;
+use crate::sounds::SoundTable;
+use crate::{FSOParsingError, FSOTableFileParser};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TableKind {
+	Animations,
+	Curves,
+	Sounds,
+	Music,
+	Mission,
+	Campaign
+}
+
+impl TableKind {
+	//Tells the kind from the file name, i.e. animation.tbl, a modular table like ships-anim.tbm, or a .fs2 mission
+	pub fn from_filename(path: &Path) -> Option<Self> {
+		let name = path.file_name()?.to_str()?.to_lowercase();
+		let is_table = |table: &str, modular: &str| name == table || name.ends_with(modular);
+		if is_table("animation.tbl", "-anim.tbm") { Some(TableKind::Animations) }
+		else if is_table("curves.tbl", "-crv.tbm") { Some(TableKind::Curves) }
+		else if is_table("sounds.tbl", "-snd.tbm") { Some(TableKind::Sounds) }
+		else if is_table("music.tbl", "-mus.tbm") { Some(TableKind::Music) }
+		else if name.ends_with(".fs2") { Some(TableKind::Mission) }
+		else if name.ends_with(".fc2") { Some(TableKind::Campaign) }
+		else { None }
+	}
+}
+
+//A parsed table file of any kind this crate knows, kept for round trips. Boxed, as missions are far larger than the other tables
+pub enum TableFile {
+	Animations(Box<AnimationTable>),
+	Curves(Box<CurveTable>),
+	Sounds(Box<SoundTable>),
+	Music(Box<MusicTable>),
+	Mission(Box<Mission>),
+	Campaign(Box<Campaign>)
+}
+
+impl TableFile {
+	pub fn parse_round_trip(kind: TableKind, text: &str) -> Result<Self, FSOParsingError> {
+		let parser = FSOTableFileParser::from_string(text).with_round_trip();
+		Ok(match kind {
+			TableKind::Animations => { TableFile::Animations(Box::new(AnimationTable::parse(parser)?)) }
+			TableKind::Curves => { TableFile::Curves(Box::new(CurveTable::parse(parser)?)) }
+			TableKind::Sounds => { TableFile::Sounds(Box::new(SoundTable::parse(parser)?)) }
+			TableKind::Music => { TableFile::Music(Box::new(MusicTable::parse(parser)?)) }
+			TableKind::Mission => { TableFile::Mission(Box::new(Mission::parse(parser)?)) }
+			TableKind::Campaign => { TableFile::Campaign(Box::new(Campaign::parse(parser)?)) }
+		})
+	}
+
+	pub fn spew_round_trip(&self) -> String {
+		match self {
+			TableFile::Animations(table) => { table.spew_round_trip() }
+			TableFile::Curves(table) => { table.spew_round_trip() }
+			TableFile::Sounds(table) => { table.spew_round_trip() }
+			TableFile::Music(table) => { table.spew_round_trip() }
+			TableFile::Mission(table) => { table.spew_round_trip() }
+			TableFile::Campaign(table) => { table.spew_round_trip() }
+		}
+	}
+
+	pub fn spew_formatted(&self, formatting: FSOFormatting) -> String {
+		match self {
+			TableFile::Animations(table) => { table.spew_formatted(formatting) }
+			TableFile::Curves(table) => { table.spew_formatted(formatting) }
+			TableFile::Sounds(table) => { table.spew_formatted(formatting) }
+			TableFile::Music(table) => { table.spew_formatted(formatting) }
+			TableFile::Mission(table) => { table.spew_formatted(formatting) }
+			TableFile::Campaign(table) => { table.spew_formatted(formatting) }
+		}
+	}
+
+	pub fn spew_into<W: Write>(&self, writer: W, formatting: FSOFormatting) -> std::io::Result<W> {
+		match self {
+			TableFile::Animations(table) => { table.spew_into(writer, formatting) }
+			TableFile::Curves(table) => { table.spew_into(writer, formatting) }
+			TableFile::Sounds(table) => { table.spew_into(writer, formatting) }
+			TableFile::Music(table) => { table.spew_into(writer, formatting) }
+			TableFile::Mission(table) => { table.spew_into(writer, formatting) }
+			TableFile::Campaign(table) => { table.spew_into(writer, formatting) }
+		}
+	}
+
+	pub fn pathed(&self) -> &dyn FSOPathed {
+		match self {
+			TableFile::Animations(table) => { table.as_ref() }
+			TableFile::Curves(table) => { table.as_ref() }
+			TableFile::Sounds(table) => { table.as_ref() }
+			TableFile::Music(table) => { table.as_ref() }
+			TableFile::Mission(table) => { table.as_ref() }
+			TableFile::Campaign(table) => { table.as_ref() }
+		}
+	}
+
+	pub fn pathed_mut(&mut self) -> &mut dyn FSOPathed {
+		match self {
+			TableFile::Animations(table) => { table.as_mut() }
+			TableFile::Curves(table) => { table.as_mut() }
+			TableFile::Sounds(table) => { table.as_mut() }
+			TableFile::Music(table) => { table.as_mut() }
+			TableFile::Mission(table) => { table.as_mut() }
+			TableFile::Campaign(table) => { table.as_mut() }
+		}
+	}
+}
+
+//Three-way merges the texts of a table file. The result keeps the layout of ours and, where both sides changed a value differently, its value
+pub fn merge_table_files(kind: TableKind, base: &str, ours: &str, theirs: &str) -> Result<(String, Vec<FSOConflict>), FSOParsingError> {
+	merge_table_files_resolving(kind, base, ours, theirs, FSOResolution::Ours)
+}
+
+//As merge_table_files, but with the value of the given side where both sides changed one differently
+pub fn merge_table_files_resolving(kind: TableKind, base: &str, ours: &str, theirs: &str, resolution: FSOResolution) -> Result<(String, Vec<FSOConflict>), FSOParsingError> {
+	let base = TableFile::parse_round_trip(kind, base)?;
+	let mut ours = TableFile::parse_round_trip(kind, ours)?;
+	let theirs = TableFile::parse_round_trip(kind, theirs)?;
+	let conflicts = fso_merge_resolving(base.pathed(), ours.pathed_mut(), theirs.pathed(), resolution);
+	Ok((ours.spew_round_trip(), conflicts))
+}
+
+//Spews a table file in the canonical layout, ending in a line break
+pub fn format_table_file(kind: TableKind, text: &str) -> Result<String, FSOParsingError> {
+	let mut formatted = TableFile::parse_round_trip(kind, text)?.spew_formatted(FSOFormatting::canonical());
+	formatted.push('\n');
+	Ok(formatted)
+}
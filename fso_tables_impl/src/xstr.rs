@@ -0,0 +1,41 @@
+use fso_tables::{FSOBuilder, FSOParser, FSOParsingError, FSOParsingHangingGobble, FSOTable};
+
+//A translatable string, either XSTR("text", id) or a plain string
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct XString {
+	pub text: String,
+	//-1 means the string has not been assigned an id in tstrings.tbl yet
+	pub id: Option<i32>
+}
+
+impl XString {
+	pub fn new(text: String) -> Self {
+		XString { text, id: None }
+	}
+}
+
+impl FSOTable for XString {
+	fn parse<'a, Parser: FSOParser<'a>>(state: &'a Parser, _hanging_gobble: Option<FSOParsingHangingGobble>) -> Result<(Self, Option<FSOParsingHangingGobble>), FSOParsingError> {
+		state.consume_whitespace_inline(&[]);
+		if state.consume_string("XSTR(").is_err() {
+			let (text, _) = String::parse(state, None)?;
+			return Ok((XString { text, id: None }, None));
+		}
+
+		state.consume_whitespace_inline(&[]);
+		state.consume_string("\"")?;
+		let text = state.read_until_target("\"", true).to_string();
+		let (id, _) = i32::parse(state, None)?;
+		state.consume_whitespace_inline(&[]);
+		state.consume_string(")")?;
+
+		Ok((XString { text, id: Some(id) }, None))
+	}
+
+	fn spew(&self, state: &mut impl FSOBuilder) {
+		match self.id {
+			Some(id) => { state.append(format!("XSTR(\"{}\", {})", self.text, id).as_str()); }
+			None => { self.text.spew(state); }
+		}
+	}
+}